@@ -0,0 +1,404 @@
+//! Replays conformance fixtures of the address lookup table program.
+//!
+//! The fixture format is defined by the `solana-address-lookup-table-fixture`
+//! crate. `replay` runs the instruction of a fixture through
+//! `processor::process` directly, without a bank: the accounts are laid out
+//! in the input format of the loader, and the `Clock` and `Rent` sysvars,
+//! return data and invocations of the system program are served by syscall
+//! stubs. `replay_builtin` runs it against the native builtin of the runtime
+//! instead, in a `solana-program-test` bank.
+//!
+//! `solana-program` offers no syscall to read the `SlotHashes` sysvar, so the
+//! slot hashes of a fixture only reach the program in a bank.
+
+use {
+    solana_address_lookup_table_fixture::{Fixture, FixtureAccount, FixtureEffects},
+    solana_address_lookup_table_program::processor,
+    solana_program_test::{BanksClientError, ProgramTest},
+    solana_sdk::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::{
+            deserialize, ProgramResult, BPF_ALIGN_OF_U128, MAX_PERMITTED_DATA_INCREASE,
+            NON_DUP_MARKER, SUCCESS,
+        },
+        instruction::{Instruction, InstructionError},
+        message::Message,
+        msg,
+        program_error::ProgramError,
+        program_stubs::{self, SyscallStubs},
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::Signer,
+        slot_hashes::SlotHashes,
+        system_instruction::{SystemError, SystemInstruction},
+        system_program,
+        transaction::{Transaction, TransactionError},
+    },
+    std::sync::{Arc, Mutex},
+};
+
+/// Differences between the outcome of a replayed fixture and its expectations
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayError {
+    /// The banks client failed to fetch state or process the transaction
+    #[error("Banks client error: {0}")]
+    BanksClient(Box<BanksClientError>),
+    /// The transaction failed before the instruction was executed
+    #[error("Transaction was not executed: {0}")]
+    NotExecuted(TransactionError),
+    /// The instruction returned a different result
+    #[error("Expected error {expected:?}, got {actual:?}")]
+    Result {
+        expected: Option<InstructionError>,
+        actual: Option<InstructionError>,
+    },
+    /// An account was left in a different state
+    #[error("Expected account {expected:?}, got {actual:?}")]
+    Account {
+        expected: Box<FixtureAccount>,
+        actual: Box<FixtureAccount>,
+    },
+    /// The instruction set different return data
+    #[error("Expected return data {expected:?}, got {actual:?}")]
+    ReturnData { expected: Vec<u8>, actual: Vec<u8> },
+}
+
+impl From<BanksClientError> for ReplayError {
+    fn from(err: BanksClientError) -> Self {
+        Self::BanksClient(Box::new(err))
+    }
+}
+
+/// Syscall stubs are global to the process, so instructions are executed one
+/// at a time
+static EXECUTE_LOCK: Mutex<()> = Mutex::new(());
+
+/// State the syscall stubs serve to the program
+struct Runtime {
+    program_id: Pubkey,
+    clock: Clock,
+    rent: Rent,
+    return_data: Mutex<Vec<u8>>,
+}
+
+/// Syscall stubs backed by the sysvars of a fixture
+struct Stubs(Arc<Runtime>);
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, _fields: &[&[u8]]) {}
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_system_program(
+            &self.0.program_id,
+            instruction,
+            account_infos,
+            signers_seeds,
+        )
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Clock) = self.0.clock.clone() };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = self.0.rent };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        let return_data = self.0.return_data.lock().unwrap();
+        (!return_data.is_empty()).then(|| (self.0.program_id, return_data.clone()))
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        *self.0.return_data.lock().unwrap() = data.to_vec();
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        1
+    }
+}
+
+/// Executes the transfers, allocations and assignments that the program asks
+/// the system program for
+fn invoke_system_program(
+    program_id: &Pubkey,
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    if instruction.program_id != system_program::id() {
+        msg!("Only the system program can be invoked");
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, program_id))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut accounts = Vec::with_capacity(instruction.accounts.len());
+    for meta in &instruction.accounts {
+        let account_info = account_infos
+            .iter()
+            .find(|account_info| account_info.key == &meta.pubkey)
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if meta.is_signer && !account_info.is_signer && !signers.contains(&meta.pubkey) {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        accounts.push(account_info);
+    }
+    let account = |index: usize| {
+        accounts
+            .get(index)
+            .copied()
+            .ok_or(ProgramError::NotEnoughAccountKeys)
+    };
+
+    match bincode::deserialize(&instruction.data)
+        .map_err(|_| ProgramError::InvalidInstructionData)?
+    {
+        SystemInstruction::Transfer { lamports } => {
+            let from = account(0)?;
+            let to = account(1)?;
+            if !from.data_is_empty() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            let from_lamports =
+                from.lamports()
+                    .checked_sub(lamports)
+                    .ok_or(ProgramError::Custom(
+                        SystemError::ResultWithNegativeLamports as u32,
+                    ))?;
+            let to_lamports = to
+                .lamports()
+                .checked_add(lamports)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            **from.try_borrow_mut_lamports()? = from_lamports;
+            **to.try_borrow_mut_lamports()? = to_lamports;
+        }
+        SystemInstruction::Allocate { space } => {
+            let account = account(0)?;
+            if !account.data_is_empty() || account.owner != &system_program::id() {
+                return Err(ProgramError::Custom(
+                    SystemError::AccountAlreadyInUse as u32,
+                ));
+            }
+            let space = usize::try_from(space).map_err(|_| ProgramError::InvalidArgument)?;
+            account.realloc(space, true)?;
+        }
+        SystemInstruction::Assign { owner } => {
+            let account = account(0)?;
+            if account.owner != &owner {
+                account.assign(&owner);
+            }
+        }
+        _ => {
+            msg!("Only transfers, allocations and assignments are supported");
+            return Err(ProgramError::InvalidInstructionData);
+        }
+    }
+    Ok(())
+}
+
+/// Lays out the accounts and instruction of `fixture` in the input format of
+/// the loader, as read by `entrypoint::deserialize`. The buffer is made of
+/// words so that it is aligned like the loader's.
+fn serialize_input(fixture: &Fixture) -> Vec<u64> {
+    let instruction = &fixture.instruction;
+    let mut input = Vec::new();
+    input.extend_from_slice(&(instruction.accounts.len() as u64).to_le_bytes());
+    for (index, meta) in instruction.accounts.iter().enumerate() {
+        if let Some(original) = instruction.accounts[..index]
+            .iter()
+            .position(|other| other.pubkey == meta.pubkey)
+        {
+            input.push(original as u8);
+            input.extend_from_slice(&[0; 7]);
+            continue;
+        }
+        let account = fixture
+            .accounts
+            .iter()
+            .find(|account| account.address == meta.pubkey)
+            .cloned()
+            .unwrap_or_else(|| FixtureAccount::new(meta.pubkey, None));
+        input.extend_from_slice(&[
+            NON_DUP_MARKER,
+            meta.is_signer.into(),
+            meta.is_writable.into(),
+            account.executable.into(),
+        ]);
+        // Original data length, filled in by `deserialize`
+        input.extend_from_slice(&[0; 4]);
+        input.extend_from_slice(meta.pubkey.as_ref());
+        input.extend_from_slice(account.owner.as_ref());
+        input.extend_from_slice(&account.lamports.to_le_bytes());
+        input.extend_from_slice(&(account.data.len() as u64).to_le_bytes());
+        input.extend_from_slice(&account.data);
+        input.resize(input.len().saturating_add(MAX_PERMITTED_DATA_INCREASE), 0);
+        input.resize(input.len().next_multiple_of(BPF_ALIGN_OF_U128), 0);
+        // Rent epoch
+        input.extend_from_slice(&u64::MAX.to_le_bytes());
+    }
+    input.extend_from_slice(&(instruction.data.len() as u64).to_le_bytes());
+    input.extend_from_slice(&instruction.data);
+    input.extend_from_slice(instruction.program_id.as_ref());
+
+    let mut words = vec![0u64; input.len().div_ceil(8)];
+    bytemuck::cast_slice_mut::<u64, u8>(&mut words)[..input.len()].copy_from_slice(&input);
+    words
+}
+
+/// Runs the instruction of `fixture` through `processor::process` and returns
+/// its outcome
+pub fn execute(fixture: &Fixture) -> FixtureEffects {
+    let _lock = EXECUTE_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+    let runtime = Arc::new(Runtime {
+        program_id: fixture.instruction.program_id,
+        clock: fixture.sysvars.clock.clone(),
+        rent: fixture.sysvars.rent,
+        return_data: Mutex::new(Vec::new()),
+    });
+    let previous_stubs = program_stubs::set_syscall_stubs(Box::new(Stubs(runtime.clone())));
+
+    let mut input = serialize_input(fixture);
+    // SAFETY: `input` is laid out as `deserialize` expects, and the account
+    // infos borrowing it are dropped before it is
+    let (result, accounts) = unsafe {
+        let (program_id, account_infos, data) = deserialize(input.as_mut_ptr() as *mut u8);
+        let result = processor::process(program_id, &account_infos, data);
+        let accounts = fixture
+            .accounts
+            .iter()
+            .map(|account| {
+                match account_infos
+                    .iter()
+                    .find(|account_info| account_info.key == &account.address)
+                {
+                    Some(account_info) => FixtureAccount {
+                        address: account.address,
+                        lamports: account_info.lamports(),
+                        data: account_info.data.borrow().to_vec(),
+                        owner: *account_info.owner,
+                        executable: account_info.executable,
+                    },
+                    None => account.clone(),
+                }
+            })
+            .collect();
+        (result, accounts)
+    };
+
+    program_stubs::set_syscall_stubs(previous_stubs);
+    let return_data = runtime.return_data.lock().unwrap().clone();
+    FixtureEffects {
+        error: result
+            .err()
+            .map(|err| InstructionError::from(u64::from(err))),
+        accounts,
+        return_data,
+    }
+}
+
+/// Compares the outcome of a replay with the expectations of `fixture`
+fn check_effects(fixture: &Fixture, effects: FixtureEffects) -> Result<(), ReplayError> {
+    let expected = &fixture.expected;
+    if effects.error != expected.error {
+        return Err(ReplayError::Result {
+            expected: expected.error.clone(),
+            actual: effects.error,
+        });
+    }
+    for expected_account in &expected.accounts {
+        let actual = effects
+            .accounts
+            .iter()
+            .find(|account| account.address == expected_account.address)
+            .cloned()
+            .unwrap_or_else(|| FixtureAccount::new(expected_account.address, None));
+        if &actual != expected_account {
+            return Err(ReplayError::Account {
+                expected: Box::new(expected_account.clone()),
+                actual: Box::new(actual),
+            });
+        }
+    }
+    if effects.return_data != expected.return_data {
+        return Err(ReplayError::ReturnData {
+            expected: expected.return_data.clone(),
+            actual: effects.return_data,
+        });
+    }
+    Ok(())
+}
+
+/// Replays `fixture` against `processor::process`
+pub fn replay(fixture: &Fixture) -> Result<(), ReplayError> {
+    check_effects(fixture, execute(fixture))
+}
+
+/// Replays `fixture` against the native builtin of the runtime
+pub async fn replay_builtin(fixture: &Fixture) -> Result<(), ReplayError> {
+    let mut context = ProgramTest::default().start_with_context().await;
+    context.set_sysvar(&fixture.sysvars.clock);
+    context.set_sysvar(&fixture.sysvars.rent);
+    let slot_hashes: Vec<_> = fixture
+        .sysvars
+        .slot_hashes
+        .iter()
+        .map(|slot_hash| (slot_hash.slot, slot_hash.hash))
+        .collect();
+    context.set_sysvar(&SlotHashes::new(&slot_hashes));
+    // Programs referenced by the instruction are provided by the runtime
+    for account in &fixture.accounts {
+        if account.exists() && !account.executable {
+            context.set_account(&account.address, &account.into());
+        }
+    }
+
+    // Signatures are not verified when processing the transaction, so only
+    // the fee payer signs
+    let message = Message::new(
+        &[Instruction::from(&fixture.instruction)],
+        Some(&context.payer.pubkey()),
+    );
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.partial_sign(&[&context.payer], context.last_blockhash);
+
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await?;
+    let error = match result.result {
+        Ok(()) => None,
+        Err(TransactionError::InstructionError(0, err)) => Some(err),
+        Err(err) => return Err(ReplayError::NotExecuted(err)),
+    };
+    let mut accounts = Vec::with_capacity(fixture.expected.accounts.len());
+    for expected in &fixture.expected.accounts {
+        let account = context.banks_client.get_account(expected.address).await?;
+        accounts.push(FixtureAccount::new(expected.address, account));
+    }
+    let return_data = result
+        .metadata
+        .and_then(|metadata| metadata.return_data)
+        .map(|return_data| return_data.data)
+        .unwrap_or_default();
+
+    check_effects(
+        fixture,
+        FixtureEffects {
+            error,
+            accounts,
+            return_data,
+        },
+    )
+}
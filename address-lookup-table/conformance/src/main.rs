@@ -0,0 +1,67 @@
+//! Replays a directory of conformance fixtures.
+//!
+//! ```sh
+//! cargo run -p solana-address-lookup-table-conformance -- [--builtin] <DIR>
+//! ```
+//!
+//! Fixtures are replayed against `processor::process`, without a bank.
+//! `--builtin` replays them against the native builtin of the runtime
+//! instead.
+
+use {
+    solana_address_lookup_table_conformance::{replay, replay_builtin},
+    solana_address_lookup_table_fixture::Fixture,
+    solana_program_test::tokio,
+    std::{env, path::PathBuf, process::exit},
+};
+
+const USAGE: &str = "usage: solana-address-lookup-table-conformance [--builtin] <DIR>";
+
+#[tokio::main]
+async fn main() {
+    let mut builtin = false;
+    let mut dir = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--builtin" => builtin = true,
+            _ if dir.is_none() && !arg.starts_with('-') => dir = Some(PathBuf::from(arg)),
+            _ => {
+                eprintln!("{USAGE}");
+                exit(2);
+            }
+        }
+    }
+    let Some(dir) = dir else {
+        eprintln!("{USAGE}");
+        exit(2);
+    };
+
+    let fixtures = Fixture::load_dir(&dir).unwrap_or_else(|err| {
+        eprintln!("Failed to load fixtures from {}: {err}", dir.display());
+        exit(1);
+    });
+
+    let mut failed = 0usize;
+    for (path, fixture) in &fixtures {
+        let result = if builtin {
+            replay_builtin(fixture).await
+        } else {
+            replay(fixture)
+        };
+        match result {
+            Ok(()) => println!("ok      {}", path.display()),
+            Err(err) => {
+                failed = failed.saturating_add(1);
+                println!("FAILED  {}: {err}", path.display());
+            }
+        }
+    }
+
+    println!(
+        "{} passed; {failed} failed",
+        fixtures.len().saturating_sub(failed)
+    );
+    if failed > 0 {
+        exit(1);
+    }
+}
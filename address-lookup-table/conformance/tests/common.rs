@@ -0,0 +1,123 @@
+#![allow(dead_code)]
+
+use {
+    solana_address_lookup_table_conformance::execute,
+    solana_address_lookup_table_fixture::{
+        Fixture, FixtureAccount, FixtureAccountMeta, FixtureEffects, FixtureInstruction,
+        FixtureSysvars,
+    },
+    solana_sdk::{
+        address_lookup_table::{
+            program::id,
+            state::{AddressLookupTable, LookupTableMeta},
+        },
+        clock::{Clock, Slot},
+        instruction::Instruction,
+        native_loader,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+    },
+    std::borrow::Cow,
+};
+
+pub const CURRENT_SLOT: Slot = 10;
+
+pub fn new_table_account(
+    address: Pubkey,
+    authority: Option<Pubkey>,
+    num_addresses: usize,
+) -> FixtureAccount {
+    let mut addresses = Vec::with_capacity(num_addresses);
+    addresses.resize_with(num_addresses, Pubkey::new_unique);
+    let data = AddressLookupTable {
+        meta: LookupTableMeta {
+            authority,
+            ..LookupTableMeta::default()
+        },
+        addresses: Cow::Owned(addresses),
+    }
+    .serialize_for_tests()
+    .unwrap();
+    FixtureAccount {
+        address,
+        lamports: Rent::default().minimum_balance(data.len()),
+        data,
+        owner: id(),
+        executable: false,
+    }
+}
+
+pub fn new_system_account(address: Pubkey, lamports: u64) -> FixtureAccount {
+    FixtureAccount {
+        address,
+        lamports,
+        data: vec![],
+        owner: system_program::id(),
+        executable: false,
+    }
+}
+
+pub fn system_program_account() -> FixtureAccount {
+    FixtureAccount {
+        address: system_program::id(),
+        lamports: 1,
+        data: b"solana_system_program".to_vec(),
+        owner: native_loader::id(),
+        executable: true,
+    }
+}
+
+/// Returns a fixture for `instruction` running against `accounts`, with the
+/// outcome of `processor::process` as its expectations. Referenced accounts
+/// missing from `accounts` do not exist.
+pub fn new_fixture(name: &str, instruction: Instruction, accounts: &[FixtureAccount]) -> Fixture {
+    let mut fixture_accounts: Vec<FixtureAccount> = Vec::new();
+    for meta in &instruction.accounts {
+        if fixture_accounts
+            .iter()
+            .all(|account| account.address != meta.pubkey)
+        {
+            fixture_accounts.push(
+                accounts
+                    .iter()
+                    .find(|account| account.address == meta.pubkey)
+                    .cloned()
+                    .unwrap_or_else(|| FixtureAccount::new(meta.pubkey, None)),
+            );
+        }
+    }
+
+    let mut fixture = Fixture {
+        name: name.to_string(),
+        sysvars: FixtureSysvars {
+            clock: Clock {
+                slot: CURRENT_SLOT,
+                ..Clock::default()
+            },
+            rent: Rent::default(),
+            slot_hashes: vec![],
+        },
+        instruction: FixtureInstruction {
+            program_id: instruction.program_id,
+            accounts: instruction
+                .accounts
+                .iter()
+                .map(|meta| FixtureAccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: instruction.data,
+        },
+        accounts: fixture_accounts.clone(),
+        expected: FixtureEffects {
+            error: None,
+            accounts: fixture_accounts,
+            return_data: vec![],
+        },
+    };
+    fixture.expected = execute(&fixture);
+    fixture
+}
@@ -0,0 +1,115 @@
+use {
+    assert_matches::assert_matches,
+    common::{new_fixture, new_system_account, new_table_account, system_program_account},
+    solana_address_lookup_table_conformance::{replay, ReplayError},
+    solana_address_lookup_table_fixture::Fixture,
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{extend_lookup_table, freeze_lookup_table},
+    },
+    solana_sdk::{instruction::InstructionError, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey},
+};
+
+mod common;
+
+fn extend_fixture(payer_is_authority: bool) -> Fixture {
+    let lookup_table_address = Pubkey::new_unique();
+    let authority_address = Pubkey::new_unique();
+    let payer_address = if payer_is_authority {
+        authority_address
+    } else {
+        Pubkey::new_unique()
+    };
+    new_fixture(
+        "extend",
+        extend_lookup_table(
+            lookup_table_address,
+            authority_address,
+            Some(payer_address),
+            vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        ),
+        &[
+            new_table_account(lookup_table_address, Some(authority_address), 10),
+            new_system_account(payer_address, LAMPORTS_PER_SOL),
+            system_program_account(),
+        ],
+    )
+}
+
+fn freeze_with_wrong_authority_fixture() -> Fixture {
+    let lookup_table_address = Pubkey::new_unique();
+    new_fixture(
+        "freeze",
+        freeze_lookup_table(lookup_table_address, Pubkey::new_unique()),
+        &[new_table_account(
+            lookup_table_address,
+            Some(Pubkey::new_unique()),
+            10,
+        )],
+    )
+}
+
+#[test]
+fn test_replay_extend_lookup_table() {
+    let fixture = extend_fixture(false);
+    let [table_before, _, payer_before, _] = fixture.accounts.as_slice() else {
+        panic!("unexpected accounts");
+    };
+    let [table_after, _, payer_after, _] = fixture.expected.accounts.as_slice() else {
+        panic!("unexpected accounts");
+    };
+
+    assert_eq!(fixture.expected.error, None);
+    assert_eq!(table_after.data.len(), table_before.data.len() + 64);
+    assert_eq!(
+        payer_before.lamports - payer_after.lamports,
+        table_after.lamports - table_before.lamports
+    );
+    assert!(payer_after.lamports < payer_before.lamports);
+    assert!(!fixture.expected.return_data.is_empty());
+    assert_matches!(replay(&fixture), Ok(()));
+}
+
+#[test]
+fn test_replay_with_duplicate_accounts() {
+    let fixture = extend_fixture(true);
+    let [_, authority_before, _] = fixture.accounts.as_slice() else {
+        panic!("unexpected accounts");
+    };
+    let [_, authority_after, _] = fixture.expected.accounts.as_slice() else {
+        panic!("unexpected accounts");
+    };
+
+    assert_eq!(fixture.expected.error, None);
+    assert!(authority_after.lamports < authority_before.lamports);
+    assert_matches!(replay(&fixture), Ok(()));
+}
+
+#[test]
+fn test_replay_error() {
+    let fixture = freeze_with_wrong_authority_fixture();
+
+    assert_eq!(
+        fixture.expected.error,
+        Some(InstructionError::Custom(
+            AddressLookupError::IncorrectAuthority as u32
+        ))
+    );
+    assert_eq!(fixture.expected.accounts, fixture.accounts);
+    assert_matches!(replay(&fixture), Ok(()));
+}
+
+#[test]
+fn test_replay_mismatch() {
+    let mut fixture = extend_fixture(false);
+    fixture.expected.accounts[0].data.pop();
+    assert_matches!(replay(&fixture), Err(ReplayError::Account { .. }));
+
+    let mut fixture = freeze_with_wrong_authority_fixture();
+    fixture.expected.error = None;
+    assert_matches!(replay(&fixture), Err(ReplayError::Result { .. }));
+
+    let mut fixture = extend_fixture(false);
+    fixture.expected.return_data.clear();
+    assert_matches!(replay(&fixture), Err(ReplayError::ReturnData { .. }));
+}
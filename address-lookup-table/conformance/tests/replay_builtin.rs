@@ -0,0 +1,33 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{new_fixture, new_table_account, CURRENT_SLOT},
+    solana_address_lookup_table_conformance::replay_builtin,
+    solana_address_lookup_table_program::instruction::deactivate_lookup_table,
+    solana_program_test::tokio,
+    solana_sdk::{address_lookup_table::state::AddressLookupTable, pubkey::Pubkey},
+};
+
+mod common;
+
+#[tokio::test]
+async fn test_replay_against_builtin() {
+    let lookup_table_address = Pubkey::new_unique();
+    let authority_address = Pubkey::new_unique();
+    let fixture = new_fixture(
+        "deactivate",
+        deactivate_lookup_table(lookup_table_address, authority_address),
+        &[new_table_account(
+            lookup_table_address,
+            Some(authority_address),
+            10,
+        )],
+    );
+    let table = AddressLookupTable::deserialize(&fixture.expected.accounts[0].data).unwrap();
+    assert_eq!(table.meta.deactivation_slot, CURRENT_SLOT);
+
+    // The builtin does not set return data, so only replay a fixture that
+    // does not expect any
+    assert_matches!(replay_builtin(&fixture).await, Ok(()));
+}
@@ -0,0 +1,15 @@
+//! Program entrypoint
+
+use {
+    crate::processor::process,
+    solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey},
+};
+
+solana_program::entrypoint!(process_instruction);
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    process(program_id, accounts, instruction_data)
+}
@@ -0,0 +1,127 @@
+//! Test program that manages an address lookup table through cross-program
+//! invocations. The table's authority is a program-derived address of this
+//! program, so every invocation is signed with its seeds.
+
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
+pub mod processor;
+
+use {
+    serde::{Deserialize, Serialize},
+    solana_address_lookup_table_program::instruction::derive_lookup_table_address,
+    solana_program::{
+        clock::Slot,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+};
+
+solana_program::declare_id!("A1tCpiTest111111111111111111111111111111111");
+
+/// Seed of the program-derived authority of every table the program manages
+pub const AUTHORITY_SEED: &[u8] = b"authority";
+
+/// Instructions supported by the test program. Each one forwards to the
+/// address lookup table instruction of the same name.
+///
+/// Every instruction takes the lookup table account, the program's authority
+/// account and the address lookup table program, followed by:
+///   - `Create`: payer and system program
+///   - `Extend`: payer and system program
+///   - `Close`: recipient
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum TestInstruction {
+    Create {
+        recent_slot: Slot,
+    },
+    Freeze,
+    Extend {
+        new_addresses: Vec<Pubkey>,
+    },
+    Deactivate,
+    Close,
+    /// Resolves addresses of the table and returns them through the return
+    /// data of this program
+    ResolveAddresses {
+        indexes: Vec<u8>,
+    },
+}
+
+/// Derives the program's authority address
+pub fn find_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUTHORITY_SEED], &id())
+}
+
+fn new_instruction(data: &TestInstruction, lookup_table_address: Pubkey) -> Instruction {
+    let (authority_address, _) = find_authority_address();
+    Instruction::new_with_bincode(
+        id(),
+        data,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, false),
+            AccountMeta::new_readonly(solana_address_lookup_table_program::id(), false),
+        ],
+    )
+}
+
+/// Constructs an instruction that creates a lookup table owned by the
+/// program's authority and returns the table's address.
+pub fn create(payer_address: Pubkey, recent_slot: Slot) -> (Instruction, Pubkey) {
+    let (authority_address, _) = find_authority_address();
+    let (lookup_table_address, _) = derive_lookup_table_address(&authority_address, recent_slot);
+    let mut instruction = new_instruction(
+        &TestInstruction::Create { recent_slot },
+        lookup_table_address,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(payer_address, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    (instruction, lookup_table_address)
+}
+
+/// Constructs an instruction that freezes a lookup table
+pub fn freeze(lookup_table_address: Pubkey) -> Instruction {
+    new_instruction(&TestInstruction::Freeze, lookup_table_address)
+}
+
+/// Constructs an instruction that extends a lookup table
+pub fn extend(
+    lookup_table_address: Pubkey,
+    payer_address: Pubkey,
+    new_addresses: Vec<Pubkey>,
+) -> Instruction {
+    let mut instruction = new_instruction(
+        &TestInstruction::Extend { new_addresses },
+        lookup_table_address,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(payer_address, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    instruction
+}
+
+/// Constructs an instruction that deactivates a lookup table
+pub fn deactivate(lookup_table_address: Pubkey) -> Instruction {
+    new_instruction(&TestInstruction::Deactivate, lookup_table_address)
+}
+
+/// Constructs an instruction that closes a lookup table
+pub fn close(lookup_table_address: Pubkey, recipient_address: Pubkey) -> Instruction {
+    let mut instruction = new_instruction(&TestInstruction::Close, lookup_table_address);
+    instruction
+        .accounts
+        .push(AccountMeta::new(recipient_address, false));
+    instruction
+}
+
+/// Constructs an instruction that resolves addresses of a lookup table
+pub fn resolve_addresses(lookup_table_address: Pubkey, indexes: Vec<u8>) -> Instruction {
+    new_instruction(
+        &TestInstruction::ResolveAddresses { indexes },
+        lookup_table_address,
+    )
+}
@@ -0,0 +1,75 @@
+//! Program state processor
+
+use {
+    crate::{TestInstruction, AUTHORITY_SEED},
+    solana_address_lookup_table_program::cpi,
+    solana_program::{
+        account_info::{next_account_info, AccountInfo},
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+/// Processes a `TestInstruction`
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> ProgramResult {
+    let instruction: TestInstruction =
+        bincode::deserialize(input).map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let _address_lookup_table_program_info = next_account_info(account_info_iter)?;
+
+    let (authority_address, bump_seed) =
+        Pubkey::find_program_address(&[AUTHORITY_SEED], program_id);
+    if *authority_info.key != authority_address {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let signers_seeds: &[&[&[u8]]] = &[&[AUTHORITY_SEED, &[bump_seed]]];
+
+    match instruction {
+        TestInstruction::Create { recent_slot } => {
+            let payer_info = next_account_info(account_info_iter)?;
+            let system_program_info = next_account_info(account_info_iter)?;
+            cpi::create(
+                lookup_table_info,
+                authority_info,
+                payer_info,
+                system_program_info,
+                recent_slot,
+                signers_seeds,
+            )
+        }
+        TestInstruction::Freeze => cpi::freeze(lookup_table_info, authority_info, signers_seeds),
+        TestInstruction::Extend { new_addresses } => {
+            let payer_info = next_account_info(account_info_iter)?;
+            let system_program_info = next_account_info(account_info_iter)?;
+            cpi::extend(
+                lookup_table_info,
+                authority_info,
+                Some((payer_info, system_program_info)),
+                new_addresses,
+                signers_seeds,
+            )
+        }
+        TestInstruction::Deactivate => {
+            cpi::deactivate(lookup_table_info, authority_info, signers_seeds)
+        }
+        TestInstruction::Close => {
+            let recipient_info = next_account_info(account_info_iter)?;
+            cpi::close(
+                lookup_table_info,
+                authority_info,
+                recipient_info,
+                signers_seeds,
+            )
+        }
+        TestInstruction::ResolveAddresses { indexes } => {
+            let addresses = cpi::resolve_addresses(lookup_table_info, indexes)?;
+            set_return_data(bytemuck::cast_slice(&addresses));
+            Ok(())
+        }
+    }
+}
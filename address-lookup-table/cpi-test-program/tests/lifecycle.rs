@@ -0,0 +1,234 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    solana_address_lookup_table_cpi_test_program::{
+        close, create, deactivate, extend, find_authority_address, freeze, id, processor::process,
+        resolve_addresses,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::AccountSharedData,
+        address_lookup_table::state::AddressLookupTable,
+        clock::{Clock, Slot},
+        hash::Hash,
+        instruction::Instruction,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        signature::Signer,
+        slot_hashes::SlotHashes,
+        system_program,
+        transaction::{Transaction, TransactionError},
+    },
+};
+
+async fn setup_test_context() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "solana_address_lookup_table_program",
+        solana_address_lookup_table_program::id(),
+        processor!(solana_address_lookup_table_program::processor::process),
+    );
+    program_test.add_program(
+        "solana_address_lookup_table_cpi_test_program",
+        id(),
+        processor!(process),
+    );
+    program_test.start_with_context().await
+}
+
+fn overwrite_slot_hashes_with_slots(context: &ProgramTestContext, slots: &[Slot]) {
+    let mut slot_hashes = SlotHashes::default();
+    for slot in slots {
+        slot_hashes.add(*slot, Hash::new_unique());
+    }
+    context.set_sysvar(&slot_hashes);
+}
+
+async fn process_ix(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+) -> Result<(), TransactionError> {
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = recent_blockhash;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err| err.unwrap())
+}
+
+async fn get_lookup_table(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> Option<AddressLookupTable<'static>> {
+    let account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()?;
+    let lookup_table = AddressLookupTable::deserialize(&account.data).unwrap();
+    Some(AddressLookupTable {
+        meta: lookup_table.meta,
+        addresses: lookup_table.addresses.into_owned().into(),
+    })
+}
+
+async fn create_lookup_table(context: &mut ProgramTestContext, recent_slot: Slot) -> Pubkey {
+    let (ix, lookup_table_address) = create(context.payer.pubkey(), recent_slot);
+    assert_matches!(process_ix(context, ix).await, Ok(()));
+    lookup_table_address
+}
+
+#[tokio::test]
+async fn test_lookup_table_lifecycle_via_cpi() {
+    let mut context = setup_test_context().await;
+    let (authority_address, _) = find_authority_address();
+
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[recent_slot]);
+    let lookup_table_address = create_lookup_table(&mut context, recent_slot).await;
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address)
+        .await
+        .unwrap();
+    assert_eq!(lookup_table.meta.authority, Some(authority_address));
+    assert!(lookup_table.addresses.is_empty());
+
+    let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let ix = extend(
+        lookup_table_address,
+        context.payer.pubkey(),
+        new_addresses.clone(),
+    );
+    assert_matches!(process_ix(&mut context, ix).await, Ok(()));
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address)
+        .await
+        .unwrap();
+    assert_eq!(lookup_table.addresses.as_ref(), new_addresses.as_slice());
+
+    let ix = deactivate(lookup_table_address);
+    assert_matches!(process_ix(&mut context, ix).await, Ok(()));
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address)
+        .await
+        .unwrap();
+    let deactivation_slot = lookup_table.meta.deactivation_slot;
+    assert_ne!(deactivation_slot, Slot::MAX);
+
+    // Move past the deactivation slot and drop it from the slot hashes so
+    // that the table is fully deactivated.
+    context.set_sysvar(&Clock {
+        slot: deactivation_slot.saturating_add(1),
+        ..Clock::default()
+    });
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let recipient = Pubkey::new_unique();
+    context.set_account(
+        &recipient,
+        &AccountSharedData::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    let table_lamports = context
+        .banks_client
+        .get_balance(lookup_table_address)
+        .await
+        .unwrap();
+    let ix = close(lookup_table_address, recipient);
+    assert_matches!(process_ix(&mut context, ix).await, Ok(()));
+
+    assert!(get_lookup_table(&mut context, lookup_table_address)
+        .await
+        .is_none());
+    assert_eq!(
+        context.banks_client.get_balance(recipient).await.unwrap(),
+        LAMPORTS_PER_SOL.saturating_add(table_lamports)
+    );
+}
+
+#[tokio::test]
+async fn test_freeze_lookup_table_via_cpi() {
+    let mut context = setup_test_context().await;
+
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[recent_slot]);
+    let lookup_table_address = create_lookup_table(&mut context, recent_slot).await;
+
+    let ix = extend(
+        lookup_table_address,
+        context.payer.pubkey(),
+        vec![Pubkey::new_unique()],
+    );
+    assert_matches!(process_ix(&mut context, ix).await, Ok(()));
+
+    let ix = freeze(lookup_table_address);
+    assert_matches!(process_ix(&mut context, ix).await, Ok(()));
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address)
+        .await
+        .unwrap();
+    assert_eq!(lookup_table.meta.authority, None);
+
+    // The program no longer controls the table
+    let ix = extend(
+        lookup_table_address,
+        context.payer.pubkey(),
+        vec![Pubkey::new_unique()],
+    );
+    assert_matches!(
+        process_ix(&mut context, ix).await,
+        Err(TransactionError::InstructionError(0, _))
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_addresses_via_cpi() {
+    let mut context = setup_test_context().await;
+
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[recent_slot]);
+    let lookup_table_address = create_lookup_table(&mut context, recent_slot).await;
+
+    let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let ix = extend(
+        lookup_table_address,
+        context.payer.pubkey(),
+        new_addresses.clone(),
+    );
+    assert_matches!(process_ix(&mut context, ix).await, Ok(()));
+
+    // Addresses become resolvable in the slot after they were appended
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address)
+        .await
+        .unwrap();
+    context.set_sysvar(&Clock {
+        slot: lookup_table.meta.last_extended_slot.saturating_add(1),
+        ..Clock::default()
+    });
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[resolve_addresses(lookup_table_address, vec![1, 0])],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let simulation = context
+        .banks_client
+        .simulate_transaction(transaction)
+        .await
+        .unwrap();
+    assert_matches!(simulation.result, Some(Ok(())));
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, id());
+    assert_eq!(
+        bytemuck::cast_slice::<u8, Pubkey>(&return_data.data),
+        &[new_addresses[1], new_addresses[0]]
+    );
+}
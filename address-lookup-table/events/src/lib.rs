@@ -0,0 +1,235 @@
+//! Decodes the events logged by the address lookup table program from the
+//! log messages of a transaction.
+//!
+//! The program logs each event with `sol_log_data`, which the runtime records
+//! as a `Program data:` line. Other programs log data the same way, so the
+//! decoder follows the `invoke`, `success` and `failed` lines of the logs and
+//! only decodes data logged while the lookup table program is executing,
+//! including when it is invoked through CPI.
+
+pub use solana_address_lookup_table_program::event::{
+    EventKind, LookupTableEvent, TableStatus, EVENT_LEN, EVENT_VERSION,
+};
+use {
+    base64::{engine::general_purpose::STANDARD, Engine},
+    solana_program::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+const LOG_TRUNCATED: &str = "Log truncated";
+
+/// Errors that may be returned while decoding events
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    /// Data logged by the program is not valid base64
+    #[error("Data logged by the program is not valid base64: {0}")]
+    InvalidBase64(#[from] base64::DecodeError),
+    /// Data logged by the program is not an event of a known version
+    #[error("Data logged by the program is not a valid event: {0}")]
+    InvalidEvent(#[from] ProgramError),
+    /// The runtime truncated the logs, so some events may be missing
+    #[error("Log messages were truncated")]
+    LogTruncated,
+}
+
+/// Returns the events logged by the lookup table program deployed at
+/// `program_id`, in the order they were logged
+pub fn decode_events(
+    program_id: &Pubkey,
+    log_messages: &[String],
+) -> Result<Vec<LookupTableEvent>, DecodeError> {
+    let program_id = program_id.to_string();
+    let mut invoke_stack = Vec::new();
+    let mut events = Vec::new();
+
+    for log_message in log_messages {
+        if let Some(data) = log_message.strip_prefix(PROGRAM_DATA_PREFIX) {
+            if invoke_stack.last() != Some(&program_id.as_str()) {
+                continue;
+            }
+            for field in data.split(' ') {
+                events.push(LookupTableEvent::unpack(&STANDARD.decode(field)?)?);
+            }
+        } else if log_message == LOG_TRUNCATED {
+            return Err(DecodeError::LogTruncated);
+        } else if let Some((invoked_program_id, status)) = log_message
+            .strip_prefix("Program ")
+            .and_then(|message| message.split_once(' '))
+        {
+            if status.starts_with("invoke [") {
+                invoke_stack.push(invoked_program_id);
+            } else if status == "success" || status.starts_with("failed") {
+                invoke_stack.pop();
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_program::clock::Slot};
+
+    fn new_event(kind: EventKind) -> LookupTableEvent {
+        LookupTableEvent {
+            kind,
+            lookup_table: Pubkey::new_unique(),
+            authority: Some(Pubkey::new_unique()),
+            slot: 42,
+            old_len: 0,
+            new_len: 2,
+            status: TableStatus::Active,
+            account: None,
+        }
+    }
+
+    fn data_log(data: &[u8]) -> String {
+        format!("{}{}", PROGRAM_DATA_PREFIX, STANDARD.encode(data))
+    }
+
+    fn invoke_logs(program_id: &Pubkey, depth: usize, inner_logs: Vec<String>) -> Vec<String> {
+        let mut logs = vec![format!("Program {} invoke [{}]", program_id, depth)];
+        logs.extend(inner_logs);
+        logs.push(format!(
+            "Program {} consumed 1000 of 200000 compute units",
+            program_id
+        ));
+        logs.push(format!("Program {} success", program_id));
+        logs
+    }
+
+    #[test]
+    fn test_decode_events() {
+        let program_id = Pubkey::new_unique();
+        let create = new_event(EventKind::Create);
+        let deactivate = LookupTableEvent {
+            kind: EventKind::Deactivate,
+            slot: Slot::MAX - 1,
+            status: TableStatus::Deactivated,
+            ..create
+        };
+
+        let mut logs = invoke_logs(
+            &program_id,
+            1,
+            vec![
+                "Program log: Instruction: CreateLookupTable".to_string(),
+                data_log(&create.pack()),
+            ],
+        );
+        logs.extend(invoke_logs(
+            &program_id,
+            1,
+            vec![data_log(&deactivate.pack())],
+        ));
+
+        assert_eq!(
+            decode_events(&program_id, &logs),
+            Ok(vec![create, deactivate])
+        );
+    }
+
+    #[test]
+    fn test_decode_account_events() {
+        let program_id = Pubkey::new_unique();
+        let propose = LookupTableEvent {
+            kind: EventKind::ProposeAuthority,
+            account: Some(Pubkey::new_unique()),
+            ..new_event(EventKind::Create)
+        };
+        let multisig = LookupTableEvent::new_multisig(Pubkey::new_unique(), 42);
+
+        let mut logs = invoke_logs(&program_id, 1, vec![data_log(&propose.pack())]);
+        logs.extend(invoke_logs(
+            &program_id,
+            1,
+            vec![data_log(&multisig.pack())],
+        ));
+
+        assert_eq!(
+            decode_events(&program_id, &logs),
+            Ok(vec![propose, multisig])
+        );
+    }
+
+    #[test]
+    fn test_decode_events_ignores_other_programs() {
+        let program_id = Pubkey::new_unique();
+        let caller_program_id = Pubkey::new_unique();
+        let event = new_event(EventKind::Extend);
+
+        // Data logged by the caller before and after the CPI is ignored, as
+        // is data logged by programs that the lookup table program invokes
+        let mut inner_logs = vec![data_log(&event.pack())];
+        inner_logs.extend(invoke_logs(
+            &Pubkey::new_unique(),
+            3,
+            vec![data_log(b"transfer")],
+        ));
+        let mut caller_logs = vec![data_log(b"before")];
+        caller_logs.extend(invoke_logs(&program_id, 2, inner_logs));
+        caller_logs.push(data_log(b"after"));
+        let logs = invoke_logs(&caller_program_id, 1, caller_logs);
+
+        assert_eq!(decode_events(&program_id, &logs), Ok(vec![event]));
+        assert_eq!(decode_events(&Pubkey::new_unique(), &logs), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_decode_events_after_failed_invocation() {
+        let program_id = Pubkey::new_unique();
+        let other_program_id = Pubkey::new_unique();
+        let event = new_event(EventKind::Freeze);
+
+        let mut logs = vec![
+            format!("Program {} invoke [1]", other_program_id),
+            format!(
+                "Program {} failed: custom program error: 0x1",
+                other_program_id
+            ),
+        ];
+        logs.extend(invoke_logs(&program_id, 1, vec![data_log(&event.pack())]));
+
+        assert_eq!(decode_events(&program_id, &logs), Ok(vec![event]));
+    }
+
+    #[test]
+    fn test_decode_events_with_invalid_data() {
+        let program_id = Pubkey::new_unique();
+
+        let logs = invoke_logs(
+            &program_id,
+            1,
+            vec![format!("{}not base64!", PROGRAM_DATA_PREFIX)],
+        );
+        assert_matches::assert_matches!(
+            decode_events(&program_id, &logs),
+            Err(DecodeError::InvalidBase64(_))
+        );
+
+        let mut data = new_event(EventKind::Close).pack();
+        data[0] = EVENT_VERSION + 1;
+        let logs = invoke_logs(&program_id, 1, vec![data_log(&data)]);
+        assert_eq!(
+            decode_events(&program_id, &logs),
+            Err(DecodeError::InvalidEvent(ProgramError::InvalidAccountData))
+        );
+    }
+
+    #[test]
+    fn test_decode_events_with_truncated_logs() {
+        let program_id = Pubkey::new_unique();
+        let mut logs = invoke_logs(
+            &program_id,
+            1,
+            vec![data_log(&new_event(EventKind::Create).pack())],
+        );
+        logs.push(LOG_TRUNCATED.to_string());
+
+        assert_eq!(
+            decode_events(&program_id, &logs),
+            Err(DecodeError::LogTruncated)
+        );
+    }
+}
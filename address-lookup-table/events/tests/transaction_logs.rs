@@ -0,0 +1,325 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    solana_address_lookup_table_cpi_test_program::{self as cpi_test_program, processor::process},
+    solana_address_lookup_table_events::{decode_events, EventKind, LookupTableEvent, TableStatus},
+    solana_address_lookup_table_program::instruction::{
+        cancel_authority_proposal, close_lookup_table, create_lookup_table_signed,
+        deactivate_lookup_table, derive_lookup_table_metadata_address, extend_lookup_table,
+        extend_lookup_table_unique, propose_authority, remove_extender, set_extender, set_metadata,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        clock::{Clock, Slot},
+        hash::Hash,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        slot_hashes::SlotHashes,
+        transaction::Transaction,
+    },
+};
+
+async fn setup_test_context() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "solana_address_lookup_table_program",
+        solana_address_lookup_table_program::id(),
+        processor!(solana_address_lookup_table_program::processor::process),
+    );
+    program_test.add_program(
+        "solana_address_lookup_table_cpi_test_program",
+        cpi_test_program::id(),
+        processor!(process),
+    );
+    program_test.start_with_context().await
+}
+
+fn overwrite_slot_hashes_with_slots(context: &ProgramTestContext, slots: &[Slot]) {
+    let mut slot_hashes = SlotHashes::default();
+    for slot in slots {
+        slot_hashes.add(*slot, Hash::new_unique());
+    }
+    context.set_sysvar(&slot_hashes);
+}
+
+/// Processes `ix` and returns the events decoded from the transaction logs
+async fn process_ix(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    authority: Option<&Keypair>,
+) -> Vec<LookupTableEvent> {
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = recent_blockhash;
+
+    let mut signers = vec![payer];
+    if let Some(authority) = authority {
+        signers.push(authority);
+    }
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &signers,
+        recent_blockhash,
+    );
+
+    let result = client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    assert_matches!(result.result, Ok(()));
+    decode_events(
+        &solana_address_lookup_table_program::id(),
+        &result.metadata.unwrap().log_messages,
+    )
+    .unwrap()
+}
+
+async fn get_slot(context: &mut ProgramTestContext) -> Slot {
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .slot
+}
+
+#[tokio::test]
+async fn test_lifecycle_events() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[recent_slot]);
+
+    let (create_ix, lookup_table_address) =
+        create_lookup_table_signed(authority.pubkey(), context.payer.pubkey(), recent_slot);
+    let slot = get_slot(&mut context).await;
+    let events = process_ix(&mut context, create_ix, Some(&authority)).await;
+    let created = LookupTableEvent {
+        kind: EventKind::Create,
+        lookup_table: lookup_table_address,
+        authority: Some(authority.pubkey()),
+        slot,
+        old_len: 0,
+        new_len: 0,
+        status: TableStatus::Active,
+        account: None,
+    };
+    assert_eq!(events, vec![created]);
+
+    let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let extend_ix = extend_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        new_addresses.clone(),
+    );
+    let events = process_ix(&mut context, extend_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::Extend,
+            new_len: 2,
+            ..created
+        }]
+    );
+
+    // Extending with addresses already in the table is still logged
+    let extend_ix = extend_lookup_table_unique(
+        lookup_table_address,
+        authority.pubkey(),
+        None,
+        new_addresses,
+        true,
+    );
+    let events = process_ix(&mut context, extend_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::Extend,
+            old_len: 2,
+            new_len: 2,
+            ..created
+        }]
+    );
+
+    let deactivate_ix = deactivate_lookup_table(lookup_table_address, authority.pubkey());
+    let events = process_ix(&mut context, deactivate_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::Deactivate,
+            old_len: 2,
+            new_len: 2,
+            status: TableStatus::Deactivated,
+            ..created
+        }]
+    );
+
+    // Move past the deactivation slot and drop it from the slot hashes so
+    // that the table is fully deactivated.
+    let next_slot = slot.saturating_add(1);
+    context.warp_to_slot(next_slot).unwrap();
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let close_ix = close_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        context.payer.pubkey(),
+    );
+    let events = process_ix(&mut context, close_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::Close,
+            slot: next_slot,
+            old_len: 2,
+            new_len: 0,
+            status: TableStatus::Closed,
+            ..created
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_extension_and_metadata_events() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[recent_slot]);
+
+    let (create_ix, lookup_table_address) =
+        create_lookup_table_signed(authority.pubkey(), context.payer.pubkey(), recent_slot);
+    let slot = get_slot(&mut context).await;
+    let events = process_ix(&mut context, create_ix, Some(&authority)).await;
+    let created = LookupTableEvent {
+        kind: EventKind::Create,
+        lookup_table: lookup_table_address,
+        authority: Some(authority.pubkey()),
+        slot,
+        old_len: 0,
+        new_len: 0,
+        status: TableStatus::Active,
+        account: None,
+    };
+    assert_eq!(events, vec![created]);
+
+    let new_authority = Pubkey::new_unique();
+    let propose_ix = propose_authority(
+        lookup_table_address,
+        authority.pubkey(),
+        new_authority,
+        Some(context.payer.pubkey()),
+    );
+    let events = process_ix(&mut context, propose_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::ProposeAuthority,
+            account: Some(new_authority),
+            ..created
+        }]
+    );
+
+    let cancel_ix = cancel_authority_proposal(lookup_table_address, authority.pubkey());
+    let events = process_ix(&mut context, cancel_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::CancelAuthorityProposal,
+            account: Some(new_authority),
+            ..created
+        }]
+    );
+
+    let extender = Pubkey::new_unique();
+    let set_extender_ix = set_extender(
+        lookup_table_address,
+        authority.pubkey(),
+        extender,
+        Some(context.payer.pubkey()),
+    );
+    let events = process_ix(&mut context, set_extender_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::SetExtender,
+            account: Some(extender),
+            ..created
+        }]
+    );
+
+    let remove_extender_ix = remove_extender(lookup_table_address, authority.pubkey());
+    let events = process_ix(&mut context, remove_extender_ix, Some(&authority)).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::RemoveExtender,
+            account: Some(extender),
+            ..created
+        }]
+    );
+
+    let set_metadata_ix = set_metadata(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        "Swaps".to_string(),
+        "https://example.com/swaps.json".to_string(),
+        vec![],
+    );
+    let events = process_ix(&mut context, set_metadata_ix, Some(&authority)).await;
+    let (metadata_address, _) = derive_lookup_table_metadata_address(&lookup_table_address);
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::SetMetadata,
+            account: Some(metadata_address),
+            ..created
+        }]
+    );
+}
+
+#[tokio::test]
+async fn test_cpi_events() {
+    let mut context = setup_test_context().await;
+    let (authority_address, _) = cpi_test_program::find_authority_address();
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[recent_slot]);
+
+    let (create_ix, lookup_table_address) =
+        cpi_test_program::create(context.payer.pubkey(), recent_slot);
+    let slot = get_slot(&mut context).await;
+    let events = process_ix(&mut context, create_ix, None).await;
+    let created = LookupTableEvent {
+        kind: EventKind::Create,
+        lookup_table: lookup_table_address,
+        authority: Some(authority_address),
+        slot,
+        old_len: 0,
+        new_len: 0,
+        status: TableStatus::Active,
+        account: None,
+    };
+    assert_eq!(events, vec![created]);
+
+    let extend_ix = cpi_test_program::extend(
+        lookup_table_address,
+        context.payer.pubkey(),
+        vec![Pubkey::new_unique()],
+    );
+    let events = process_ix(&mut context, extend_ix, None).await;
+    assert_eq!(
+        events,
+        vec![LookupTableEvent {
+            kind: EventKind::Extend,
+            new_len: 1,
+            ..created
+        }]
+    );
+}
@@ -0,0 +1,384 @@
+//! Conformance fixture format for the address lookup table program.
+//!
+//! A fixture captures a single instruction together with the state it runs
+//! in: the accounts it references, the `Clock`, `Rent` and `SlotHashes`
+//! sysvars, and the result, account states and return data it is expected to
+//! produce. Fixtures are stored as JSON so that other implementations of the
+//! program can replay them:
+//!
+//! ```json
+//! {
+//!   "name": "freeze_lookup_table_ix-test_freeze_lookup_table",
+//!   "sysvars": {
+//!     "clock": { "slot": 1, "epoch_start_timestamp": 0, "epoch": 0,
+//!                "leader_schedule_epoch": 1, "unix_timestamp": 0 },
+//!     "rent": { "lamports_per_byte_year": 3480, "exemption_threshold": 2.0,
+//!               "burn_percent": 50 },
+//!     "slot_hashes": [{ "slot": 0, "hash": "<base58>" }]
+//!   },
+//!   "instruction": {
+//!     "program_id": "AddressLookupTab1e1111111111111111111111111",
+//!     "accounts": [{ "pubkey": "<base58>", "is_signer": false, "is_writable": true }],
+//!     "data": "<base64>"
+//!   },
+//!   "accounts": [{ "address": "<base58>", "lamports": 1, "data": "<base64>",
+//!                  "owner": "<base58>", "executable": false }],
+//!   "expected": { "error": null, "accounts": [], "return_data": "" }
+//! }
+//! ```
+//!
+//! `accounts` and `expected.accounts` list every account the instruction
+//! references, in order. Accounts that do not exist have no lamports, no data
+//! and are owned by the system program. `expected.error` is the
+//! `InstructionError` returned by the instruction, in the serde encoding of
+//! `solana-sdk`, or `null` if it succeeds.
+//!
+//! Fixtures record the errors of the build of the program that produced them,
+//! so fixtures exported from a build with the `native-errors` feature carry
+//! native errors and should be replayed against such a build.
+//!
+//! The tests of the program export fixtures, and the conformance runner in
+//! `../conformance` replays them.
+
+use {
+    serde::{Deserialize, Serialize},
+    solana_sdk::{
+        account::{Account, AccountSharedData},
+        clock::{Clock, Slot},
+        hash::Hash,
+        instruction::{AccountMeta, Instruction, InstructionError},
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+    },
+    std::{
+        fs::{self, File, OpenOptions},
+        io::{self, BufReader, BufWriter},
+        path::{Path, PathBuf},
+    },
+};
+
+/// Errors that may be returned while loading or saving fixtures
+#[derive(Debug, thiserror::Error)]
+pub enum FixtureError {
+    /// Reading or writing a fixture file failed
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// A fixture file is not valid JSON or does not match the format
+    #[error("Invalid fixture: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Serializes values as strings with their `Display` and `FromStr`
+/// implementations, used for base58 addresses and hashes
+mod display_string {
+    use {
+        serde::{de::Error, Deserialize, Deserializer, Serializer},
+        std::{fmt::Display, str::FromStr},
+    };
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Serializes bytes as base64 strings
+mod base64_bytes {
+    use {
+        base64::{engine::general_purpose::STANDARD, Engine},
+        serde::{de::Error, Deserialize, Deserializer, Serializer},
+    };
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        STANDARD
+            .decode(String::deserialize(deserializer)?)
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Single instruction, the state it runs in and the outcome it produces
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    /// Name of the case the fixture was captured from
+    pub name: String,
+    /// Sysvars the instruction reads
+    pub sysvars: FixtureSysvars,
+    /// Instruction to execute
+    pub instruction: FixtureInstruction,
+    /// Accounts referenced by the instruction, before it is executed
+    pub accounts: Vec<FixtureAccount>,
+    /// Outcome of the instruction
+    pub expected: FixtureEffects,
+}
+
+/// Sysvars the program reads
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FixtureSysvars {
+    pub clock: Clock,
+    pub rent: Rent,
+    pub slot_hashes: Vec<FixtureSlotHash>,
+}
+
+/// Entry of the slot hashes sysvar
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureSlotHash {
+    pub slot: Slot,
+    #[serde(with = "display_string")]
+    pub hash: Hash,
+}
+
+/// Instruction executed by a fixture
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureInstruction {
+    #[serde(with = "display_string")]
+    pub program_id: Pubkey,
+    pub accounts: Vec<FixtureAccountMeta>,
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// Account referenced by an instruction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureAccountMeta {
+    #[serde(with = "display_string")]
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// State of an account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureAccount {
+    #[serde(with = "display_string")]
+    pub address: Pubkey,
+    pub lamports: u64,
+    #[serde(with = "base64_bytes")]
+    pub data: Vec<u8>,
+    #[serde(with = "display_string")]
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+/// Outcome of the instruction of a fixture
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FixtureEffects {
+    /// Error returned by the instruction, if any
+    pub error: Option<InstructionError>,
+    /// Accounts referenced by the instruction, after it is executed
+    pub accounts: Vec<FixtureAccount>,
+    /// Return data set by the instruction, empty if there is none
+    #[serde(with = "base64_bytes")]
+    pub return_data: Vec<u8>,
+}
+
+impl FixtureAccount {
+    /// Describes the state of the account at `address`, or of a missing
+    /// account if `account` is `None`
+    pub fn new(address: Pubkey, account: Option<Account>) -> Self {
+        let account = account.unwrap_or_else(|| Account::new(0, 0, &system_program::id()));
+        Self {
+            address,
+            lamports: account.lamports,
+            data: account.data,
+            owner: account.owner,
+            executable: account.executable,
+        }
+    }
+
+    /// Returns whether the account exists, i.e. holds lamports
+    pub fn exists(&self) -> bool {
+        self.lamports > 0
+    }
+}
+
+impl From<&FixtureAccount> for AccountSharedData {
+    fn from(account: &FixtureAccount) -> Self {
+        Account {
+            lamports: account.lamports,
+            data: account.data.clone(),
+            owner: account.owner,
+            executable: account.executable,
+            rent_epoch: 0,
+        }
+        .into()
+    }
+}
+
+impl From<&FixtureInstruction> for Instruction {
+    fn from(instruction: &FixtureInstruction) -> Self {
+        let accounts = instruction
+            .accounts
+            .iter()
+            .map(|meta| AccountMeta {
+                pubkey: meta.pubkey,
+                is_signer: meta.is_signer,
+                is_writable: meta.is_writable,
+            })
+            .collect();
+        Instruction::new_with_bytes(instruction.program_id, &instruction.data, accounts)
+    }
+}
+
+impl Fixture {
+    /// Reads a fixture from a JSON file
+    pub fn load(path: &Path) -> Result<Self, FixtureError> {
+        let file = File::open(path)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    }
+
+    /// Reads every `.json` fixture in `dir`, ordered by file name
+    pub fn load_dir(dir: &Path) -> Result<Vec<(PathBuf, Self)>, FixtureError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| {
+                let fixture = Self::load(&path)?;
+                Ok((path, fixture))
+            })
+            .collect()
+    }
+
+    /// Writes the fixture to a new JSON file in `dir` named after the
+    /// fixture, with a numeric suffix if the case produced several fixtures.
+    /// Returns the path of the file.
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, FixtureError> {
+        fs::create_dir_all(dir)?;
+        let stem: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        for index in 0usize.. {
+            let path = match index {
+                0 => dir.join(format!("{stem}.json")),
+                _ => dir.join(format!("{stem}-{index}.json")),
+            };
+            // `create_new` keeps tests running in parallel from overwriting
+            // each other's fixtures
+            let file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(file) => file,
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err.into()),
+            };
+            serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+            return Ok(path);
+        }
+        unreachable!("ran out of fixture file names")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk::hash::hash};
+
+    fn new_fixture() -> Fixture {
+        let address = Pubkey::new_unique();
+        let account = FixtureAccount {
+            address,
+            lamports: 42,
+            data: vec![1, 2, 3],
+            owner: Pubkey::new_unique(),
+            executable: false,
+        };
+        Fixture {
+            name: "lookup_table_ix::test_case".to_string(),
+            sysvars: FixtureSysvars {
+                clock: Clock::default(),
+                rent: Rent::default(),
+                slot_hashes: vec![FixtureSlotHash {
+                    slot: 7,
+                    hash: hash(&[7]),
+                }],
+            },
+            instruction: FixtureInstruction {
+                program_id: solana_sdk::address_lookup_table::program::id(),
+                accounts: vec![FixtureAccountMeta {
+                    pubkey: address,
+                    is_signer: false,
+                    is_writable: true,
+                }],
+                data: vec![1, 0, 0, 0],
+            },
+            accounts: vec![account.clone()],
+            expected: FixtureEffects {
+                error: Some(InstructionError::Custom(4)),
+                accounts: vec![account],
+                return_data: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_json_encoding() {
+        let fixture = new_fixture();
+        let json = serde_json::to_value(&fixture).unwrap();
+
+        assert_eq!(
+            json["instruction"]["program_id"],
+            "AddressLookupTab1e1111111111111111111111111"
+        );
+        assert_eq!(json["instruction"]["data"], "AQAAAA==");
+        assert_eq!(
+            json["sysvars"]["slot_hashes"][0]["hash"],
+            hash(&[7]).to_string()
+        );
+        assert_eq!(json["expected"]["error"]["Custom"], 4);
+        assert_eq!(serde_json::from_value::<Fixture>(json).unwrap(), fixture);
+    }
+
+    #[test]
+    fn test_save_and_load_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = new_fixture();
+
+        let first = fixture.save(dir.path()).unwrap();
+        let second = fixture.save(dir.path()).unwrap();
+        assert_eq!(
+            first.file_name().unwrap(),
+            "lookup_table_ix__test_case.json"
+        );
+        assert_eq!(
+            second.file_name().unwrap(),
+            "lookup_table_ix__test_case-1.json"
+        );
+        fs::write(dir.path().join("README.md"), "not a fixture").unwrap();
+
+        let mut expected = vec![(first, fixture.clone()), (second, fixture)];
+        expected.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(Fixture::load_dir(dir.path()).unwrap(), expected);
+    }
+}
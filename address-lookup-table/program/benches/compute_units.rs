@@ -0,0 +1,698 @@
+//! Compute unit benchmarks
+//!
+//! Runs every instruction of the SBF build of the program against a matrix
+//! of table sizes and batch sizes, and compares the compute units consumed by
+//! each case with the baseline recorded in `compute_units.txt`. Cases that the
+//! native builtin also supports are run against it too, for comparison.
+//!
+//! ```sh
+//! cargo build-sbf
+//! cargo bench --features test-sbf --bench compute_units -- [--bless] [--threshold <percent>] [<filter>]
+//! ```
+//!
+//! The run fails if a case consumes more than `--threshold` percent above its
+//! baseline, 5 by default, or if a case is missing from the baseline.
+//! `--bless` records the measured units as the new baseline instead. A filter
+//! only runs the cases whose name contains it.
+
+use {
+    common::{
+        add_lookup_table_account, new_address_lookup_table, overwrite_slot_hashes_with_slots,
+        set_legacy_lookup_table_extension_account, set_lookup_table_extension_account,
+    },
+    solana_address_lookup_table_program::{
+        instruction::{
+            accept_authority, cancel_authority_proposal, cancel_scheduled_deactivation,
+            clone_lookup_table, close_lookup_table, close_lookup_tables, create_lookup_table,
+            create_lookup_table_with_addresses, create_lookup_table_with_seed,
+            deactivate_lookup_table, deactivate_lookup_tables, execute_scheduled_deactivation,
+            extend_lookup_table, extend_lookup_table_unique, freeze_lookup_table,
+            initialize_multisig, migrate_lookup_table, propose_authority, reactivate_lookup_table,
+            remove_extender, resolve_addresses, schedule_deactivation, set_extender, set_metadata,
+            truncate_lookup_table,
+        },
+        state::{
+            LookupTableExtension, LookupTableMultisig, LOOKUP_TABLE_MAX_ADDRESSES,
+            LOOKUP_TABLE_SEED_LEN, MAX_METADATA_NAME_LEN, MAX_METADATA_TAGS, MAX_METADATA_TAG_LEN,
+            MAX_MULTISIG_SIGNERS, MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        },
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::AccountSharedData,
+        address_lookup_table::program::id,
+        clock::{Clock, Slot},
+        compute_budget::ComputeBudgetInstruction,
+        feature::{self, Feature},
+        feature_set::native_programs_consume_cu,
+        instruction::Instruction,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::Signer,
+        transaction::Transaction,
+    },
+    std::{collections::HashMap, env, fmt::Write, fs, path::Path, process},
+};
+
+#[path = "../tests/common.rs"]
+mod common;
+
+const PROGRAM_NAME: &str = "solana_address_lookup_table_program";
+const BASELINE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/benches/compute_units.txt");
+const BASELINE_HEADER: &str = "\
+# Compute units consumed by the SBF build of the program, one case per line.
+# Generated by `cargo bench --features test-sbf --bench compute_units -- --bless`.
+";
+const DEFAULT_THRESHOLD_PERCENT: u64 = 5;
+
+/// Number of addresses in the lookup tables that instructions run against
+const TABLE_SIZES: [usize; 4] = [0, 64, 128, 226];
+/// Number of addresses, indexes or tables that an instruction handles at
+/// once. 30 new addresses is about as many as fits in a transaction.
+const BATCH_SIZES: [usize; 3] = [1, 10, 30];
+
+const CURRENT_SLOT: Slot = 1_000;
+const RECENT_SLOT: Slot = CURRENT_SLOT - 1;
+
+#[derive(Clone, Copy)]
+enum Bench {
+    CreateLookupTable,
+    CreateLookupTableWithSeed,
+    CreateLookupTableWithAddresses,
+    CloneLookupTable,
+    FreezeLookupTable,
+    ExtendLookupTable,
+    ExtendLookupTableUnique,
+    DeactivateLookupTable,
+    DeactivateLookupTables,
+    CloseLookupTable,
+    CloseLookupTables,
+    ProposeAuthority,
+    AcceptAuthority,
+    CancelAuthorityProposal,
+    ReactivateLookupTable,
+    TruncateLookupTable,
+    SetExtender,
+    RemoveExtender,
+    InitializeMultisig,
+    ResolveAddresses,
+    SetMetadata,
+    MigrateLookupTable,
+    ScheduleDeactivation,
+    CancelScheduledDeactivation,
+    ExecuteScheduledDeactivation,
+}
+
+impl Bench {
+    const ALL: [Self; 25] = [
+        Self::CreateLookupTable,
+        Self::CreateLookupTableWithSeed,
+        Self::CreateLookupTableWithAddresses,
+        Self::CloneLookupTable,
+        Self::FreezeLookupTable,
+        Self::ExtendLookupTable,
+        Self::ExtendLookupTableUnique,
+        Self::DeactivateLookupTable,
+        Self::DeactivateLookupTables,
+        Self::CloseLookupTable,
+        Self::CloseLookupTables,
+        Self::ProposeAuthority,
+        Self::AcceptAuthority,
+        Self::CancelAuthorityProposal,
+        Self::ReactivateLookupTable,
+        Self::TruncateLookupTable,
+        Self::SetExtender,
+        Self::RemoveExtender,
+        Self::InitializeMultisig,
+        Self::ResolveAddresses,
+        Self::SetMetadata,
+        Self::MigrateLookupTable,
+        Self::ScheduleDeactivation,
+        Self::CancelScheduledDeactivation,
+        Self::ExecuteScheduledDeactivation,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::CreateLookupTable => "CreateLookupTable",
+            Self::CreateLookupTableWithSeed => "CreateLookupTableWithSeed",
+            Self::CreateLookupTableWithAddresses => "CreateLookupTableWithAddresses",
+            Self::CloneLookupTable => "CloneLookupTable",
+            Self::FreezeLookupTable => "FreezeLookupTable",
+            Self::ExtendLookupTable => "ExtendLookupTable",
+            Self::ExtendLookupTableUnique => "ExtendLookupTableUnique",
+            Self::DeactivateLookupTable => "DeactivateLookupTable",
+            Self::DeactivateLookupTables => "DeactivateLookupTables",
+            Self::CloseLookupTable => "CloseLookupTable",
+            Self::CloseLookupTables => "CloseLookupTables",
+            Self::ProposeAuthority => "ProposeAuthority",
+            Self::AcceptAuthority => "AcceptAuthority",
+            Self::CancelAuthorityProposal => "CancelAuthorityProposal",
+            Self::ReactivateLookupTable => "ReactivateLookupTable",
+            Self::TruncateLookupTable => "TruncateLookupTable",
+            Self::SetExtender => "SetExtender",
+            Self::RemoveExtender => "RemoveExtender",
+            Self::InitializeMultisig => "InitializeMultisig",
+            Self::ResolveAddresses => "ResolveAddresses",
+            Self::SetMetadata => "SetMetadata",
+            Self::MigrateLookupTable => "MigrateLookupTable",
+            Self::ScheduleDeactivation => "ScheduleDeactivation",
+            Self::CancelScheduledDeactivation => "CancelScheduledDeactivation",
+            Self::ExecuteScheduledDeactivation => "ExecuteScheduledDeactivation",
+        }
+    }
+
+    /// Returns the table sizes to run the instruction against, or `None` if
+    /// it does not act on an existing table
+    fn table_sizes(self) -> Option<&'static [usize]> {
+        match self {
+            Self::CreateLookupTable
+            | Self::CreateLookupTableWithSeed
+            | Self::CreateLookupTableWithAddresses
+            | Self::InitializeMultisig => None,
+            // Empty tables cannot be frozen, truncated, cloned or resolved
+            Self::FreezeLookupTable
+            | Self::TruncateLookupTable
+            | Self::CloneLookupTable
+            | Self::ResolveAddresses => Some(&TABLE_SIZES[1..]),
+            _ => Some(&TABLE_SIZES),
+        }
+    }
+
+    fn has_batch(self) -> bool {
+        matches!(
+            self,
+            Self::CreateLookupTableWithAddresses
+                | Self::CloneLookupTable
+                | Self::ExtendLookupTable
+                | Self::ExtendLookupTableUnique
+                | Self::DeactivateLookupTables
+                | Self::CloseLookupTables
+                | Self::ResolveAddresses
+        )
+    }
+
+    /// Whether the native builtin supports the instruction
+    fn is_native(self) -> bool {
+        matches!(
+            self,
+            Self::CreateLookupTable
+                | Self::FreezeLookupTable
+                | Self::ExtendLookupTable
+                | Self::DeactivateLookupTable
+                | Self::CloseLookupTable
+        )
+    }
+}
+
+struct Case {
+    bench: Bench,
+    table_len: usize,
+    batch_len: usize,
+}
+
+impl Case {
+    fn name(&self) -> String {
+        let mut name = self.bench.name().to_string();
+        if self.bench.table_sizes().is_some() {
+            write!(name, "/table={}", self.table_len).unwrap();
+        }
+        if self.bench.has_batch() {
+            write!(name, "/batch={}", self.batch_len).unwrap();
+        }
+        name
+    }
+}
+
+fn cases() -> Vec<Case> {
+    let mut cases = vec![];
+    for bench in Bench::ALL {
+        let table_sizes = bench.table_sizes().unwrap_or(&[0]);
+        let batch_sizes: &[usize] = if bench.has_batch() {
+            &BATCH_SIZES
+        } else {
+            &[0]
+        };
+        for &table_len in table_sizes {
+            for &batch_len in batch_sizes {
+                let fits = match bench {
+                    Bench::ExtendLookupTable | Bench::ExtendLookupTableUnique => {
+                        table_len.saturating_add(batch_len) <= LOOKUP_TABLE_MAX_ADDRESSES
+                    }
+                    Bench::CloneLookupTable => batch_len <= table_len,
+                    _ => true,
+                };
+                if fits {
+                    cases.push(Case {
+                        bench,
+                        table_len,
+                        batch_len,
+                    });
+                }
+            }
+        }
+    }
+    cases
+}
+
+fn new_addresses(len: usize) -> Vec<Pubkey> {
+    (0..len).map(|_| Pubkey::new_unique()).collect()
+}
+
+async fn add_lookup_table(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+    len: usize,
+    deactivation_slot: Slot,
+) -> Pubkey {
+    let lookup_table_address = Pubkey::new_unique();
+    let mut lookup_table = new_address_lookup_table(authority, len);
+    lookup_table.meta.deactivation_slot = deactivation_slot;
+    add_lookup_table_account(context, lookup_table_address, lookup_table).await;
+    lookup_table_address
+}
+
+async fn add_extension(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+    update: impl FnOnce(&mut LookupTableExtension),
+) {
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    update(&mut extension);
+    set_lookup_table_extension_account(context, extension).await;
+}
+
+/// Sets up the accounts of `case` and returns its instruction, signed by the
+/// payer alone
+async fn prepare(context: &mut ProgramTestContext, case: &Case) -> Instruction {
+    let payer = context.payer.pubkey();
+    let Case {
+        bench,
+        table_len,
+        batch_len,
+    } = *case;
+
+    let deactivation_slot = match bench {
+        Bench::CloseLookupTable | Bench::CloseLookupTables => 0,
+        Bench::ReactivateLookupTable => RECENT_SLOT,
+        _ => Slot::MAX,
+    };
+    let authority = match bench {
+        Bench::AcceptAuthority => Pubkey::new_unique(),
+        _ => payer,
+    };
+    let lookup_table_address =
+        add_lookup_table(context, Some(authority), table_len, deactivation_slot).await;
+
+    match bench {
+        Bench::CreateLookupTable => create_lookup_table(payer, payer, RECENT_SLOT).0,
+        Bench::CreateLookupTableWithSeed => {
+            create_lookup_table_with_seed(payer, payer, [1; LOOKUP_TABLE_SEED_LEN]).0
+        }
+        Bench::CreateLookupTableWithAddresses => {
+            create_lookup_table_with_addresses(
+                payer,
+                payer,
+                RECENT_SLOT,
+                new_addresses(batch_len),
+                false,
+            )
+            .0
+        }
+        Bench::CloneLookupTable => {
+            clone_lookup_table(
+                lookup_table_address,
+                payer,
+                payer,
+                RECENT_SLOT,
+                0,
+                u16::try_from(batch_len).unwrap(),
+            )
+            .0
+        }
+        Bench::FreezeLookupTable => freeze_lookup_table(lookup_table_address, payer),
+        Bench::ExtendLookupTable => extend_lookup_table(
+            lookup_table_address,
+            payer,
+            Some(payer),
+            new_addresses(batch_len),
+        ),
+        Bench::ExtendLookupTableUnique => extend_lookup_table_unique(
+            lookup_table_address,
+            payer,
+            Some(payer),
+            new_addresses(batch_len),
+            false,
+        ),
+        Bench::DeactivateLookupTable => deactivate_lookup_table(lookup_table_address, payer),
+        Bench::CloseLookupTable => close_lookup_table(lookup_table_address, payer, payer),
+        Bench::DeactivateLookupTables | Bench::CloseLookupTables => {
+            let mut lookup_table_addresses = vec![lookup_table_address];
+            for _ in 1..batch_len {
+                lookup_table_addresses.push(
+                    add_lookup_table(context, Some(payer), table_len, deactivation_slot).await,
+                );
+            }
+            match bench {
+                Bench::DeactivateLookupTables => {
+                    deactivate_lookup_tables(payer, &lookup_table_addresses)
+                }
+                _ => close_lookup_tables(payer, payer, &lookup_table_addresses),
+            }
+        }
+        Bench::ProposeAuthority => propose_authority(
+            lookup_table_address,
+            payer,
+            Pubkey::new_unique(),
+            Some(payer),
+        ),
+        Bench::AcceptAuthority => {
+            add_extension(context, lookup_table_address, |extension| {
+                extension.pending_authority = Some(payer).try_into().unwrap();
+            })
+            .await;
+            accept_authority(lookup_table_address, payer)
+        }
+        Bench::CancelAuthorityProposal => {
+            add_extension(context, lookup_table_address, |extension| {
+                extension.pending_authority = Some(Pubkey::new_unique()).try_into().unwrap();
+            })
+            .await;
+            cancel_authority_proposal(lookup_table_address, payer)
+        }
+        Bench::ReactivateLookupTable => reactivate_lookup_table(lookup_table_address, payer),
+        Bench::TruncateLookupTable => truncate_lookup_table(
+            lookup_table_address,
+            payer,
+            payer,
+            u8::try_from(table_len / 2).unwrap(),
+            MIN_TRUNCATION_COOL_DOWN_SLOTS,
+            Some(payer),
+        ),
+        Bench::SetExtender => set_extender(
+            lookup_table_address,
+            payer,
+            Pubkey::new_unique(),
+            Some(payer),
+        ),
+        Bench::RemoveExtender => {
+            add_extension(context, lookup_table_address, |extension| {
+                extension.extender = Some(Pubkey::new_unique()).try_into().unwrap();
+            })
+            .await;
+            remove_extender(lookup_table_address, payer)
+        }
+        Bench::InitializeMultisig => {
+            let multisig_address = Pubkey::new_unique();
+            let rent = context.banks_client.get_rent().await.unwrap();
+            context.set_account(
+                &multisig_address,
+                &AccountSharedData::new(
+                    rent.minimum_balance(LookupTableMultisig::LEN),
+                    LookupTableMultisig::LEN,
+                    &id(),
+                ),
+            );
+            initialize_multisig(
+                multisig_address,
+                &new_addresses(MAX_MULTISIG_SIGNERS),
+                u8::try_from(MAX_MULTISIG_SIGNERS).unwrap(),
+            )
+        }
+        Bench::ResolveAddresses => resolve_addresses(
+            lookup_table_address,
+            (0..batch_len)
+                .map(|index| u8::try_from(index.checked_rem(table_len).unwrap()).unwrap())
+                .collect(),
+        ),
+        Bench::SetMetadata => set_metadata(
+            lookup_table_address,
+            payer,
+            Some(payer),
+            "n".repeat(MAX_METADATA_NAME_LEN),
+            "https://example.com/lookup-table.json".to_string(),
+            vec!["t".repeat(MAX_METADATA_TAG_LEN); MAX_METADATA_TAGS],
+        ),
+        Bench::MigrateLookupTable => {
+            set_legacy_lookup_table_extension_account(
+                context,
+                LookupTableExtension::new(lookup_table_address),
+            )
+            .await;
+            migrate_lookup_table(lookup_table_address, Some(payer))
+        }
+        Bench::ScheduleDeactivation => schedule_deactivation(
+            lookup_table_address,
+            payer,
+            CURRENT_SLOT.saturating_add(100),
+            Some(payer),
+        ),
+        Bench::CancelScheduledDeactivation => {
+            add_extension(context, lookup_table_address, |extension| {
+                extension.set_scheduled_deactivation_slot(Some(CURRENT_SLOT.saturating_add(100)));
+            })
+            .await;
+            cancel_scheduled_deactivation(lookup_table_address, payer)
+        }
+        Bench::ExecuteScheduledDeactivation => {
+            add_extension(context, lookup_table_address, |extension| {
+                extension.set_scheduled_deactivation_slot(Some(CURRENT_SLOT));
+            })
+            .await;
+            execute_scheduled_deactivation(lookup_table_address)
+        }
+    }
+}
+
+/// Starts a test context running either the SBF build of the program or the
+/// native builtin
+async fn start(sbf: bool) -> ProgramTestContext {
+    let mut program_test = if sbf {
+        ProgramTest::new(PROGRAM_NAME, id(), None)
+    } else {
+        ProgramTest::default()
+    };
+
+    // `solana-program-test` stops builtins from consuming compute units, so
+    // that programs it runs natively do not have to. Clusters charge them, so
+    // the feature is activated again at the next epoch.
+    program_test.add_account(
+        native_programs_consume_cu::id(),
+        feature::create_account(
+            &Feature::default(),
+            Rent::default().minimum_balance(Feature::size_of()),
+        )
+        .into(),
+    );
+    let mut context = program_test.start_with_context().await;
+    let next_epoch_slot = context
+        .genesis_config()
+        .epoch_schedule
+        .get_first_slot_in_epoch(1);
+    context.warp_to_slot(next_epoch_slot).unwrap();
+
+    context.set_sysvar(&Clock {
+        slot: CURRENT_SLOT,
+        ..Clock::default()
+    });
+    context
+}
+
+/// Returns the compute units consumed by a transaction holding `instructions`
+/// after raising the compute unit limit
+async fn process(context: &mut ProgramTestContext, instructions: &[Instruction]) -> u64 {
+    let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(1_400_000)];
+    all_instructions.extend_from_slice(instructions);
+
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &all_instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    if let Err(err) = result.result {
+        panic!("Transaction failed: {}", err);
+    }
+    result.metadata.unwrap().compute_units_consumed
+}
+
+/// Returns the compute units consumed by the instruction of `case`
+async fn measure(case: &Case, sbf: bool) -> u64 {
+    let mut context = start(sbf).await;
+    let slot_hashes: &[Slot] = match case.bench {
+        Bench::CloseLookupTable | Bench::CloseLookupTables => &[],
+        _ => &[RECENT_SLOT],
+    };
+    overwrite_slot_hashes_with_slots(&context, slot_hashes);
+
+    let overhead = process(&mut context, &[]).await;
+    let instruction = prepare(&mut context, case).await;
+    process(&mut context, &[instruction])
+        .await
+        .saturating_sub(overhead)
+}
+
+fn read_baseline(path: &Path) -> HashMap<String, u64> {
+    let Ok(baseline) = fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+    baseline
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (name, units) = line
+                .split_once(' ')
+                .unwrap_or_else(|| panic!("Invalid baseline entry: {}", line));
+            let units = units
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid baseline entry: {}", line));
+            (name.to_string(), units)
+        })
+        .collect()
+}
+
+fn write_baseline(path: &Path, cases: &[Case], baseline: &HashMap<String, u64>) {
+    let mut contents = BASELINE_HEADER.to_string();
+    for case in cases {
+        let name = case.name();
+        if let Some(units) = baseline.get(&name) {
+            writeln!(contents, "{} {}", name, units).unwrap();
+        }
+    }
+    fs::write(path, contents).unwrap();
+}
+
+struct Args {
+    bless: bool,
+    threshold_percent: u64,
+    filter: Option<String>,
+}
+
+fn parse_args() -> Args {
+    let mut args = Args {
+        bless: false,
+        threshold_percent: DEFAULT_THRESHOLD_PERCENT,
+        filter: None,
+    };
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--bless" => args.bless = true,
+            "--threshold" => {
+                args.threshold_percent = iter
+                    .next()
+                    .and_then(|threshold| threshold.parse().ok())
+                    .expect("--threshold takes a whole number of percent");
+            }
+            // Passed by `cargo bench`
+            "--bench" => {}
+            _ if arg.starts_with("--") => panic!("Unknown argument: {}", arg),
+            _ => args.filter = Some(arg),
+        }
+    }
+    args
+}
+
+/// Points `solana-program-test` at the SBF build of the program, exiting if
+/// it has not been built
+fn find_sbf_program() {
+    let sbf_out_dir = env::var("SBF_OUT_DIR")
+        .or_else(|_| env::var("BPF_OUT_DIR"))
+        .unwrap_or_else(|_| concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy").into());
+    let program_path = Path::new(&sbf_out_dir).join(format!("{}.so", PROGRAM_NAME));
+    if !program_path.exists() {
+        eprintln!(
+            "{} not found, build the program with `cargo build-sbf` or set SBF_OUT_DIR",
+            program_path.display()
+        );
+        process::exit(1);
+    }
+    env::set_var("SBF_OUT_DIR", sbf_out_dir);
+}
+
+#[tokio::main]
+async fn main() {
+    let args = parse_args();
+    find_sbf_program();
+
+    let cases = cases();
+    let baseline_path = Path::new(BASELINE_PATH);
+    let mut baseline = read_baseline(baseline_path);
+    let mut regressions = 0usize;
+    let mut missing = 0usize;
+
+    println!(
+        "{:<50} {:>8} {:>8} {:>8} {:>8}",
+        "case", "units", "baseline", "change", "native"
+    );
+    for case in &cases {
+        let name = case.name();
+        if !args
+            .filter
+            .as_ref()
+            .map_or(true, |filter| name.contains(filter.as_str()))
+        {
+            continue;
+        }
+
+        let units = measure(case, true).await;
+        let native_units = if case.bench.is_native() {
+            measure(case, false).await.to_string()
+        } else {
+            "-".to_string()
+        };
+
+        let change = match baseline.get(&name) {
+            Some(&baseline_units) => {
+                let allowed_units = baseline_units
+                    .saturating_add(baseline_units.saturating_mul(args.threshold_percent) / 100);
+                let regressed = units > allowed_units;
+                if regressed {
+                    regressions = regressions.saturating_add(1);
+                }
+                format!(
+                    "{:+.1}%{}",
+                    (units as f64 / baseline_units.max(1) as f64 - 1.0) * 100.0,
+                    if regressed { "!" } else { "" }
+                )
+            }
+            None => {
+                missing = missing.saturating_add(1);
+                "new!".to_string()
+            }
+        };
+        let baseline_units = baseline.get(&name).map_or("-".to_string(), u64::to_string);
+        println!(
+            "{:<50} {:>8} {:>8} {:>8} {:>8}",
+            name, units, baseline_units, change, native_units
+        );
+
+        if args.bless {
+            baseline.insert(name, units);
+        }
+    }
+
+    if args.bless {
+        write_baseline(baseline_path, &cases, &baseline);
+        println!("Updated {}", baseline_path.display());
+    } else if regressions > 0 || missing > 0 {
+        if regressions > 0 {
+            eprintln!(
+                "{} cases consumed more than {}% above their baseline.",
+                regressions, args.threshold_percent
+            );
+        }
+        if missing > 0 {
+            eprintln!("{} cases are missing from the baseline.", missing);
+        }
+        eprintln!("Run with --bless to accept the new numbers.");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,128 @@
+//! Cross-program invocation helpers for programs that manage address lookup
+//! tables.
+//!
+//! Each helper builds the instruction from the given accounts and invokes the
+//! program. Pass the seeds of any program-derived signer in `signers_seeds`,
+//! or an empty slice if every signer already signed the transaction.
+
+use {
+    crate::instruction,
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Slot,
+        entrypoint::ProgramResult,
+        program::{get_return_data, invoke, invoke_signed},
+        program_error::ProgramError,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+    },
+};
+
+/// Creates an address lookup table derived from `authority` and
+/// `recent_slot`. The authority must sign.
+pub fn create<'a>(
+    lookup_table: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    recent_slot: Slot,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let (instruction, _) =
+        instruction::create_lookup_table_signed(*authority.key, *payer.key, recent_slot);
+    invoke_signed(
+        &instruction,
+        &[
+            lookup_table.clone(),
+            authority.clone(),
+            payer.clone(),
+            system_program.clone(),
+        ],
+        signers_seeds,
+    )
+}
+
+/// Freezes an address lookup table. Fails for a table with a scheduled
+/// deactivation, which must be frozen with its extension account.
+pub fn freeze<'a>(
+    lookup_table: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::freeze_lookup_table(*lookup_table.key, *authority.key),
+        &[lookup_table.clone(), authority.clone()],
+        signers_seeds,
+    )
+}
+
+/// Extends an address lookup table with new addresses. The payer and system
+/// program are only needed if the table must be topped up for rent.
+pub fn extend<'a>(
+    lookup_table: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    payer_and_system_program: Option<(&AccountInfo<'a>, &AccountInfo<'a>)>,
+    new_addresses: Vec<Pubkey>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    let instruction = instruction::extend_lookup_table(
+        *lookup_table.key,
+        *authority.key,
+        payer_and_system_program.map(|(payer, _)| *payer.key),
+        new_addresses,
+    );
+    let mut account_infos = vec![lookup_table.clone(), authority.clone()];
+    if let Some((payer, system_program)) = payer_and_system_program {
+        account_infos.extend([payer.clone(), system_program.clone()]);
+    }
+    invoke_signed(&instruction, &account_infos, signers_seeds)
+}
+
+/// Deactivates an address lookup table.
+pub fn deactivate<'a>(
+    lookup_table: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::deactivate_lookup_table(*lookup_table.key, *authority.key),
+        &[lookup_table.clone(), authority.clone()],
+        signers_seeds,
+    )
+}
+
+/// Closes a deactivated address lookup table, draining its lamports to the
+/// recipient.
+pub fn close<'a>(
+    lookup_table: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    recipient: &AccountInfo<'a>,
+    signers_seeds: &[&[&[u8]]],
+) -> ProgramResult {
+    invoke_signed(
+        &instruction::close_lookup_table(*lookup_table.key, *authority.key, *recipient.key),
+        &[lookup_table.clone(), authority.clone(), recipient.clone()],
+        signers_seeds,
+    )
+}
+
+/// Resolves addresses of an address lookup table by index, with the same
+/// semantics the runtime uses when loading addresses for a transaction.
+pub fn resolve_addresses(
+    lookup_table: &AccountInfo,
+    indexes: Vec<u8>,
+) -> Result<Vec<Pubkey>, ProgramError> {
+    invoke(
+        &instruction::resolve_addresses(*lookup_table.key, indexes),
+        &[lookup_table.clone()],
+    )?;
+    let Some((program_id, data)) = get_return_data() else {
+        // Resolving no addresses leaves the return data empty
+        return Ok(Vec::new());
+    };
+    if program_id != crate::id() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    data.chunks_exact(PUBKEY_BYTES)
+        .map(|key| Pubkey::try_from(key).map_err(|_| ProgramError::InvalidAccountData))
+        .collect()
+}
@@ -1,9 +1,27 @@
 //! Program error types
+//!
+//! Each failure condition has its own `AddressLookupError` variant, returned
+//! as a custom program error. The native address lookup table program reports
+//! most of these conditions with generic instruction errors instead. Enabling
+//! the `native-errors` feature converts every variant that has a native
+//! counterpart to that error, see `AddressLookupError::native_error`, so that
+//! clients written against the native program observe the same codes.
 
-use spl_program_error::*;
+use spl_program_error::{solana_program::program_error::ProgramError, *};
 
 /// Errors that may be returned by the program.
-#[spl_program_error]
+#[repr(u32)]
+#[derive(
+    Clone,
+    Debug,
+    Eq,
+    thiserror::Error,
+    num_derive::FromPrimitive,
+    PartialEq,
+    DecodeError,
+    PrintProgramError,
+)]
+#[num_traits = "num_traits"]
 pub enum AddressLookupError {
     /// Attempted to lookup addresses from a table that does not exist
     #[error("Attempted to lookup addresses from a table that does not exist")]
@@ -29,4 +47,193 @@ pub enum AddressLookupError {
     // Failed to deserialize address lookup table
     #[error("Failed to deserialize address lookup table")]
     FailedToDeserialize,
+    /// Address is already present in the lookup table or the extension batch
+    #[error("Address is already present in the lookup table or the extension batch")]
+    DuplicateAddress,
+    /// Slot used to derive the table address is not in the slot hashes sysvar
+    #[error("Slot used to derive the table address is not a recent slot")]
+    NotRecentSlot,
+    /// Account address does not match the address derived for it
+    #[error("Account address does not match the address derived for it")]
+    AddressMismatch,
+    /// Lookup table already holds the maximum number of addresses
+    #[error("Lookup table is full and cannot contain more addresses")]
+    TableFull,
+    /// Adding the addresses would exceed the maximum table length
+    #[error("Adding the addresses would exceed the maximum lookup table length")]
+    MaxCapacityExceeded,
+    /// Instruction does not add any address to the table
+    #[error("Must add at least one address")]
+    EmptyExtend,
+    /// Empty lookup tables cannot be frozen
+    #[error("Empty lookup tables cannot be frozen")]
+    EmptyTable,
+    /// Lookup table has been deactivated
+    #[error("Lookup table is deactivated")]
+    TableDeactivated,
+    /// Lookup table has not been deactivated
+    #[error("Lookup table is not deactivated")]
+    TableNotDeactivated,
+    /// Lookup table is deactivated but may still be used by transactions
+    #[error("Lookup table is still deactivating")]
+    DeactivationInProgress,
+    /// Lookup table is fully deactivated and can only be closed
+    #[error("Lookup table is fully deactivated and can no longer be reactivated")]
+    DeactivationComplete,
+    /// Recipient of reclaimed lamports is an account being closed
+    #[error("Recipient of reclaimed lamports cannot be an account being closed")]
+    RecipientIsTable,
+    /// Lookup table has no pending authority
+    #[error("Lookup table has no pending authority")]
+    NoPendingAuthority,
+    /// Lookup table has no extender
+    #[error("Lookup table has no extender")]
+    NoExtender,
+    /// Lookup table has no scheduled deactivation
+    #[error("Lookup table has no scheduled deactivation")]
+    NoScheduledDeactivation,
+    /// Scheduled deactivation slot is not later than the current slot
+    #[error("Scheduled deactivation slot must be later than the current slot")]
+    ScheduledSlotNotInFuture,
+    /// Scheduled deactivation slot has not been reached yet
+    #[error("Scheduled deactivation slot has not been reached")]
+    DeactivationNotDue,
+    /// Range of addresses is empty or out of the bounds of the table
+    #[error("Range of addresses is empty or out of bounds")]
+    InvalidAddressRange,
+    /// Lookup table was extended too recently to be truncated
+    #[error("Lookup table was extended too recently to be truncated")]
+    TruncationTooSoon,
+    /// Metadata exceeds the length limits or contains a NUL character
+    #[error("Metadata exceeds the length limits or contains a NUL character")]
+    InvalidMetadata,
+    /// Extension account must be upgraded with `MigrateLookupTable`
+    #[error("Extension account must be upgraded with MigrateLookupTable")]
+    ExtensionNotMigrated,
+    /// Lookup table was truncated too recently to be extended
+    #[error("Lookup table was truncated too recently to be extended")]
+    TableRecentlyTruncated,
+    /// Seed-derived lookup table address belonged to a closed table
+    #[error("Seed-derived lookup table address belonged to a closed table")]
+    TableAddressClosed,
+}
+
+impl AddressLookupError {
+    /// Returns the error the native program reports for the same condition.
+    ///
+    /// Conditions of instructions that the native program does not support
+    /// have no counterpart and return `None`. Serialization failures are
+    /// reported as `InvalidAccountData`, since the native `GenericError` has
+    /// no program error equivalent.
+    ///
+    /// The native `Immutable` and `IncorrectAuthority` errors cannot be
+    /// returned by programs built against this version of `solana-program`,
+    /// so `LookupTableImmutable` and `IncorrectAuthority` return `None` too.
+    pub fn native_error(&self) -> Option<ProgramError> {
+        match self {
+            Self::FailedToSerialize | Self::FailedToDeserialize | Self::ExtensionNotMigrated => {
+                Some(ProgramError::InvalidAccountData)
+            }
+            Self::NotRecentSlot
+            | Self::MaxCapacityExceeded
+            | Self::EmptyExtend
+            | Self::EmptyTable
+            | Self::InvalidAddressRange
+            | Self::InvalidMetadata => Some(ProgramError::InvalidInstructionData),
+            Self::AddressMismatch
+            | Self::TableFull
+            | Self::TableDeactivated
+            | Self::TableNotDeactivated
+            | Self::DeactivationInProgress
+            | Self::DeactivationComplete
+            | Self::RecipientIsTable
+            | Self::NoPendingAuthority
+            | Self::NoExtender
+            | Self::NoScheduledDeactivation
+            | Self::ScheduledSlotNotInFuture
+            | Self::DeactivationNotDue
+            | Self::TruncationTooSoon
+            | Self::TableRecentlyTruncated => Some(ProgramError::InvalidArgument),
+            Self::LookupTableImmutable
+            | Self::IncorrectAuthority
+            | Self::LookupTableAccountNotFound
+            | Self::InvalidAccountOwner
+            | Self::InvalidAccountData
+            | Self::InvalidLookupIndex
+            | Self::DuplicateAddress
+            | Self::TableAddressClosed => None,
+        }
+    }
+}
+
+impl From<AddressLookupError> for ProgramError {
+    fn from(e: AddressLookupError) -> Self {
+        #[cfg(feature = "native-errors")]
+        if let Some(native_error) = e.native_error() {
+            return native_error;
+        }
+        ProgramError::Custom(e as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, spl_program_error::num_traits::FromPrimitive};
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        assert_eq!(AddressLookupError::LookupTableImmutable as u32, 4);
+        assert_eq!(AddressLookupError::IncorrectAuthority as u32, 5);
+        assert_eq!(AddressLookupError::DuplicateAddress as u32, 8);
+        assert_eq!(AddressLookupError::NotRecentSlot as u32, 9);
+        assert_eq!(
+            AddressLookupError::from_u32(AddressLookupError::ExtensionNotMigrated as u32),
+            Some(AddressLookupError::ExtensionNotMigrated)
+        );
+    }
+
+    #[test]
+    fn test_into_program_error() {
+        let expected = |err: AddressLookupError| {
+            if cfg!(feature = "native-errors") {
+                err.native_error().unwrap()
+            } else {
+                ProgramError::Custom(err as u32)
+            }
+        };
+        for err in [
+            AddressLookupError::FailedToDeserialize,
+            AddressLookupError::NotRecentSlot,
+            AddressLookupError::TableDeactivated,
+        ] {
+            assert_eq!(ProgramError::from(err.clone()), expected(err));
+        }
+
+        // Errors without a native counterpart are always custom errors
+        assert_eq!(
+            ProgramError::from(AddressLookupError::LookupTableImmutable),
+            ProgramError::Custom(4)
+        );
+        assert_eq!(
+            ProgramError::from(AddressLookupError::DuplicateAddress),
+            ProgramError::Custom(8)
+        );
+    }
+
+    #[test]
+    fn test_native_error() {
+        assert_eq!(
+            AddressLookupError::TableFull.native_error(),
+            Some(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            AddressLookupError::MaxCapacityExceeded.native_error(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            AddressLookupError::FailedToDeserialize.native_error(),
+            Some(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(AddressLookupError::InvalidLookupIndex.native_error(), None);
+    }
 }
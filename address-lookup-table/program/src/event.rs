@@ -0,0 +1,365 @@
+//! Events logged when an instruction changes a lookup table
+//!
+//! Every instruction that changes a table, its extension account or its
+//! metadata account logs one event per affected table with `sol_log_data`.
+//! `InitializeMultisig` logs an event for the new multisig account. The
+//! runtime records each event as a `Program data:` log line holding the
+//! base64-encoded event.
+//!
+//! An event is 113 bytes, with little-endian integers:
+//!
+//! | Offset | Size | Field                                                |
+//! |--------|------|------------------------------------------------------|
+//! | 0      | 1    | Version, currently `EVENT_VERSION`                   |
+//! | 1      | 1    | `EventKind`                                          |
+//! | 2      | 32   | Address of the lookup table                          |
+//! | 34     | 1    | `1` if the table has an authority, `0` otherwise     |
+//! | 35     | 32   | Authority of the table, zeroed if there is none      |
+//! | 67     | 8    | Slot in which the change happened                    |
+//! | 75     | 2    | Number of addresses before the change                |
+//! | 77     | 2    | Number of addresses after the change                 |
+//! | 79     | 1    | `TableStatus` after the change                       |
+//! | 80     | 1    | `1` if the event names an account, `0` otherwise     |
+//! | 81     | 32   | Account named by the event, zeroed if there is none  |
+//!
+//! New fields are only ever appended. Decoders accept longer events of a
+//! known version and ignore the trailing bytes.
+
+use {
+    crate::state::LookupTableMeta,
+    solana_program::{
+        clock::Slot,
+        log::sol_log_data,
+        program_error::ProgramError,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+    },
+};
+
+/// Version of the event layout
+pub const EVENT_VERSION: u8 = 1;
+
+/// Length of an event logged by this version of the program
+pub const EVENT_LEN: usize = 113;
+
+/// Change to a lookup table described by an event
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    /// The table was created, by any of the create instructions or by
+    /// `CloneLookupTable`
+    Create,
+    /// The table was frozen
+    Freeze,
+    /// Addresses were appended to the table
+    Extend,
+    /// Addresses were removed from the end of the table
+    Truncate,
+    /// The table was deactivated, either directly or by executing a scheduled
+    /// deactivation
+    Deactivate,
+    /// A deactivating table was made active again
+    Reactivate,
+    /// The table was closed
+    Close,
+    /// The pending authority of the table accepted the authority
+    ChangeAuthority,
+    /// A deactivation was scheduled for a later slot
+    ScheduleDeactivation,
+    /// A scheduled deactivation was cancelled
+    CancelScheduledDeactivation,
+    /// A new authority was proposed for the table
+    ProposeAuthority,
+    /// The pending authority proposal of the table was cancelled
+    CancelAuthorityProposal,
+    /// An extender was set for the table
+    SetExtender,
+    /// The extender of the table was removed
+    RemoveExtender,
+    /// The metadata of the table was created or replaced
+    SetMetadata,
+    /// A multisig account was initialized. The event describes the multisig
+    /// rather than a table.
+    InitializeMultisig,
+}
+
+impl TryFrom<u8> for EventKind {
+    type Error = ProgramError;
+
+    fn try_from(kind: u8) -> Result<Self, Self::Error> {
+        Ok(match kind {
+            0 => Self::Create,
+            1 => Self::Freeze,
+            2 => Self::Extend,
+            3 => Self::Truncate,
+            4 => Self::Deactivate,
+            5 => Self::Reactivate,
+            6 => Self::Close,
+            7 => Self::ChangeAuthority,
+            8 => Self::ScheduleDeactivation,
+            9 => Self::CancelScheduledDeactivation,
+            10 => Self::ProposeAuthority,
+            11 => Self::CancelAuthorityProposal,
+            12 => Self::SetExtender,
+            13 => Self::RemoveExtender,
+            14 => Self::SetMetadata,
+            15 => Self::InitializeMultisig,
+            _ => return Err(ProgramError::InvalidAccountData),
+        })
+    }
+}
+
+/// Status of a lookup table after the change described by an event
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableStatus {
+    /// The table is active and may be extended
+    Active,
+    /// The table has a deactivation slot. It may still be in use until the
+    /// slot leaves the slot hashes sysvar.
+    Deactivated,
+    /// The table account was closed
+    Closed,
+}
+
+impl TryFrom<u8> for TableStatus {
+    type Error = ProgramError;
+
+    fn try_from(status: u8) -> Result<Self, Self::Error> {
+        Ok(match status {
+            0 => Self::Active,
+            1 => Self::Deactivated,
+            2 => Self::Closed,
+            _ => return Err(ProgramError::InvalidAccountData),
+        })
+    }
+}
+
+/// Change to a lookup table, as logged by the program
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LookupTableEvent {
+    /// What changed
+    pub kind: EventKind,
+    /// Address of the table. For `InitializeMultisig`, the address of the
+    /// multisig account.
+    pub lookup_table: Pubkey,
+    /// Authority of the table after the change. For `Close`, the authority
+    /// that closed the table.
+    pub authority: Option<Pubkey>,
+    /// Slot in which the change happened
+    pub slot: Slot,
+    /// Number of addresses before the change
+    pub old_len: u16,
+    /// Number of addresses after the change
+    pub new_len: u16,
+    /// Status of the table after the change
+    pub status: TableStatus,
+    /// Account the change concerns besides the table: the proposed authority
+    /// for `ProposeAuthority`, the withdrawn proposal for
+    /// `CancelAuthorityProposal`, the extender for `SetExtender` and
+    /// `RemoveExtender` and the metadata account for `SetMetadata`
+    pub account: Option<Pubkey>,
+}
+
+impl LookupTableEvent {
+    /// Describes a change to a table from its metadata after the change
+    pub fn new(
+        kind: EventKind,
+        lookup_table: Pubkey,
+        lookup_table_meta: &LookupTableMeta,
+        slot: Slot,
+        old_len: usize,
+        new_len: usize,
+    ) -> Result<Self, ProgramError> {
+        let status = if kind == EventKind::Close {
+            TableStatus::Closed
+        } else if u64::from(lookup_table_meta.deactivation_slot) != Slot::MAX {
+            TableStatus::Deactivated
+        } else {
+            TableStatus::Active
+        };
+        Ok(Self {
+            kind,
+            lookup_table,
+            authority: lookup_table_meta.authority(),
+            slot,
+            old_len: u16::try_from(old_len).map_err(|_| ProgramError::InvalidAccountData)?,
+            new_len: u16::try_from(new_len).map_err(|_| ProgramError::InvalidAccountData)?,
+            status,
+            account: None,
+        })
+    }
+
+    /// Describes the initialization of a multisig account
+    pub fn new_multisig(multisig: Pubkey, slot: Slot) -> Self {
+        Self {
+            kind: EventKind::InitializeMultisig,
+            lookup_table: multisig,
+            authority: None,
+            slot,
+            old_len: 0,
+            new_len: 0,
+            status: TableStatus::Active,
+            account: None,
+        }
+    }
+
+    /// Serializes the event
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(EVENT_LEN);
+        data.extend_from_slice(&[EVENT_VERSION, self.kind as u8]);
+        data.extend_from_slice(self.lookup_table.as_ref());
+        match self.authority {
+            Some(authority) => {
+                data.push(1);
+                data.extend_from_slice(authority.as_ref());
+            }
+            None => data.extend_from_slice(&[0; 1 + PUBKEY_BYTES]),
+        }
+        data.extend_from_slice(&self.slot.to_le_bytes());
+        data.extend_from_slice(&self.old_len.to_le_bytes());
+        data.extend_from_slice(&self.new_len.to_le_bytes());
+        data.push(self.status as u8);
+        match self.account {
+            Some(account) => {
+                data.push(1);
+                data.extend_from_slice(account.as_ref());
+            }
+            None => data.extend_from_slice(&[0; 1 + PUBKEY_BYTES]),
+        }
+        data
+    }
+
+    /// Deserializes an event logged by any version of the program that uses
+    /// a layout this crate knows about
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < EVENT_LEN || data[0] != EVENT_VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let pubkey_at = |offset: usize| {
+            Pubkey::try_from(&data[offset..offset.saturating_add(PUBKEY_BYTES)])
+                .map_err(|_| ProgramError::InvalidAccountData)
+        };
+        let authority = match data[34] {
+            0 => None,
+            1 => Some(pubkey_at(35)?),
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let account = match data[80] {
+            0 => None,
+            1 => Some(pubkey_at(81)?),
+            _ => return Err(ProgramError::InvalidAccountData),
+        };
+        let mut slot = [0; 8];
+        slot.copy_from_slice(&data[67..75]);
+        let mut old_len = [0; 2];
+        old_len.copy_from_slice(&data[75..77]);
+        let mut new_len = [0; 2];
+        new_len.copy_from_slice(&data[77..79]);
+        Ok(Self {
+            kind: EventKind::try_from(data[1])?,
+            lookup_table: pubkey_at(2)?,
+            authority,
+            slot: Slot::from_le_bytes(slot),
+            old_len: u16::from_le_bytes(old_len),
+            new_len: u16::from_le_bytes(new_len),
+            status: TableStatus::try_from(data[79])?,
+            account,
+        })
+    }
+
+    /// Logs the event with `sol_log_data`
+    pub fn log(&self) {
+        sol_log_data(&[&self.pack()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_event(authority: Option<Pubkey>) -> LookupTableEvent {
+        LookupTableEvent {
+            kind: EventKind::Extend,
+            lookup_table: Pubkey::new_unique(),
+            authority,
+            slot: Slot::MAX - 1,
+            old_len: 255,
+            new_len: 256,
+            status: TableStatus::Active,
+            account: None,
+        }
+    }
+
+    #[test]
+    fn test_pack_unpack() {
+        let with_account = LookupTableEvent {
+            kind: EventKind::SetExtender,
+            account: Some(Pubkey::new_unique()),
+            ..new_event(None)
+        };
+        let multisig = LookupTableEvent::new_multisig(Pubkey::new_unique(), 3);
+        for event in [
+            new_event(Some(Pubkey::new_unique())),
+            new_event(None),
+            with_account,
+            multisig,
+        ] {
+            let data = event.pack();
+            assert_eq!(data.len(), EVENT_LEN);
+            assert_eq!(LookupTableEvent::unpack(&data), Ok(event));
+        }
+    }
+
+    #[test]
+    fn test_new_event_status() {
+        let lookup_table = Pubkey::new_unique();
+        let mut meta = LookupTableMeta::new(Pubkey::new_unique());
+        let event = |kind, meta: &LookupTableMeta| {
+            LookupTableEvent::new(kind, lookup_table, meta, 10, 1, 2)
+                .unwrap()
+                .status
+        };
+        assert_eq!(event(EventKind::Extend, &meta), TableStatus::Active);
+        meta.deactivation_slot = 9.into();
+        assert_eq!(
+            event(EventKind::Deactivate, &meta),
+            TableStatus::Deactivated
+        );
+        assert_eq!(event(EventKind::Close, &meta), TableStatus::Closed);
+    }
+
+    #[test]
+    fn test_unpack_ignores_appended_fields() {
+        let event = new_event(None);
+        let mut data = event.pack();
+        data.extend_from_slice(&[7; 8]);
+        assert_eq!(LookupTableEvent::unpack(&data), Ok(event));
+    }
+
+    #[test]
+    fn test_unpack_invalid_data() {
+        let data = LookupTableEvent {
+            account: Some(Pubkey::new_unique()),
+            ..new_event(Some(Pubkey::new_unique()))
+        }
+        .pack();
+        let invalid = |index: usize, value: u8| {
+            let mut data = data.clone();
+            data[index] = value;
+            LookupTableEvent::unpack(&data)
+        };
+
+        assert_eq!(
+            LookupTableEvent::unpack(&data[..EVENT_LEN - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            invalid(0, EVENT_VERSION + 1),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(invalid(1, 16), Err(ProgramError::InvalidAccountData));
+        assert_eq!(invalid(34, 2), Err(ProgramError::InvalidAccountData));
+        assert_eq!(invalid(79, 3), Err(ProgramError::InvalidAccountData));
+        assert_eq!(invalid(80, 2), Err(ProgramError::InvalidAccountData));
+    }
+}
@@ -0,0 +1,1406 @@
+//! Program instructions
+
+use {
+    crate::state::{
+        find_extension_address, find_lookup_table_address_with_seed, find_metadata_address,
+        LOOKUP_TABLE_SEED_LEN,
+    },
+    serde::{Deserialize, Serialize},
+    solana_program::{
+        clock::{Epoch, Slot},
+        epoch_schedule::EpochSchedule,
+        instruction::{AccountMeta, Instruction},
+        program::MAX_RETURN_DATA,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+        system_program,
+    },
+};
+
+/// Instructions supported by the program.
+///
+/// The bincode encoding of this enum is wire-compatible with the SDK's
+/// `ProgramInstruction`, so existing clients continue to work unchanged.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub enum AddressLookupInstruction {
+    /// Create an address lookup table
+    ///
+    /// Sets `ReturnData::CreateLookupTable` as the return data, as do the
+    /// other create instructions.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Uninitialized address lookup table account
+    ///   1. `[SIGNER]` Account used to derive and control the new address
+    ///      lookup table.
+    ///   2. `[SIGNER, WRITE]` Account that will fund the new address lookup
+    ///      table.
+    ///   3. `[]` System program for CPI.
+    CreateLookupTable {
+        /// A recent slot must be used in the derivation path
+        /// for each initialized table. When closing table accounts,
+        /// the initialization slot must no longer be "recent" to prevent
+        /// address tables from being recreated with reordered or
+        /// otherwise malicious addresses.
+        recent_slot: Slot,
+        /// Address tables are always initialized at program-derived
+        /// addresses using the funding address, recent blockhash, and
+        /// the user-passed `bump_seed`.
+        bump_seed: u8,
+    },
+
+    /// Permanently freeze an address lookup table, making it immutable.
+    ///
+    /// Any scheduled deactivation is cleared, since it could no longer be
+    /// cancelled. The table's extension account is required while a
+    /// deactivation is scheduled.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to freeze
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE, OPTIONAL]` Extension account of the lookup table, if a
+    ///      deactivation is scheduled
+    ///   3. ..3+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    FreezeLookupTable,
+
+    /// Extend an address lookup table with new addresses. Funding account and
+    /// system program account references are only required if the lookup
+    /// table account requires additional lamports to cover the rent-exempt
+    /// balance after being extended.
+    ///
+    /// Sets `ReturnData::ExtendLookupTable` as the return data, as does
+    /// `ExtendLookupTableUnique`.
+    ///
+    /// The table's extender may sign instead of the authority, in which case
+    /// the table's extension account must directly follow the signer. The
+    /// extension account is also required until the cool-down of the last
+    /// `TruncateLookupTable` is over. If the authority is a multisig, its
+    /// signers follow the last account passed, so the optional accounts
+    /// before them may still be left out.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to extend
+    ///   1. `[SIGNER]` Current authority or extender
+    ///   2. `[OPTIONAL]` Extension account of the lookup table, if signed by
+    ///      the extender or while a truncation cool-down is pending
+    ///   3. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the table
+    ///      reallocation
+    ///   4. `[OPTIONAL]` System program for CPI.
+    ///   5. ..5+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    ExtendLookupTable { new_addresses: Vec<Pubkey> },
+
+    /// Deactivate an address lookup table, making it unusable and
+    /// eligible for closure after a short period of time.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to deactivate
+    ///   1. `[SIGNER]` Current authority
+    ///   2. ..2+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    DeactivateLookupTable,
+
+    /// Close an address lookup table account
+    ///
+    /// If the table has metadata or extension accounts, they may be passed
+    /// after the recipient to be closed along with the table. Otherwise their
+    /// rent stays locked in them. The extension account of a seed-derived
+    /// table is always kept. A passed extension account is also read to report
+    /// a scheduled deactivation when the table is still active.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to close
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Recipient of closed account lamports
+    ///   3. `[WRITE, OPTIONAL]` Metadata account of the lookup table
+    ///   4. `[WRITE, OPTIONAL]` Extension account of the lookup table
+    ///   5. ..5+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    CloseLookupTable,
+
+    /// Nominate a new authority for an address lookup table. The nominee
+    /// takes over once it signs an `AcceptAuthority` instruction. Proposing
+    /// again replaces any pending nominee.
+    ///
+    /// The pending authority is kept in the table's extension account, which
+    /// is created on first use. Funding account and system program account
+    /// references are only required if the extension account must be created.
+    ///
+    /// If the authority is a multisig, its signers follow the last account
+    /// passed, so the optional accounts before them may still be left out.
+    ///
+    /// # Account references
+    ///   0. `[]` Address lookup table account
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Extension account of the lookup table
+    ///   3. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the extension
+    ///      account
+    ///   4. `[OPTIONAL]` System program for CPI.
+    ///   5. ..5+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    ProposeAuthority { new_authority: Pubkey },
+
+    /// Accept a pending authority handover, making the signer the new
+    /// authority of the address lookup table. This is how a multisig account
+    /// becomes the authority of a table.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account
+    ///   1. `[SIGNER]` Pending authority
+    ///   2. `[WRITE]` Extension account of the lookup table
+    ///   3. ..3+M `[SIGNER]` M signer accounts, if the pending authority is a
+    ///      multisig
+    AcceptAuthority,
+
+    /// Cancel a pending authority handover. May be signed by either the
+    /// current authority or the pending authority.
+    ///
+    /// # Account references
+    ///   0. `[]` Address lookup table account
+    ///   1. `[SIGNER]` Current authority or pending authority
+    ///   2. `[WRITE]` Extension account of the lookup table
+    ///   3. ..3+M `[SIGNER]` M signer accounts, if the signing authority is a
+    ///      multisig
+    CancelAuthorityProposal,
+
+    /// Reactivate an address lookup table that is still deactivating,
+    /// cancelling its deactivation. Fails once the table is fully
+    /// deactivated. A deactivation that is only scheduled is cancelled with
+    /// `CancelScheduledDeactivation` instead.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to reactivate
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[OPTIONAL]` Extension account of the lookup table
+    ///   3. ..3+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    ReactivateLookupTable,
+
+    /// Remove addresses from the end of an active address lookup table and
+    /// return the rent freed by shrinking the account to the recipient.
+    ///
+    /// The table must not have been extended in the last `cool_down_slots`
+    /// slots, and cannot be extended again for as many slots after it is
+    /// truncated, so that transactions compiled against the removed addresses
+    /// never resolve the addresses that later take their indexes. The end of
+    /// the cool-down is kept in the table's extension account, which is
+    /// created on first use and must be passed to `ExtendLookupTable` until
+    /// the cool-down is over. Funding account and system program account
+    /// references are only required if the extension account must be created.
+    ///
+    /// If the authority is a multisig, its signers follow the last account
+    /// passed, so the optional accounts before them may still be left out.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to truncate
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Recipient of reclaimed lamports
+    ///   3. `[WRITE]` Extension account of the lookup table
+    ///   4. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the extension
+    ///      account
+    ///   5. `[OPTIONAL]` System program for CPI.
+    ///   6. ..6+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    TruncateLookupTable {
+        /// Number of addresses the table keeps. Must be less than the current
+        /// number of addresses.
+        new_len: u8,
+        /// Cool-down in slots. Must be at least
+        /// `MIN_TRUNCATION_COOL_DOWN_SLOTS`.
+        cool_down_slots: Slot,
+    },
+
+    /// Create an address lookup table that is populated with an initial list
+    /// of addresses, optionally freezing it in the same instruction. The
+    /// table is derived and funded exactly as in `CreateLookupTable`.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Uninitialized address lookup table account
+    ///   1. `[SIGNER]` Account used to derive and control the new address
+    ///      lookup table.
+    ///   2. `[SIGNER, WRITE]` Account that will fund the new address lookup
+    ///      table.
+    ///   3. `[]` System program for CPI.
+    CreateLookupTableWithAddresses {
+        /// See `CreateLookupTable::recent_slot`
+        recent_slot: Slot,
+        /// See `CreateLookupTable::bump_seed`
+        bump_seed: u8,
+        /// Addresses the new table is created with
+        addresses: Vec<Pubkey>,
+        /// Whether to freeze the table once it is populated
+        freeze: bool,
+    },
+
+    /// Create an address lookup table at an address derived from the
+    /// authority and a caller-chosen seed rather than a recent slot, so the
+    /// address is known up front and is the same on every cluster.
+    ///
+    /// The extension account of the table is created along with it and is
+    /// never closed, so that the address cannot be reused once the table has
+    /// been closed. Creation fails if the extension account already exists.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Uninitialized address lookup table account
+    ///   1. `[SIGNER]` Account used to derive and control the new address
+    ///      lookup table.
+    ///   2. `[SIGNER, WRITE]` Account that will fund the new address lookup
+    ///      table and its extension account.
+    ///   3. `[WRITE]` Uninitialized extension account of the lookup table
+    ///   4. `[]` System program for CPI.
+    CreateLookupTableWithSeed {
+        /// Caller-chosen seed used in the derivation path, along with the
+        /// authority address and a version byte
+        seed: [u8; LOOKUP_TABLE_SEED_LEN],
+        /// Bump seed of the derived table address
+        bump_seed: u8,
+    },
+
+    /// Extend an address lookup table with new addresses that are not
+    /// already in the table. Addresses are compared against the existing
+    /// entries and against earlier addresses in the same batch.
+    ///
+    /// Accounts are the same as in `ExtendLookupTable`.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to extend
+    ///   1. `[SIGNER]` Current authority or extender
+    ///   2. `[OPTIONAL]` Extension account of the lookup table, if signed by
+    ///      the extender or while a truncation cool-down is pending
+    ///   3. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the table
+    ///      reallocation
+    ///   4. `[OPTIONAL]` System program for CPI.
+    ExtendLookupTableUnique {
+        /// Addresses to append to the table
+        new_addresses: Vec<Pubkey>,
+        /// If set, duplicate addresses are left out, even from a full
+        /// table. Otherwise the instruction fails on the first duplicate.
+        skip_duplicates: bool,
+    },
+
+    /// Allow an account other than the authority to extend an address lookup
+    /// table. The extender cannot freeze, deactivate, close or otherwise
+    /// manage the table. Setting an extender replaces the previous one.
+    ///
+    /// The extender is kept in the table's extension account, which is
+    /// created on first use. Funding account and system program account
+    /// references are only required if the extension account must be created.
+    ///
+    /// If the authority is a multisig, its signers follow the last account
+    /// passed, so the optional accounts before them may still be left out.
+    ///
+    /// # Account references
+    ///   0. `[]` Address lookup table account
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Extension account of the lookup table
+    ///   3. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the extension
+    ///      account
+    ///   4. `[OPTIONAL]` System program for CPI.
+    ///   5. ..5+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    SetExtender { extender: Pubkey },
+
+    /// Revoke the extender of an address lookup table.
+    ///
+    /// # Account references
+    ///   0. `[]` Address lookup table account
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Extension account of the lookup table
+    ///   3. ..3+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    RemoveExtender,
+
+    /// Initialize an M-of-N multisig account that can act as the authority of
+    /// address lookup tables. Once it is the authority, freezing, extending,
+    /// deactivating and closing a table require M of its N signers instead
+    /// of a single signature.
+    ///
+    /// The multisig account must be allocated to `LookupTableMultisig::LEN`
+    /// bytes, assigned to this program and rent exempt beforehand, usually
+    /// with a system program `CreateAccount` instruction in the same
+    /// transaction.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Uninitialized multisig account
+    ///   1. ..1+N `[]` Signer accounts, at most `MAX_MULTISIG_SIGNERS`
+    InitializeMultisig {
+        /// Number of signatures required to approve an instruction
+        m: u8,
+    },
+
+    /// Resolve addresses of an address lookup table by index, with the same
+    /// semantics the runtime applies when loading addresses for a
+    /// transaction. The table must not be fully deactivated and addresses
+    /// appended in the current slot cannot be resolved yet.
+    ///
+    /// The resolved addresses are returned through the return data as the
+    /// concatenation of their 32-byte keys, in the order of `indexes`.
+    ///
+    /// # Account references
+    ///   0. `[]` Address lookup table account
+    ResolveAddresses {
+        /// Indexes of the addresses to resolve, at most
+        /// `MAX_RESOLVED_ADDRESSES`
+        indexes: Vec<u8>,
+    },
+
+    /// Set the human-readable name, URI and tags of an address lookup table.
+    /// Setting metadata replaces all previous values.
+    ///
+    /// The metadata is kept in a separate account derived from the table
+    /// address, which is created on first use. Funding account and system
+    /// program account references are only required if the metadata account
+    /// must be created.
+    ///
+    /// If the authority is a multisig, its signers follow the last account
+    /// passed, so the optional accounts before them may still be left out.
+    ///
+    /// # Account references
+    ///   0. `[]` Address lookup table account
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Metadata account of the lookup table
+    ///   3. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the metadata
+    ///      account
+    ///   4. `[OPTIONAL]` System program for CPI.
+    ///   5. ..5+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    SetMetadata {
+        /// Name of the table, at most `MAX_METADATA_NAME_LEN` bytes
+        name: String,
+        /// URI of the table, at most `MAX_METADATA_URI_LEN` bytes
+        uri: String,
+        /// At most `MAX_METADATA_TAGS` non-empty tags of at most
+        /// `MAX_METADATA_TAG_LEN` bytes each
+        tags: Vec<String>,
+    },
+
+    /// Upgrade the per-table state of an address lookup table to the current
+    /// version. The table account itself is never modified, so it stays
+    /// readable by the runtime throughout.
+    ///
+    /// Tables without an extension account get one, and extension accounts
+    /// written with an older layout are resized and upgraded in place,
+    /// keeping the pending authority and extender. Tables that are already
+    /// current are left unchanged. Anyone may migrate a table. Funding account
+    /// and system program account references are only required if the
+    /// extension account must be created or grown.
+    ///
+    /// # Account references
+    ///   0. `[]` Address lookup table account
+    ///   1. `[WRITE]` Extension account of the lookup table
+    ///   2. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the extension
+    ///      account
+    ///   3. `[OPTIONAL]` System program for CPI.
+    MigrateLookupTable,
+
+    /// Schedule deactivation of an address lookup table at a future slot, so
+    /// that users of the table are notified on-chain in advance. Once the
+    /// slot is reached, anyone may deactivate the table with
+    /// `ExecuteScheduledDeactivation`. To deactivate at the start of an epoch,
+    /// schedule the first slot of that epoch. Scheduling again replaces the
+    /// previous schedule.
+    ///
+    /// The schedule is kept in the table's extension account, which is
+    /// created on first use, until it is executed or cancelled. Funding
+    /// account and system program account references are only required if
+    /// the extension account must be created.
+    ///
+    /// If the authority is a multisig, its signers follow the last account
+    /// passed, so the optional accounts before them may still be left out.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Extension account of the lookup table
+    ///   3. `[SIGNER, WRITE, OPTIONAL]` Account that will fund the extension
+    ///      account
+    ///   4. `[OPTIONAL]` System program for CPI.
+    ///   5. ..5+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    ScheduleDeactivation {
+        /// Slot from which the table may be deactivated. Must be later than
+        /// the current slot.
+        slot: Slot,
+    },
+
+    /// Cancel the scheduled deactivation of an address lookup table.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account
+    ///   1. `[SIGNER]` Current authority
+    ///   2. `[WRITE]` Extension account of the lookup table
+    ///   3. ..3+M `[SIGNER]` M signer accounts, if the authority is a multisig
+    CancelScheduledDeactivation,
+
+    /// Deactivate an address lookup table whose scheduled deactivation slot
+    /// has been reached. The table is deactivated in the current slot, exactly
+    /// as with `DeactivateLookupTable`, and the schedule is cleared. No
+    /// signature is required.
+    ///
+    /// The schedule of a frozen table, left over from before freezing cleared
+    /// it, is discarded instead, leaving the table active.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Address lookup table account to deactivate
+    ///   1. `[WRITE]` Extension account of the lookup table
+    ExecuteScheduledDeactivation,
+
+    /// Create an address lookup table populated with a range of addresses
+    /// copied from an existing table. The source table may have any status,
+    /// including frozen or deactivated, and is left unchanged. The new table
+    /// is derived and funded exactly as in `CreateLookupTable`, and its
+    /// addresses become usable in the next slot.
+    ///
+    /// Sets `ReturnData::CreateLookupTable` as the return data.
+    ///
+    /// # Account references
+    ///   0. `[WRITE]` Uninitialized address lookup table account
+    ///   1. `[SIGNER]` Account used to derive and control the new address
+    ///      lookup table.
+    ///   2. `[SIGNER, WRITE]` Account that will fund the new address lookup
+    ///      table.
+    ///   3. `[]` Address lookup table account to copy addresses from
+    ///   4. `[]` System program for CPI.
+    CloneLookupTable {
+        /// See `CreateLookupTable::recent_slot`
+        recent_slot: Slot,
+        /// See `CreateLookupTable::bump_seed`
+        bump_seed: u8,
+        /// Index of the first address to copy from the source table
+        start_index: u8,
+        /// Number of addresses to copy, at least one
+        num_addresses: u16,
+    },
+
+    /// Deactivate several address lookup tables controlled by the same
+    /// authority. Each table is checked as in `DeactivateLookupTable`, and
+    /// the instruction fails if any table fails, logging the index of the
+    /// offending table.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Current authority of every table
+    ///   1. ..1+N `[WRITE]` N address lookup table accounts to deactivate
+    ///   1+N. ..1+N+M `[SIGNER]` M signer accounts, if the authority is a
+    ///      multisig
+    DeactivateLookupTables {
+        /// Number of address lookup table accounts, N
+        num_lookup_tables: u8,
+    },
+
+    /// Close several address lookup tables controlled by the same authority,
+    /// draining the lamports of all of them to a single recipient. Each table
+    /// is checked as in `CloseLookupTable`, and the instruction fails if any
+    /// table fails, logging the index of the offending table.
+    ///
+    /// Each table account may be followed by its metadata and extension
+    /// accounts, in that order, to close them along with the table, exactly
+    /// as in `CloseLookupTable`. Either may be omitted for any table.
+    ///
+    /// # Account references
+    ///   0. `[SIGNER]` Current authority of every table
+    ///   1. `[WRITE]` Recipient of closed account lamports
+    ///   2. ..2+N `[WRITE]` N address lookup table accounts to close, each
+    ///      optionally followed by:
+    ///      - `[WRITE, OPTIONAL]` Metadata account of the lookup table
+    ///      - `[WRITE, OPTIONAL]` Extension account of the lookup table
+    ///   2+N. ..2+N+M `[SIGNER]` M signer accounts, if the authority is a
+    ///      multisig, following the last table and its optional accounts
+    CloseLookupTables {
+        /// Number of address lookup table accounts, N
+        num_lookup_tables: u8,
+    },
+}
+
+/// The maximum number of addresses a `ResolveAddresses` instruction can
+/// return, bounded by the size of the return data
+pub const MAX_RESOLVED_ADDRESSES: usize = MAX_RETURN_DATA / PUBKEY_BYTES;
+
+/// Derives the address of an address table account from a wallet address and
+/// a recent block's slot.
+pub fn derive_lookup_table_address(
+    authority_address: &Pubkey,
+    recent_block_slot: Slot,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[authority_address.as_ref(), &recent_block_slot.to_le_bytes()],
+        &crate::id(),
+    )
+}
+
+/// Derives the address of a seed-derived address table account from a wallet
+/// address and a caller-chosen seed.
+pub fn derive_lookup_table_address_with_seed(
+    authority_address: &Pubkey,
+    seed: &[u8; LOOKUP_TABLE_SEED_LEN],
+) -> (Pubkey, u8) {
+    find_lookup_table_address_with_seed(authority_address, seed, &crate::id())
+}
+
+/// Derives the address of the extension account of an address lookup table.
+pub fn derive_lookup_table_extension_address(lookup_table_address: &Pubkey) -> (Pubkey, u8) {
+    find_extension_address(lookup_table_address, &crate::id())
+}
+
+/// Derives the address of the metadata account of an address lookup table.
+pub fn derive_lookup_table_metadata_address(lookup_table_address: &Pubkey) -> (Pubkey, u8) {
+    find_metadata_address(lookup_table_address, &crate::id())
+}
+
+/// Constructs an instruction to create a table account and returns
+/// the instruction and the table account's derived address.
+fn create_lookup_table_common(
+    authority_address: Pubkey,
+    payer_address: Pubkey,
+    recent_slot: Slot,
+    authority_is_signer: bool,
+) -> (Instruction, Pubkey) {
+    let (lookup_table_address, bump_seed) =
+        derive_lookup_table_address(&authority_address, recent_slot);
+    let instruction = Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CreateLookupTable {
+            recent_slot,
+            bump_seed,
+        },
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, authority_is_signer),
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+
+    (instruction, lookup_table_address)
+}
+
+/// Constructs an instruction to create a table account and returns
+/// the instruction and the table account's derived address.
+///
+/// # Note
+///
+/// This instruction requires the authority to be a signer, which is only
+/// necessary on clusters where the
+/// `relax_authority_signer_check_for_lookup_table_creation` feature is not
+/// active.
+pub fn create_lookup_table_signed(
+    authority_address: Pubkey,
+    payer_address: Pubkey,
+    recent_slot: Slot,
+) -> (Instruction, Pubkey) {
+    create_lookup_table_common(authority_address, payer_address, recent_slot, true)
+}
+
+/// Constructs an instruction to create a table account and returns
+/// the instruction and the table account's derived address.
+pub fn create_lookup_table(
+    authority_address: Pubkey,
+    payer_address: Pubkey,
+    recent_slot: Slot,
+) -> (Instruction, Pubkey) {
+    create_lookup_table_common(authority_address, payer_address, recent_slot, false)
+}
+
+/// Constructs an instruction to create a table account populated with
+/// `addresses`, frozen if `freeze` is set, and returns the instruction and the
+/// table account's derived address.
+pub fn create_lookup_table_with_addresses(
+    authority_address: Pubkey,
+    payer_address: Pubkey,
+    recent_slot: Slot,
+    addresses: Vec<Pubkey>,
+    freeze: bool,
+) -> (Instruction, Pubkey) {
+    let (lookup_table_address, bump_seed) =
+        derive_lookup_table_address(&authority_address, recent_slot);
+    let instruction = Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CreateLookupTableWithAddresses {
+            recent_slot,
+            bump_seed,
+            addresses,
+            freeze,
+        },
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+
+    (instruction, lookup_table_address)
+}
+
+/// Constructs an instruction to create a table account populated with
+/// `num_addresses` addresses of the source table, starting at `start_index`,
+/// and returns the instruction and the new table account's derived address.
+pub fn clone_lookup_table(
+    source_lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    payer_address: Pubkey,
+    recent_slot: Slot,
+    start_index: u8,
+    num_addresses: u16,
+) -> (Instruction, Pubkey) {
+    let (lookup_table_address, bump_seed) =
+        derive_lookup_table_address(&authority_address, recent_slot);
+    let instruction = Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CloneLookupTable {
+            recent_slot,
+            bump_seed,
+            start_index,
+            num_addresses,
+        },
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(source_lookup_table_address, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+
+    (instruction, lookup_table_address)
+}
+
+/// Constructs an instruction to create a table account at an address derived
+/// from `seed` and returns the instruction and the table account's derived
+/// address.
+pub fn create_lookup_table_with_seed(
+    authority_address: Pubkey,
+    payer_address: Pubkey,
+    seed: [u8; LOOKUP_TABLE_SEED_LEN],
+) -> (Instruction, Pubkey) {
+    let (lookup_table_address, bump_seed) =
+        derive_lookup_table_address_with_seed(&authority_address, &seed);
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let instruction = Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CreateLookupTableWithSeed { seed, bump_seed },
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new(extension_address, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+    );
+
+    (instruction, lookup_table_address)
+}
+
+/// Constructs an instruction that freezes an address lookup
+/// table so that it can never be closed or extended again. Empty
+/// lookup tables cannot be frozen.
+pub fn freeze_lookup_table(lookup_table_address: Pubkey, authority_address: Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::FreezeLookupTable,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+        ],
+    )
+}
+
+/// Constructs an instruction that freezes an address lookup table and clears
+/// its scheduled deactivation, which requires passing its extension account.
+pub fn freeze_lookup_table_with_extension(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let mut instruction = freeze_lookup_table(lookup_table_address, authority_address);
+    instruction
+        .accounts
+        .push(AccountMeta::new(extension_address, false));
+    instruction
+}
+
+/// Constructs an instruction which extends an address lookup
+/// table account with new addresses.
+pub fn extend_lookup_table(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    payer_address: Option<Pubkey>,
+    new_addresses: Vec<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(lookup_table_address, false),
+        AccountMeta::new_readonly(authority_address, true),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::ExtendLookupTable { new_addresses },
+        accounts,
+    )
+}
+
+/// Constructs an instruction in which the extender of an address lookup table
+/// extends it with new addresses.
+pub fn extend_lookup_table_as_extender(
+    lookup_table_address: Pubkey,
+    extender_address: Pubkey,
+    payer_address: Option<Pubkey>,
+    new_addresses: Vec<Pubkey>,
+) -> Instruction {
+    let mut instruction = extend_lookup_table(
+        lookup_table_address,
+        extender_address,
+        payer_address,
+        new_addresses,
+    );
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    instruction
+        .accounts
+        .insert(2, AccountMeta::new_readonly(extension_address, false));
+    instruction
+}
+
+/// Constructs an instruction which extends an address lookup table account
+/// with the new addresses that it does not contain yet. Duplicates are
+/// skipped if `skip_duplicates` is set and rejected otherwise.
+pub fn extend_lookup_table_unique(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    payer_address: Option<Pubkey>,
+    new_addresses: Vec<Pubkey>,
+    skip_duplicates: bool,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(lookup_table_address, false),
+        AccountMeta::new_readonly(authority_address, true),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::ExtendLookupTableUnique {
+            new_addresses,
+            skip_duplicates,
+        },
+        accounts,
+    )
+}
+
+/// Constructs an instruction that deactivates an address lookup
+/// table so that it cannot be extended again and will be unusable
+/// and eligible for closure after a short amount of time.
+pub fn deactivate_lookup_table(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::DeactivateLookupTable,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+        ],
+    )
+}
+
+/// Returns an instruction that closes an address lookup table
+/// account. The account will be deallocated and the lamports
+/// will be drained to the recipient address.
+pub fn close_lookup_table(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    recipient_address: Pubkey,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CloseLookupTable,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+            AccountMeta::new(recipient_address, false),
+        ],
+    )
+}
+
+/// Returns an instruction that closes an address lookup table account along
+/// with its metadata and extension accounts, draining the lamports of all of
+/// them to the recipient address. Accounts the table never created are
+/// skipped.
+pub fn close_lookup_table_with_accounts(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    recipient_address: Pubkey,
+) -> Instruction {
+    let (metadata_address, _) = derive_lookup_table_metadata_address(&lookup_table_address);
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let mut instruction =
+        close_lookup_table(lookup_table_address, authority_address, recipient_address);
+    instruction.accounts.extend([
+        AccountMeta::new(metadata_address, false),
+        AccountMeta::new(extension_address, false),
+    ]);
+    instruction
+}
+
+/// Constructs an instruction that deactivates several address lookup tables
+/// controlled by the same authority.
+///
+/// # Panics
+///
+/// Panics if more than `u8::MAX` lookup tables are given.
+pub fn deactivate_lookup_tables(
+    authority_address: Pubkey,
+    lookup_table_addresses: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(authority_address, true)];
+    accounts.extend(
+        lookup_table_addresses
+            .iter()
+            .map(|lookup_table_address| AccountMeta::new(*lookup_table_address, false)),
+    );
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::DeactivateLookupTables {
+            num_lookup_tables: num_lookup_tables(lookup_table_addresses),
+        },
+        accounts,
+    )
+}
+
+/// Returns an instruction that closes several address lookup tables
+/// controlled by the same authority, draining the lamports of all of them to
+/// the recipient address. Their metadata and extension accounts are left
+/// open, see `close_lookup_tables_with_accounts`.
+///
+/// # Panics
+///
+/// Panics if more than `u8::MAX` lookup tables are given.
+pub fn close_lookup_tables(
+    authority_address: Pubkey,
+    recipient_address: Pubkey,
+    lookup_table_addresses: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(authority_address, true),
+        AccountMeta::new(recipient_address, false),
+    ];
+    accounts.extend(
+        lookup_table_addresses
+            .iter()
+            .map(|lookup_table_address| AccountMeta::new(*lookup_table_address, false)),
+    );
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CloseLookupTables {
+            num_lookup_tables: num_lookup_tables(lookup_table_addresses),
+        },
+        accounts,
+    )
+}
+
+/// Constructs an instruction that closes several address lookup tables along
+/// with their metadata and extension accounts, draining all of them to a
+/// single recipient.
+///
+/// # Panics
+///
+/// Panics if more than `u8::MAX` lookup tables are given.
+pub fn close_lookup_tables_with_accounts(
+    authority_address: Pubkey,
+    recipient_address: Pubkey,
+    lookup_table_addresses: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(authority_address, true),
+        AccountMeta::new(recipient_address, false),
+    ];
+    for lookup_table_address in lookup_table_addresses {
+        let (metadata_address, _) = derive_lookup_table_metadata_address(lookup_table_address);
+        let (extension_address, _) = derive_lookup_table_extension_address(lookup_table_address);
+        accounts.extend([
+            AccountMeta::new(*lookup_table_address, false),
+            AccountMeta::new(metadata_address, false),
+            AccountMeta::new(extension_address, false),
+        ]);
+    }
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CloseLookupTables {
+            num_lookup_tables: num_lookup_tables(lookup_table_addresses),
+        },
+        accounts,
+    )
+}
+
+fn num_lookup_tables(lookup_table_addresses: &[Pubkey]) -> u8 {
+    u8::try_from(lookup_table_addresses.len()).expect("at most u8::MAX lookup tables")
+}
+
+/// Constructs an instruction that nominates a new authority for an address
+/// lookup table. The payer is only needed when the table's extension account
+/// does not exist yet.
+pub fn propose_authority(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    new_authority_address: Pubkey,
+    payer_address: Option<Pubkey>,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(lookup_table_address, false),
+        AccountMeta::new_readonly(authority_address, true),
+        AccountMeta::new(extension_address, false),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::ProposeAuthority {
+            new_authority: new_authority_address,
+        },
+        accounts,
+    )
+}
+
+/// Constructs an instruction in which the pending authority of an address
+/// lookup table accepts the handover.
+pub fn accept_authority(
+    lookup_table_address: Pubkey,
+    new_authority_address: Pubkey,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::AcceptAuthority,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(new_authority_address, true),
+            AccountMeta::new(extension_address, false),
+        ],
+    )
+}
+
+/// Constructs an instruction that cancels a pending authority handover. The
+/// signer may be either the current or the pending authority.
+pub fn cancel_authority_proposal(
+    lookup_table_address: Pubkey,
+    signer_address: Pubkey,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CancelAuthorityProposal,
+        vec![
+            AccountMeta::new_readonly(lookup_table_address, false),
+            AccountMeta::new_readonly(signer_address, true),
+            AccountMeta::new(extension_address, false),
+        ],
+    )
+}
+
+/// Constructs an instruction that reactivates an address lookup table
+/// account which has been deactivated but is not yet closable.
+pub fn reactivate_lookup_table(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::ReactivateLookupTable,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+            AccountMeta::new_readonly(extension_address, false),
+        ],
+    )
+}
+
+/// Constructs an instruction that truncates an address lookup table account
+/// to `new_len` addresses, draining the freed rent to the recipient address.
+/// The payer is only needed when the table's extension account does not
+/// exist yet.
+pub fn truncate_lookup_table(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    recipient_address: Pubkey,
+    new_len: u8,
+    cool_down_slots: Slot,
+    payer_address: Option<Pubkey>,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let mut accounts = vec![
+        AccountMeta::new(lookup_table_address, false),
+        AccountMeta::new_readonly(authority_address, true),
+        AccountMeta::new(recipient_address, false),
+        AccountMeta::new(extension_address, false),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::TruncateLookupTable {
+            new_len,
+            cool_down_slots,
+        },
+        accounts,
+    )
+}
+
+/// Constructs an instruction that sets the extender of an address lookup
+/// table. The payer is only needed when the table's extension account does not
+/// exist yet.
+pub fn set_extender(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    extender_address: Pubkey,
+    payer_address: Option<Pubkey>,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(lookup_table_address, false),
+        AccountMeta::new_readonly(authority_address, true),
+        AccountMeta::new(extension_address, false),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::SetExtender {
+            extender: extender_address,
+        },
+        accounts,
+    )
+}
+
+/// Constructs an instruction that removes the extender of an address lookup
+/// table.
+pub fn remove_extender(lookup_table_address: Pubkey, authority_address: Pubkey) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::RemoveExtender,
+        vec![
+            AccountMeta::new_readonly(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+            AccountMeta::new(extension_address, false),
+        ],
+    )
+}
+
+/// Constructs an instruction that initializes an `m`-of-`signers.len()`
+/// multisig account. The account must already be allocated and assigned to
+/// this program.
+pub fn initialize_multisig(
+    multisig_address: Pubkey,
+    signer_addresses: &[Pubkey],
+    m: u8,
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(multisig_address, false)];
+    accounts.extend(
+        signer_addresses
+            .iter()
+            .map(|signer_address| AccountMeta::new_readonly(*signer_address, false)),
+    );
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::InitializeMultisig { m },
+        accounts,
+    )
+}
+
+/// Adapts an instruction built for a single authority to a multisig
+/// authority. The authority, which is always the first signer of the
+/// instruction, no longer signs and the given multisig signers are appended
+/// instead, after any optional accounts the instruction was built with.
+pub fn with_multisig_signers(
+    mut instruction: Instruction,
+    signer_addresses: &[Pubkey],
+) -> Instruction {
+    if let Some(authority) = instruction
+        .accounts
+        .iter_mut()
+        .find(|account| account.is_signer)
+    {
+        authority.is_signer = false;
+    }
+    instruction.accounts.extend(
+        signer_addresses
+            .iter()
+            .map(|signer_address| AccountMeta::new_readonly(*signer_address, true)),
+    );
+    instruction
+}
+
+/// Constructs an instruction that resolves addresses of an address lookup
+/// table by index and returns them through the return data.
+pub fn resolve_addresses(lookup_table_address: Pubkey, indexes: Vec<u8>) -> Instruction {
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::ResolveAddresses { indexes },
+        vec![AccountMeta::new_readonly(lookup_table_address, false)],
+    )
+}
+
+/// Constructs an instruction that sets the metadata of an address lookup
+/// table. The payer is only needed when the table's metadata account does not
+/// exist yet.
+pub fn set_metadata(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    payer_address: Option<Pubkey>,
+    name: String,
+    uri: String,
+    tags: Vec<String>,
+) -> Instruction {
+    let (metadata_address, _) = derive_lookup_table_metadata_address(&lookup_table_address);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(lookup_table_address, false),
+        AccountMeta::new_readonly(authority_address, true),
+        AccountMeta::new(metadata_address, false),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::SetMetadata { name, uri, tags },
+        accounts,
+    )
+}
+
+/// Constructs an instruction that upgrades the per-table state of an address
+/// lookup table to the current version. The payer is only needed when the
+/// table's extension account must be created or grown.
+pub fn migrate_lookup_table(
+    lookup_table_address: Pubkey,
+    payer_address: Option<Pubkey>,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let mut accounts = vec![
+        AccountMeta::new_readonly(lookup_table_address, false),
+        AccountMeta::new(extension_address, false),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::MigrateLookupTable,
+        accounts,
+    )
+}
+
+/// Constructs an instruction that schedules deactivation of an address lookup
+/// table at `slot`. The payer is only needed when the table's extension
+/// account does not exist yet.
+pub fn schedule_deactivation(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    slot: Slot,
+    payer_address: Option<Pubkey>,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let mut accounts = vec![
+        AccountMeta::new(lookup_table_address, false),
+        AccountMeta::new_readonly(authority_address, true),
+        AccountMeta::new(extension_address, false),
+    ];
+
+    if let Some(payer_address) = payer_address {
+        accounts.extend([
+            AccountMeta::new(payer_address, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+    }
+
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::ScheduleDeactivation { slot },
+        accounts,
+    )
+}
+
+/// Constructs an instruction that schedules deactivation of an address lookup
+/// table at the first slot of `epoch`.
+pub fn schedule_deactivation_at_epoch(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+    epoch: Epoch,
+    epoch_schedule: &EpochSchedule,
+    payer_address: Option<Pubkey>,
+) -> Instruction {
+    schedule_deactivation(
+        lookup_table_address,
+        authority_address,
+        epoch_schedule.get_first_slot_in_epoch(epoch),
+        payer_address,
+    )
+}
+
+/// Constructs an instruction that cancels the scheduled deactivation of an
+/// address lookup table.
+pub fn cancel_scheduled_deactivation(
+    lookup_table_address: Pubkey,
+    authority_address: Pubkey,
+) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::CancelScheduledDeactivation,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+            AccountMeta::new(extension_address, false),
+        ],
+    )
+}
+
+/// Constructs an instruction that deactivates an address lookup table whose
+/// scheduled deactivation slot has been reached.
+pub fn execute_scheduled_deactivation(lookup_table_address: Pubkey) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    Instruction::new_with_bincode(
+        crate::id(),
+        &AddressLookupInstruction::ExecuteScheduledDeactivation,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new(extension_address, false),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_program::address_lookup_table::instruction::{self as sdk, ProgramInstruction},
+    };
+
+    fn assert_same_encoding(instruction: AddressLookupInstruction, expected: ProgramInstruction) {
+        assert_eq!(
+            bincode::serialize(&instruction).unwrap(),
+            bincode::serialize(&expected).unwrap(),
+        );
+        assert_eq!(
+            bincode::deserialize::<AddressLookupInstruction>(
+                &bincode::serialize(&expected).unwrap()
+            )
+            .unwrap(),
+            instruction,
+        );
+    }
+
+    #[test]
+    fn test_instruction_encoding_matches_sdk() {
+        let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+        assert_same_encoding(
+            AddressLookupInstruction::CreateLookupTable {
+                recent_slot: 123,
+                bump_seed: 254,
+            },
+            ProgramInstruction::CreateLookupTable {
+                recent_slot: 123,
+                bump_seed: 254,
+            },
+        );
+        assert_same_encoding(
+            AddressLookupInstruction::FreezeLookupTable,
+            ProgramInstruction::FreezeLookupTable,
+        );
+        assert_same_encoding(
+            AddressLookupInstruction::ExtendLookupTable {
+                new_addresses: new_addresses.clone(),
+            },
+            ProgramInstruction::ExtendLookupTable { new_addresses },
+        );
+        assert_same_encoding(
+            AddressLookupInstruction::DeactivateLookupTable,
+            ProgramInstruction::DeactivateLookupTable,
+        );
+        assert_same_encoding(
+            AddressLookupInstruction::CloseLookupTable,
+            ProgramInstruction::CloseLookupTable,
+        );
+    }
+
+    #[test]
+    fn test_builders_match_sdk() {
+        let lookup_table_address = Pubkey::new_unique();
+        let authority_address = Pubkey::new_unique();
+        let payer_address = Pubkey::new_unique();
+        let recent_slot = 123;
+
+        assert_eq!(
+            derive_lookup_table_address(&authority_address, recent_slot),
+            sdk::derive_lookup_table_address(&authority_address, recent_slot),
+        );
+        assert_eq!(
+            create_lookup_table(authority_address, payer_address, recent_slot),
+            sdk::create_lookup_table(authority_address, payer_address, recent_slot),
+        );
+        assert_eq!(
+            create_lookup_table_signed(authority_address, payer_address, recent_slot),
+            sdk::create_lookup_table_signed(authority_address, payer_address, recent_slot),
+        );
+        assert_eq!(
+            freeze_lookup_table(lookup_table_address, authority_address),
+            sdk::freeze_lookup_table(lookup_table_address, authority_address),
+        );
+        for payer_address in [None, Some(payer_address)] {
+            let new_addresses = vec![Pubkey::new_unique()];
+            assert_eq!(
+                extend_lookup_table(
+                    lookup_table_address,
+                    authority_address,
+                    payer_address,
+                    new_addresses.clone(),
+                ),
+                sdk::extend_lookup_table(
+                    lookup_table_address,
+                    authority_address,
+                    payer_address,
+                    new_addresses,
+                ),
+            );
+        }
+        assert_eq!(
+            deactivate_lookup_table(lookup_table_address, authority_address),
+            sdk::deactivate_lookup_table(lookup_table_address, authority_address),
+        );
+        assert_eq!(
+            close_lookup_table(lookup_table_address, authority_address, payer_address),
+            sdk::close_lookup_table(lookup_table_address, authority_address, payer_address),
+        );
+    }
+}
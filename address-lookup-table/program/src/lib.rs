@@ -2,13 +2,15 @@
 //!
 //! [np]: https://docs.solana.com/developing/runtime-facilities/programs#address-lookup-table-program
 
+#[cfg(feature = "cpi")]
+pub mod cpi;
 #[cfg(not(feature = "no-entrypoint"))]
 mod entrypoint;
 pub mod error;
+pub mod event;
+pub mod instruction;
 pub mod processor;
+pub mod return_data;
+pub mod state;
 
 solana_program::declare_id!("AddressLookupTab1e1111111111111111111111111");
-
-// Export current sdk types for downstream users building with a different sdk
-// version
-pub use solana_program::address_lookup_table::{instruction, state};
@@ -1,20 +1,26 @@
 //! Program state processor
 
 use {
-    crate::error::AddressLookupError,
+    crate::{
+        error::AddressLookupError,
+        event::{EventKind, LookupTableEvent},
+        instruction::{AddressLookupInstruction, MAX_RESOLVED_ADDRESSES},
+        return_data::ReturnData,
+        state::{
+            find_extension_address, find_metadata_address, AddressLookupTable,
+            AddressLookupTableMut, LookupTableExtension, LookupTableMeta, LookupTableMetadata,
+            LookupTableMultisig, LookupTableStatus, EXTENSION_FLAG_SEED_DERIVED, EXTENSION_SEED,
+            LOOKUP_TABLE_MAX_ADDRESSES, LOOKUP_TABLE_META_SIZE, LOOKUP_TABLE_SEED_LEN,
+            LOOKUP_TABLE_SEED_VERSION, MAX_MULTISIG_SIGNERS, METADATA_SEED,
+            MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        },
+    },
     solana_program::{
         account_info::{next_account_info, AccountInfo},
-        address_lookup_table::{
-            instruction::ProgramInstruction as AddressLookupInstruction,
-            state::{
-                AddressLookupTable, LookupTableMeta, LookupTableStatus, ProgramState,
-                LOOKUP_TABLE_MAX_ADDRESSES, LOOKUP_TABLE_META_SIZE,
-            },
-        },
         clock::{Clock, Slot},
         entrypoint::ProgramResult,
         msg,
-        program::invoke,
+        program::{invoke, invoke_signed, set_return_data},
         program_error::ProgramError,
         program_utils,
         pubkey::{Pubkey, PUBKEY_BYTES},
@@ -23,6 +29,7 @@ use {
         system_instruction,
         sysvar::Sysvar,
     },
+    std::slice::Iter,
 };
 
 /// DOES NOT BELONG IN PROGRAM CRATE
@@ -43,220 +50,1938 @@ where
         .map_err(|_| ProgramError::InvalidInstructionData)
 }
 
-fn process_create_lookup_table(
+/// Copy of a lookup table account's state, detached from the account data
+struct LookupTableState {
+    meta: LookupTableMeta,
+    addresses_len: usize,
+}
+
+/// Reads the state of a lookup table account.
+///
+/// The metadata is copied out so that the account data borrow is released
+/// before returning. Handlers are then free to reallocate the account, invoke
+/// other programs with it, or write state back with
+/// `write_lookup_table_meta` and `write_lookup_table_addresses`.
+fn read_lookup_table(lookup_table_info: &AccountInfo) -> Result<LookupTableState, ProgramError> {
+    let lookup_table_data = lookup_table_info.try_borrow_data()?;
+    let lookup_table = AddressLookupTable::unpack(&lookup_table_data)
+        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToDeserialize.into())?;
+    Ok(LookupTableState {
+        meta: *lookup_table.meta,
+        addresses_len: lookup_table.addresses.len(),
+    })
+}
+
+/// Overwrites the metadata of a lookup table account
+fn write_lookup_table_meta(
+    lookup_table_info: &AccountInfo,
+    lookup_table_meta: &LookupTableMeta,
+) -> Result<(), ProgramError> {
+    let mut lookup_table_data = lookup_table_info.try_borrow_mut_data()?;
+    let lookup_table = AddressLookupTableMut::unpack(&mut lookup_table_data)
+        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToSerialize.into())?;
+    *lookup_table.meta = *lookup_table_meta;
+    Ok(())
+}
+
+/// Writes addresses into a lookup table account, starting at `start_index`.
+/// The account must already be sized to hold them.
+fn write_lookup_table_addresses(
+    lookup_table_info: &AccountInfo,
+    start_index: usize,
+    addresses: &[Pubkey],
+) -> Result<(), ProgramError> {
+    let mut lookup_table_data = lookup_table_info.try_borrow_mut_data()?;
+    let lookup_table = AddressLookupTableMut::unpack(&mut lookup_table_data)
+        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToSerialize.into())?;
+    let end_index = start_index
+        .checked_add(addresses.len())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    lookup_table
+        .addresses
+        .get_mut(start_index..end_index)
+        .ok_or(ProgramError::InvalidAccountData)?
+        .copy_from_slice(addresses);
+    Ok(())
+}
+
+/// Funds, allocates and assigns a program-derived account to this program.
+/// Lamports already held by the account count towards its rent-exempt
+/// balance.
+fn create_pda_account<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    new_account_info: &AccountInfo<'a>,
+    data_len: usize,
+    signer_seeds: &[&[u8]],
+) -> Result<(), ProgramError> {
+    let required_lamports = <Rent as Sysvar>::get()?
+        .minimum_balance(data_len)
+        .max(1)
+        .saturating_sub(new_account_info.lamports());
+
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer_info.key, new_account_info.key, required_lamports),
+            &[payer_info.clone(), new_account_info.clone()],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(new_account_info.key, data_len as u64),
+        &[new_account_info.clone()],
+        &[signer_seeds],
+    )?;
+
+    invoke_signed(
+        &system_instruction::assign(new_account_info.key, program_id),
+        &[new_account_info.clone()],
+        &[signer_seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Checks that `extension_info` is the extension account of the given lookup
+/// table and returns its bump seed
+fn check_extension_address(
+    program_id: &Pubkey,
+    lookup_table_key: &Pubkey,
+    extension_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (derived_extension_key, bump_seed) = find_extension_address(lookup_table_key, program_id);
+    if extension_info.key != &derived_extension_key {
+        msg!(
+            "Extension address must match derived address: {}",
+            derived_extension_key
+        );
+        return Err(AddressLookupError::AddressMismatch.into());
+    }
+    Ok(bump_seed)
+}
+
+/// Finds the extension account of a lookup table among optional accounts,
+/// returning `None` if it was not passed or was never created
+fn find_extension_account<'a, 'b>(
+    program_id: &Pubkey,
+    lookup_table_key: &Pubkey,
+    account_infos: &'a [AccountInfo<'b>],
+) -> Option<&'a AccountInfo<'b>> {
+    let (extension_key, _) = find_extension_address(lookup_table_key, program_id);
+    account_infos
+        .iter()
+        .find(|account_info| account_info.key == &extension_key)
+        .filter(|extension_info| extension_info.owner == program_id)
+}
+
+/// Reads the scheduled deactivation slot of a lookup table from its extension
+/// account, if the extension account is among `account_infos`
+fn read_scheduled_deactivation_slot(
+    program_id: &Pubkey,
+    lookup_table_key: &Pubkey,
+    account_infos: &[AccountInfo],
+) -> Result<Option<Slot>, ProgramError> {
+    let Some(extension_info) = find_extension_account(program_id, lookup_table_key, account_infos)
+    else {
+        return Ok(None);
+    };
+    let extension_data = extension_info.try_borrow_data()?;
+    Ok(LookupTableExtension::unpack(&extension_data)?.scheduled_deactivation_slot())
+}
+
+/// Checks the extension account of a lookup table and creates it if it does
+/// not exist yet, in which case the funding account is taken from
+/// `account_info_iter`
+fn init_extension_account<'a>(
+    program_id: &Pubkey,
+    lookup_table_info: &AccountInfo<'a>,
+    extension_info: &AccountInfo<'a>,
+    account_info_iter: &mut Iter<AccountInfo<'a>>,
+) -> Result<(), ProgramError> {
+    let bump_seed = check_extension_address(program_id, lookup_table_info.key, extension_info)?;
+    if extension_info.owner == program_id {
+        if LookupTableExtension::is_legacy(&extension_info.try_borrow_data()?) {
+            msg!("Extension account must be upgraded with MigrateLookupTable");
+            return Err(AddressLookupError::ExtensionNotMigrated.into());
+        }
+        return Ok(());
+    }
+
+    let payer_info = next_account_info(account_info_iter)?;
+    if !payer_info.is_signer {
+        msg!("Payer account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    create_pda_account(
+        program_id,
+        payer_info,
+        extension_info,
+        LookupTableExtension::LEN,
+        &[EXTENSION_SEED, lookup_table_info.key.as_ref(), &[bump_seed]],
+    )?;
+    extension_info
+        .try_borrow_mut_data()?
+        .copy_from_slice(bytemuck::bytes_of(&LookupTableExtension::new(
+            *lookup_table_info.key,
+        )));
+
+    Ok(())
+}
+
+/// Takes the extension account of a lookup table from `account_info_iter` if
+/// it is the next account, so that it may be left out when not needed
+fn next_extension_account<'a, 'b>(
+    program_id: &Pubkey,
+    lookup_table_key: &Pubkey,
+    account_info_iter: &mut Iter<'a, AccountInfo<'b>>,
+) -> Option<&'a AccountInfo<'b>> {
+    let extension_info = account_info_iter.clone().next()?;
+    let (extension_key, _) = find_extension_address(lookup_table_key, program_id);
+    if extension_info.key != &extension_key || extension_info.owner != program_id {
+        return None;
+    }
+    account_info_iter.next()
+}
+
+/// Checks whether `signer_info` is the extender recorded in a lookup table's
+/// extension account
+fn check_extender(
+    extension_info: Option<&AccountInfo>,
+    signer_info: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    let Some(extension_info) = extension_info else {
+        return Ok(false);
+    };
+    let extension_data = extension_info.try_borrow_data()?;
+    let extension = LookupTableExtension::unpack(&extension_data)?;
+    Ok(Option::<Pubkey>::from(extension.extender) == Some(*signer_info.key))
+}
+
+/// Checks that an authority approved the instruction. An authority that is a
+/// multisig account approves through its signers, which must be among
+/// `multisig_signer_infos`. Any other authority must sign itself.
+///
+/// Callers pass every account after the last required one, so the signers
+/// are found whether or not optional accounts precede them. Optional accounts
+/// only count if they are signers of the multisig, such as a funding account
+/// that is also one of its signers.
+fn check_authority_signer(
+    program_id: &Pubkey,
+    authority_info: &AccountInfo,
+    multisig_signer_infos: Option<&[AccountInfo]>,
+) -> Result<(), ProgramError> {
+    if authority_info.owner != program_id {
+        if !authority_info.is_signer {
+            msg!("Authority account must be a signer");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        return Ok(());
+    }
+
+    let multisig_data = authority_info.try_borrow_data()?;
+    let multisig = LookupTableMultisig::unpack(&multisig_data).map_err(|_| {
+        msg!("Authority account must be a signer");
+        ProgramError::MissingRequiredSignature
+    })?;
+    let multisig_signer_infos = multisig_signer_infos.unwrap_or_default();
+    let num_signatures = multisig
+        .signers()
+        .iter()
+        .filter(|signer| {
+            multisig_signer_infos
+                .iter()
+                .any(|signer_info| signer_info.is_signer && signer_info.key == *signer)
+        })
+        .count();
+    if num_signatures < usize::from(multisig.m) {
+        msg!(
+            "Multisig authority requires {} signatures, found {}",
+            multisig.m,
+            num_signatures,
+        );
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    Ok(())
+}
+
+/// Reports the address and bump seed of a created table through the return
+/// data
+fn set_create_return_data(lookup_table_address: &Pubkey, bump_seed: u8) {
+    set_return_data(
+        &ReturnData::CreateLookupTable {
+            lookup_table_address: *lookup_table_address,
+            bump_seed,
+        }
+        .pack(),
+    );
+}
+
+/// Reports where the new addresses of an extended table start, the new
+/// length and the last extended slot through the return data
+fn set_extend_return_data(
+    old_len: usize,
+    new_len: usize,
+    lookup_table_meta: &LookupTableMeta,
+) -> Result<(), ProgramError> {
+    let appended_len = new_len.saturating_sub(old_len);
+    let start_index = if appended_len > 0 { old_len } else { 0 };
+    set_return_data(
+        &ReturnData::ExtendLookupTable {
+            start_index: u8::try_from(start_index).map_err(|_| ProgramError::InvalidAccountData)?,
+            new_len: u16::try_from(new_len).map_err(|_| ProgramError::InvalidAccountData)?,
+            last_extended_slot: lookup_table_meta.last_extended_slot.into(),
+            appended_len: u16::try_from(appended_len)
+                .map_err(|_| ProgramError::InvalidAccountData)?,
+        }
+        .pack(),
+    );
+    Ok(())
+}
+
+/// Logs an event describing a change to a lookup table, given its metadata
+/// after the change
+fn log_event(
+    kind: EventKind,
+    lookup_table_address: &Pubkey,
+    lookup_table_meta: &LookupTableMeta,
+    slot: Slot,
+    old_len: usize,
+    new_len: usize,
+) -> Result<(), ProgramError> {
+    LookupTableEvent::new(
+        kind,
+        *lookup_table_address,
+        lookup_table_meta,
+        slot,
+        old_len,
+        new_len,
+    )?
+    .log();
+    Ok(())
+}
+
+/// Logs an event describing a change to the extension or metadata account of
+/// a lookup table, which leaves the addresses of the table unchanged
+fn log_account_event(
+    kind: EventKind,
+    lookup_table_address: &Pubkey,
+    lookup_table_meta: &LookupTableMeta,
+    addresses_len: usize,
+    account: Option<Pubkey>,
+) -> Result<(), ProgramError> {
+    let clock = <Clock as Sysvar>::get()?;
+    LookupTableEvent {
+        account,
+        ..LookupTableEvent::new(
+            kind,
+            *lookup_table_address,
+            lookup_table_meta,
+            clock.slot,
+            addresses_len,
+            addresses_len,
+        )?
+    }
+    .log();
+    Ok(())
+}
+
+/// Checks the accounts shared by all create instructions
+fn check_create_accounts(
+    lookup_table_info: &AccountInfo,
+    authority_info: &AccountInfo,
+    payer_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    if read_lookup_table(lookup_table_info).is_ok() {
+        msg!("Table account must not be allocated");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    if !authority_info.is_signer {
+        msg!("Authority account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if !payer_info.is_signer {
+        msg!("Payer account must be a signer");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    Ok(())
+}
+
+fn check_recent_slot(recent_slot: Slot) -> Result<(), ProgramError> {
+    if <SlotHashes as Sysvar>::get()?.get(&recent_slot).is_none() {
+        msg!("{} is not a recent slot", recent_slot);
+        return Err(AddressLookupError::NotRecentSlot.into());
+    }
+    Ok(())
+}
+
+/// Allocates the lookup table at the address derived from
+/// `lookup_table_signer_seeds` with room for `num_addresses` addresses.
+///
+/// Returns `false` without allocating anything if the table account is
+/// already owned by the program.
+fn allocate_lookup_table_account<'a>(
+    program_id: &Pubkey,
+    lookup_table_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    lookup_table_signer_seeds: &[&[u8]],
+    num_addresses: usize,
+) -> Result<bool, ProgramError> {
+    let derived_table_key = Pubkey::create_program_address(lookup_table_signer_seeds, program_id)?;
+
+    if lookup_table_info.key != &derived_table_key {
+        msg!(
+            "Table address must match derived address: {}",
+            derived_table_key
+        );
+        return Err(AddressLookupError::AddressMismatch.into());
+    }
+
+    if lookup_table_info.owner == program_id {
+        return Ok(false);
+    }
+
+    let table_data_len = LOOKUP_TABLE_META_SIZE
+        .checked_add(num_addresses.saturating_mul(PUBKEY_BYTES))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    create_pda_account(
+        program_id,
+        payer_info,
+        lookup_table_info,
+        table_data_len,
+        lookup_table_signer_seeds,
+    )?;
+
+    Ok(true)
+}
+
+/// Allocates a lookup table holding `addresses` at the address derived from
+/// `lookup_table_signer_seeds`, reports it through the return data and
+/// initializes it with `lookup_table_meta`. Addresses become usable in the
+/// next slot, as if an empty table had been extended.
+///
+/// An empty table that is already owned by the program is left unchanged
+/// and `false` is returned, as the native program does. Creating a table
+/// with addresses at an allocated address fails.
+fn init_lookup_table<'a>(
+    program_id: &Pubkey,
+    lookup_table_info: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    lookup_table_signer_seeds: &[&[u8]],
+    bump_seed: u8,
+    mut lookup_table_meta: LookupTableMeta,
+    addresses: &[Pubkey],
+) -> Result<bool, ProgramError> {
+    let allocated = allocate_lookup_table_account(
+        program_id,
+        lookup_table_info,
+        payer_info,
+        lookup_table_signer_seeds,
+        addresses.len(),
+    )?;
+    if !allocated && !addresses.is_empty() {
+        msg!("Table account must not be allocated");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    set_create_return_data(lookup_table_info.key, bump_seed);
+    if !allocated {
+        return Ok(false);
+    }
+
+    let clock = <Clock as Sysvar>::get()?;
+    if !addresses.is_empty() {
+        lookup_table_meta.last_extended_slot = clock.slot.into();
+    }
+
+    let mut lookup_table_data = lookup_table_info.try_borrow_mut_data()?;
+    let lookup_table =
+        AddressLookupTableMut::init(&mut lookup_table_data, lookup_table_meta)
+            .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToSerialize.into())?;
+    lookup_table.addresses.copy_from_slice(addresses);
+
+    log_event(
+        EventKind::Create,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        0,
+        addresses.len(),
+    )?;
+
+    Ok(true)
+}
+
+fn process_create_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    recent_slot: Slot,
+    bump_seed: u8,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+
+    check_create_accounts(lookup_table_info, authority_info, payer_info)?;
+    check_recent_slot(recent_slot)?;
+
+    let recent_slot_bytes = recent_slot.to_le_bytes();
+    let lookup_table_signer_seeds: &[&[u8]] = &[
+        authority_info.key.as_ref(),
+        &recent_slot_bytes,
+        &[bump_seed],
+    ];
+
+    init_lookup_table(
+        program_id,
+        lookup_table_info,
+        payer_info,
+        lookup_table_signer_seeds,
+        bump_seed,
+        LookupTableMeta::new(*authority_info.key),
+        &[],
+    )?;
+
+    Ok(())
+}
+
+fn process_create_lookup_table_with_addresses(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    recent_slot: Slot,
+    bump_seed: u8,
+    addresses: Vec<Pubkey>,
+    freeze: bool,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+
+    if addresses.is_empty() {
+        msg!("Must create with at least one address");
+        return Err(AddressLookupError::EmptyExtend.into());
+    }
+    if addresses.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+        msg!(
+            "Lookup table length {} would exceed max capacity of {}",
+            addresses.len(),
+            LOOKUP_TABLE_MAX_ADDRESSES,
+        );
+        return Err(AddressLookupError::MaxCapacityExceeded.into());
+    }
+
+    check_create_accounts(lookup_table_info, authority_info, payer_info)?;
+    check_recent_slot(recent_slot)?;
+
+    let recent_slot_bytes = recent_slot.to_le_bytes();
+    let lookup_table_signer_seeds: &[&[u8]] = &[
+        authority_info.key.as_ref(),
+        &recent_slot_bytes,
+        &[bump_seed],
+    ];
+
+    let mut lookup_table_meta = LookupTableMeta::new(*authority_info.key);
+    if freeze {
+        lookup_table_meta.set_authority(None);
+    }
+    init_lookup_table(
+        program_id,
+        lookup_table_info,
+        payer_info,
+        lookup_table_signer_seeds,
+        bump_seed,
+        lookup_table_meta,
+        &addresses,
+    )?;
+
+    Ok(())
+}
+
+fn process_clone_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    recent_slot: Slot,
+    bump_seed: u8,
+    start_index: u8,
+    num_addresses: u16,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let source_lookup_table_info = next_account_info(account_info_iter)?;
+
+    if source_lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let addresses = {
+        let source_data = source_lookup_table_info.try_borrow_data()?;
+        let source_lookup_table = AddressLookupTable::unpack(&source_data)
+            .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToDeserialize.into())?;
+        let start_index = usize::from(start_index);
+        let end_index = start_index.saturating_add(usize::from(num_addresses));
+        match source_lookup_table.addresses.get(start_index..end_index) {
+            Some(addresses) if !addresses.is_empty() => addresses.to_vec(),
+            Some(_) => {
+                msg!("Must clone at least one address");
+                return Err(AddressLookupError::InvalidAddressRange.into());
+            }
+            None => {
+                msg!(
+                    "Range {}..{} is out of bounds of the source table's {} addresses",
+                    start_index,
+                    end_index,
+                    source_lookup_table.addresses.len(),
+                );
+                return Err(AddressLookupError::InvalidAddressRange.into());
+            }
+        }
+    };
+
+    check_create_accounts(lookup_table_info, authority_info, payer_info)?;
+    check_recent_slot(recent_slot)?;
+
+    let recent_slot_bytes = recent_slot.to_le_bytes();
+    let lookup_table_signer_seeds: &[&[u8]] = &[
+        authority_info.key.as_ref(),
+        &recent_slot_bytes,
+        &[bump_seed],
+    ];
+
+    init_lookup_table(
+        program_id,
+        lookup_table_info,
+        payer_info,
+        lookup_table_signer_seeds,
+        bump_seed,
+        LookupTableMeta::new(*authority_info.key),
+        &addresses,
+    )?;
+
+    Ok(())
+}
+
+fn process_create_lookup_table_with_seed(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    seed: [u8; LOOKUP_TABLE_SEED_LEN],
+    bump_seed: u8,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
+
+    check_create_accounts(lookup_table_info, authority_info, payer_info)?;
+    let extension_bump_seed =
+        check_extension_address(program_id, lookup_table_info.key, extension_info)?;
+
+    // The extension account of a seed-derived table is never closed, so that
+    // it marks the address as used once the table itself is gone. Like
+    // slot-derived tables, a closed table can then never be recreated.
+    if extension_info.owner == program_id {
+        msg!("Lookup table address was used by a table that has been closed");
+        return Err(AddressLookupError::TableAddressClosed.into());
+    }
+
+    let lookup_table_signer_seeds: &[&[u8]] = &[
+        authority_info.key.as_ref(),
+        &[LOOKUP_TABLE_SEED_VERSION],
+        &seed,
+        &[bump_seed],
+    ];
+
+    if !init_lookup_table(
+        program_id,
+        lookup_table_info,
+        payer_info,
+        lookup_table_signer_seeds,
+        bump_seed,
+        LookupTableMeta::new(*authority_info.key),
+        &[],
+    )? {
+        return Ok(());
+    }
+
+    create_pda_account(
+        program_id,
+        payer_info,
+        extension_info,
+        LookupTableExtension::LEN,
+        &[
+            EXTENSION_SEED,
+            lookup_table_info.key.as_ref(),
+            &[extension_bump_seed],
+        ],
+    )?;
+    let mut extension = LookupTableExtension::new(*lookup_table_info.key);
+    extension.flags = EXTENSION_FLAG_SEED_DERIVED.into();
+    extension_info
+        .try_borrow_mut_data()?
+        .copy_from_slice(bytemuck::bytes_of(&extension));
+
+    Ok(())
+}
+
+fn process_freeze_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(2..))?;
+
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is already frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+    if u64::from(lookup_table_meta.deactivation_slot) != Slot::MAX {
+        msg!("Deactivated tables cannot be frozen");
+        return Err(AddressLookupError::TableDeactivated.into());
+    }
+    if addresses_len == 0 {
+        msg!("Empty lookup tables cannot be frozen");
+        return Err(AddressLookupError::EmptyTable.into());
+    }
+
+    // Nobody could cancel a schedule once the table is frozen
+    let optional_infos = accounts.get(2..).unwrap_or_default();
+    match find_extension_account(program_id, lookup_table_info.key, optional_infos) {
+        Some(extension_info) => {
+            let mut extension_data = extension_info.try_borrow_mut_data()?;
+            let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+            if extension.scheduled_deactivation_slot().is_some() {
+                msg!("Clearing the scheduled deactivation of the lookup table");
+                extension.set_scheduled_deactivation_slot(None);
+            }
+        }
+        None if bool::from(lookup_table_meta.deactivation_scheduled) => {
+            msg!("Extension account is required to clear the scheduled deactivation");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        None => {}
+    }
+
+    lookup_table_meta.deactivation_scheduled = false.into();
+    lookup_table_meta.set_authority(None);
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+
+    log_event(
+        EventKind::Freeze,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        <Clock as Sysvar>::get()?.slot,
+        addresses_len,
+        addresses_len,
+    )
+}
+
+/// How an extend instruction handles addresses that are already in the table
+/// or earlier in the same batch
+#[derive(Clone, Copy, PartialEq)]
+enum DuplicateAddresses {
+    Allow,
+    Skip,
+    Reject,
+}
+
+/// Applies `policy` to `new_addresses`, returning the addresses to append
+fn dedup_new_addresses(
+    lookup_table_info: &AccountInfo,
+    new_addresses: Vec<Pubkey>,
+    policy: DuplicateAddresses,
+) -> Result<Vec<Pubkey>, ProgramError> {
+    if policy == DuplicateAddresses::Allow {
+        return Ok(new_addresses);
+    }
+
+    let lookup_table_data = lookup_table_info.try_borrow_data()?;
+    let lookup_table = AddressLookupTable::unpack(&lookup_table_data)
+        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToDeserialize.into())?;
+
+    let mut unique_addresses = Vec::with_capacity(new_addresses.len());
+    for (batch_index, new_address) in new_addresses.into_iter().enumerate() {
+        let table_index = lookup_table
+            .addresses
+            .iter()
+            .position(|address| address == &new_address);
+        let earlier_batch_index = unique_addresses
+            .iter()
+            .position(|address| address == &new_address);
+        if table_index.is_none() && earlier_batch_index.is_none() {
+            unique_addresses.push(new_address);
+            continue;
+        }
+        if policy == DuplicateAddresses::Reject {
+            match table_index {
+                Some(table_index) => msg!(
+                    "New address at index {} is already in the lookup table at index {}",
+                    batch_index,
+                    table_index,
+                ),
+                None => msg!(
+                    "New address at index {} is repeated in the batch",
+                    batch_index,
+                ),
+            }
+            return Err(AddressLookupError::DuplicateAddress.into());
+        }
+    }
+
+    Ok(unique_addresses)
+}
+
+fn process_extend_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_addresses: Vec<Pubkey>,
+    duplicates: DuplicateAddresses,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let extension_info =
+        next_extension_account(program_id, lookup_table_info.key, account_info_iter);
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(2..))?;
+
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len: old_table_addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key)
+        && !check_extender(extension_info, authority_info)?
+    {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+    if u64::from(lookup_table_meta.deactivation_slot) != Slot::MAX {
+        msg!("Deactivated tables cannot be extended");
+        return Err(AddressLookupError::TableDeactivated.into());
+    }
+
+    // Skipped addresses take no space, so a full table may still be extended
+    // with addresses it already contains
+    let requested_addresses_len = new_addresses.len();
+    let new_addresses = dedup_new_addresses(lookup_table_info, new_addresses, duplicates)?;
+    let clock = <Clock as Sysvar>::get()?;
+    if requested_addresses_len > 0 && new_addresses.is_empty() {
+        msg!("All new addresses are already in the lookup table");
+        log_event(
+            EventKind::Extend,
+            lookup_table_info.key,
+            &lookup_table_meta,
+            clock.slot,
+            old_table_addresses_len,
+            old_table_addresses_len,
+        )?;
+        return set_extend_return_data(
+            old_table_addresses_len,
+            old_table_addresses_len,
+            &lookup_table_meta,
+        );
+    }
+
+    if old_table_addresses_len >= LOOKUP_TABLE_MAX_ADDRESSES {
+        msg!("Lookup table is full and cannot contain more addresses");
+        return Err(AddressLookupError::TableFull.into());
+    }
+
+    if new_addresses.is_empty() {
+        msg!("Must extend with at least one address");
+        return Err(AddressLookupError::EmptyExtend.into());
+    }
+
+    let new_table_address_len = old_table_addresses_len.saturating_add(new_addresses.len());
+    if new_table_address_len > LOOKUP_TABLE_MAX_ADDRESSES {
+        msg!(
+            "Extended lookup table length {} would exceed max capacity of {}",
+            new_table_address_len,
+            LOOKUP_TABLE_MAX_ADDRESSES,
+        );
+        return Err(AddressLookupError::MaxCapacityExceeded.into());
+    }
+
+    if bool::from(lookup_table_meta.truncation_cool_down) {
+        let Some(extension_info) = extension_info else {
+            msg!("Extension account is required to extend a truncated lookup table");
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let extension_data = extension_info.try_borrow_data()?;
+        let cool_down_end_slot = LookupTableExtension::unpack(&extension_data)?
+            .truncation_cool_down_end_slot()
+            .unwrap_or_default();
+        if clock.slot < cool_down_end_slot {
+            msg!(
+                "Truncated lookup table cannot be extended for another {} slots",
+                cool_down_end_slot.saturating_sub(clock.slot),
+            );
+            return Err(AddressLookupError::TableRecentlyTruncated.into());
+        }
+        lookup_table_meta.truncation_cool_down = false.into();
+    }
+
+    let last_extended_slot = u64::from(lookup_table_meta.last_extended_slot);
+    if clock.slot != last_extended_slot {
+        lookup_table_meta.last_extended_slot = clock.slot.into();
+        lookup_table_meta.last_extended_slot_start_index = u8::try_from(old_table_addresses_len)
+            .map_err(|_| {
+                // This is impossible as long as the length of new_addresses
+                // is non-zero and LOOKUP_TABLE_MAX_ADDRESSES == u8::MAX + 1.
+                ProgramError::InvalidAccountData
+            })?;
+    }
+
+    let new_table_data_len = LOOKUP_TABLE_META_SIZE
+        .checked_add(new_table_address_len.saturating_mul(PUBKEY_BYTES))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    lookup_table_info.realloc(new_table_data_len, false)?;
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+    write_lookup_table_addresses(lookup_table_info, old_table_addresses_len, &new_addresses)?;
+
+    // Rent exemption is only enforced once the instruction completes, so the
+    // table may be topped up after it has been resized.
+
+    let required_lamports = <Rent as Sysvar>::get()?
+        .minimum_balance(new_table_data_len)
+        .max(1)
+        .saturating_sub(lookup_table_info.lamports());
+
+    if required_lamports > 0 {
+        let payer_info = next_account_info(account_info_iter)?;
+        if !payer_info.is_signer {
+            msg!("Payer account must be a signer");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        invoke(
+            &system_instruction::transfer(payer_info.key, lookup_table_info.key, required_lamports),
+            &[payer_info.clone(), lookup_table_info.clone()],
+        )?;
+    }
+
+    log_event(
+        EventKind::Extend,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        old_table_addresses_len,
+        new_table_address_len,
+    )?;
+    set_extend_return_data(
+        old_table_addresses_len,
+        new_table_address_len,
+        &lookup_table_meta,
+    )
+}
+
+fn process_deactivate_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(2..))?;
+
+    let clock = <Clock as Sysvar>::get()?;
+    deactivate_lookup_table(lookup_table_info, authority_info, &clock)
+}
+
+/// Deactivates a lookup table in the current slot. The caller checks the
+/// table's owner and that the authority signed.
+fn deactivate_lookup_table(
+    lookup_table_info: &AccountInfo,
+    authority_info: &AccountInfo,
+    clock: &Clock,
+) -> Result<(), ProgramError> {
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+    if u64::from(lookup_table_meta.deactivation_slot) != Slot::MAX {
+        msg!("Lookup table is already deactivated");
+        return Err(AddressLookupError::TableDeactivated.into());
+    }
+
+    lookup_table_meta.deactivation_slot = clock.slot.into();
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+
+    log_event(
+        EventKind::Deactivate,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        addresses_len,
+        addresses_len,
+    )
+}
+
+fn process_close_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(3..))?;
+
+    let scheduled_deactivation_slot = read_scheduled_deactivation_slot(
+        program_id,
+        lookup_table_info.key,
+        account_info_iter.as_slice(),
+    )?;
+    let clock = <Clock as Sysvar>::get()?;
+    let slot_hashes = <SlotHashes as Sysvar>::get()?;
+    close_lookup_table(
+        lookup_table_info,
+        authority_info,
+        recipient_info,
+        scheduled_deactivation_slot,
+        &clock,
+        &slot_hashes,
+    )?;
+
+    close_table_accounts(
+        program_id,
+        lookup_table_info,
+        recipient_info,
+        account_info_iter,
+    )
+}
+
+/// Closes a fully deactivated lookup table, draining its lamports to the
+/// recipient. The caller checks the table's owner and that the authority
+/// signed, and reads the scheduled deactivation slot from the table's
+/// extension account if it was passed.
+fn close_lookup_table(
+    lookup_table_info: &AccountInfo,
+    authority_info: &AccountInfo,
+    recipient_info: &AccountInfo,
+    scheduled_deactivation_slot: Option<Slot>,
+    clock: &Clock,
+    slot_hashes: &SlotHashes,
+) -> Result<(), ProgramError> {
+    if recipient_info.key == lookup_table_info.key {
+        msg!("Lookup table cannot be the recipient of reclaimed lamports");
+        return Err(AddressLookupError::RecipientIsTable.into());
+    }
+
+    let LookupTableState {
+        meta: lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+
+    match lookup_table_meta.status_with_schedule(
+        clock.slot,
+        slot_hashes,
+        scheduled_deactivation_slot,
+    ) {
+        LookupTableStatus::Activated => {
+            msg!("Lookup table is not deactivated");
+            Err(AddressLookupError::TableNotDeactivated)
+        }
+        LookupTableStatus::DeactivationScheduled { remaining_slots } => {
+            msg!(
+                "Lookup table is not deactivated, and is scheduled to be deactivated in {} slots",
+                remaining_slots
+            );
+            Err(AddressLookupError::TableNotDeactivated)
+        }
+        LookupTableStatus::Deactivating { remaining_blocks } => {
+            msg!(
+                "Table cannot be closed until it's fully deactivated in {} blocks",
+                remaining_blocks
+            );
+            Err(AddressLookupError::DeactivationInProgress)
+        }
+        LookupTableStatus::Deactivated => Ok(()),
+    }?;
+
+    // Address Lookup Tables are not reassigned since they are derived from
+    // a particular slot.
+    drain_account(lookup_table_info, recipient_info)?;
+
+    log_event(
+        EventKind::Close,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        addresses_len,
+        0,
+    )
+}
+
+/// Runs `f` on each of the `num_lookup_tables` lookup tables of a batch, taken
+/// from `account_info_iter`, logging the index of the first table that fails
+/// before returning its error. `f` may consume accounts that follow a table.
+fn for_each_lookup_table<'a, 'b>(
+    program_id: &Pubkey,
+    num_lookup_tables: u8,
+    account_info_iter: &mut Iter<'a, AccountInfo<'b>>,
+    mut f: impl FnMut(&'a AccountInfo<'b>, &mut Iter<'a, AccountInfo<'b>>) -> ProgramResult,
+) -> Result<(), ProgramError> {
+    if num_lookup_tables == 0 {
+        msg!("Must pass at least one lookup table");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    }
+    for index in 0..num_lookup_tables {
+        let lookup_table_info = next_account_info(account_info_iter)?;
+        let result = if lookup_table_info.owner != program_id {
+            Err(ProgramError::InvalidAccountOwner)
+        } else {
+            f(lookup_table_info, account_info_iter)
+        };
+        result.map_err(|err| {
+            msg!("Lookup table at index {} failed: {}", index, err);
+            err
+        })?;
+    }
+    Ok(())
+}
+
+fn process_deactivate_lookup_tables(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    num_lookup_tables: u8,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+
+    // Lookup tables never sign, so the multisig signers may be looked up
+    // among all of the accounts that follow the authority
+    check_authority_signer(program_id, authority_info, accounts.get(1..))?;
+
+    let clock = <Clock as Sysvar>::get()?;
+    for_each_lookup_table(
+        program_id,
+        num_lookup_tables,
+        account_info_iter,
+        |lookup_table_info, _| deactivate_lookup_table(lookup_table_info, authority_info, &clock),
+    )
+}
+
+fn process_close_lookup_tables(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    num_lookup_tables: u8,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let authority_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+
+    // Neither lookup tables nor their metadata and extension accounts sign,
+    // so the multisig signers may be looked up among all of the accounts
+    // that follow the recipient
+    check_authority_signer(program_id, authority_info, accounts.get(2..))?;
+
+    let clock = <Clock as Sysvar>::get()?;
+    let slot_hashes = <SlotHashes as Sysvar>::get()?;
+    for_each_lookup_table(
+        program_id,
+        num_lookup_tables,
+        account_info_iter,
+        |lookup_table_info, account_info_iter| {
+            let scheduled_deactivation_slot = read_scheduled_deactivation_slot(
+                program_id,
+                lookup_table_info.key,
+                account_info_iter.as_slice(),
+            )?;
+            close_lookup_table(
+                lookup_table_info,
+                authority_info,
+                recipient_info,
+                scheduled_deactivation_slot,
+                &clock,
+                &slot_hashes,
+            )?;
+            close_table_accounts(
+                program_id,
+                lookup_table_info,
+                recipient_info,
+                account_info_iter,
+            )
+        },
+    )
+}
+
+/// Drains the lamports of an account to the recipient and deallocates it
+fn drain_account(
+    account_info: &AccountInfo,
+    recipient_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let new_recipient_lamports = account_info
+        .lamports()
+        .checked_add(recipient_info.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    **account_info.try_borrow_mut_lamports()? = 0;
+    **recipient_info.try_borrow_mut_lamports()? = new_recipient_lamports;
+    account_info.realloc(0, true)
+}
+
+/// Closes the metadata and extension accounts of a lookup table if they are
+/// the next accounts, in that order, draining their lamports to the
+/// recipient. Either may be omitted, and a passed account that was never
+/// created is skipped. The extension account of a seed-derived table is kept
+/// to mark its address as used. Any other account is left in
+/// `account_info_iter`.
+fn close_table_accounts(
+    program_id: &Pubkey,
+    lookup_table_info: &AccountInfo,
+    recipient_info: &AccountInfo,
+    account_info_iter: &mut Iter<AccountInfo>,
+) -> Result<(), ProgramError> {
+    if account_info_iter.as_slice().is_empty() {
+        return Ok(());
+    }
+    let (metadata_key, _) = find_metadata_address(lookup_table_info.key, program_id);
+    let (extension_key, _) = find_extension_address(lookup_table_info.key, program_id);
+    for key in [metadata_key, extension_key] {
+        let Some(account_info) = account_info_iter.as_slice().first() else {
+            break;
+        };
+        if account_info.key != &key {
+            continue;
+        }
+        account_info_iter.next();
+        if account_info.owner != program_id {
+            continue;
+        }
+        if account_info.key == &extension_key
+            && LookupTableExtension::unpack(&account_info.try_borrow_data()?)?.is_seed_derived()
+        {
+            msg!("Extension account of a seed-derived table is never closed");
+            continue;
+        }
+        if recipient_info.key == account_info.key {
+            msg!("Metadata and extension accounts cannot be the recipient of reclaimed lamports");
+            return Err(AddressLookupError::RecipientIsTable.into());
+        }
+        drain_account(account_info, recipient_info)?;
+    }
+
+    Ok(())
+}
+
+fn process_propose_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_authority: Pubkey,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(3..))?;
+
+    let LookupTableState {
+        meta: lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+
+    init_extension_account(
+        program_id,
+        lookup_table_info,
+        extension_info,
+        account_info_iter,
+    )?;
+
+    let mut extension_data = extension_info.try_borrow_mut_data()?;
+    let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+    extension.pending_authority = Some(new_authority).try_into()?;
+
+    log_account_event(
+        EventKind::ProposeAuthority,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        addresses_len,
+        Some(new_authority),
+    )
+}
+
+fn process_accept_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let new_authority_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, new_authority_info, accounts.get(3..))?;
+
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+
+    check_extension_address(program_id, lookup_table_info.key, extension_info)?;
+    if extension_info.owner != program_id {
+        msg!("Lookup table has no pending authority");
+        return Err(AddressLookupError::NoPendingAuthority.into());
+    }
+
+    {
+        let mut extension_data = extension_info.try_borrow_mut_data()?;
+        let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+        match Option::<Pubkey>::from(extension.pending_authority) {
+            None => {
+                msg!("Lookup table has no pending authority");
+                return Err(AddressLookupError::NoPendingAuthority.into());
+            }
+            Some(pending_authority) if pending_authority != *new_authority_info.key => {
+                return Err(AddressLookupError::IncorrectAuthority.into());
+            }
+            Some(_) => {}
+        }
+        extension.pending_authority = None.try_into()?;
+    }
+
+    lookup_table_meta.set_authority(Some(*new_authority_info.key));
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+
+    log_event(
+        EventKind::ChangeAuthority,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        <Clock as Sysvar>::get()?.slot,
+        addresses_len,
+        addresses_len,
+    )
+}
+
+fn process_cancel_authority_proposal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let signer_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, signer_info, accounts.get(3..))?;
+
+    let LookupTableState {
+        meta: lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    check_extension_address(program_id, lookup_table_info.key, extension_info)?;
+    if extension_info.owner != program_id {
+        msg!("Lookup table has no pending authority");
+        return Err(AddressLookupError::NoPendingAuthority.into());
+    }
+
+    let mut extension_data = extension_info.try_borrow_mut_data()?;
+    let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+    let Some(pending_authority) = Option::<Pubkey>::from(extension.pending_authority) else {
+        msg!("Lookup table has no pending authority");
+        return Err(AddressLookupError::NoPendingAuthority.into());
+    };
+    if lookup_table_meta.authority() != Some(*signer_info.key)
+        && pending_authority != *signer_info.key
+    {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+    extension.pending_authority = None.try_into()?;
+
+    log_account_event(
+        EventKind::CancelAuthorityProposal,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        addresses_len,
+        Some(pending_authority),
+    )
+}
+
+fn process_reactivate_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(2..))?;
+
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+
+    let scheduled_deactivation_slot = read_scheduled_deactivation_slot(
+        program_id,
+        lookup_table_info.key,
+        account_info_iter.as_slice(),
+    )?;
+    let clock = <Clock as Sysvar>::get()?;
+    let slot_hashes = <SlotHashes as Sysvar>::get()?;
+
+    match lookup_table_meta.status_with_schedule(
+        clock.slot,
+        &slot_hashes,
+        scheduled_deactivation_slot,
+    ) {
+        LookupTableStatus::Activated => {
+            msg!("Lookup table is not deactivated");
+            Err(AddressLookupError::TableNotDeactivated)
+        }
+        LookupTableStatus::DeactivationScheduled { .. } => {
+            msg!("Lookup table is not deactivated, cancel its scheduled deactivation instead");
+            Err(AddressLookupError::TableNotDeactivated)
+        }
+        LookupTableStatus::Deactivating { .. } => Ok(()),
+        LookupTableStatus::Deactivated => {
+            msg!("Lookup table is fully deactivated and can no longer be reactivated");
+            Err(AddressLookupError::DeactivationComplete)
+        }
+    }?;
+
+    lookup_table_meta.deactivation_slot = Slot::MAX.into();
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+
+    log_event(
+        EventKind::Reactivate,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        addresses_len,
+        addresses_len,
+    )
+}
+
+fn process_truncate_lookup_table(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_len: u8,
+    cool_down_slots: Slot,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let recipient_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(4..))?;
+
+    if recipient_info.key == lookup_table_info.key {
+        msg!("Lookup table cannot be the recipient of reclaimed lamports");
+        return Err(AddressLookupError::RecipientIsTable.into());
+    }
+
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+    if u64::from(lookup_table_meta.deactivation_slot) != Slot::MAX {
+        msg!("Deactivated tables cannot be truncated");
+        return Err(AddressLookupError::TableDeactivated.into());
+    }
+    if cool_down_slots < MIN_TRUNCATION_COOL_DOWN_SLOTS {
+        msg!(
+            "Truncation cool-down of {} slots must be at least {} slots",
+            cool_down_slots,
+            MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        );
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    lookup_table_meta.last_extended_slot_start_index =
+        new_len.min(lookup_table_meta.last_extended_slot_start_index);
+    let new_len = usize::from(new_len);
+    if new_len >= addresses_len {
+        msg!(
+            "Truncated lookup table length {} must be less than the current length {}",
+            new_len,
+            addresses_len,
+        );
+        return Err(AddressLookupError::InvalidAddressRange.into());
+    }
+
+    // The last address is always part of the removed range, and it was
+    // appended no earlier than the last extension.
+    let clock = <Clock as Sysvar>::get()?;
+    let earliest_truncation_slot =
+        u64::from(lookup_table_meta.last_extended_slot).saturating_add(cool_down_slots);
+    if clock.slot < earliest_truncation_slot {
+        msg!(
+            "Lookup table cannot be truncated for another {} slots",
+            earliest_truncation_slot.saturating_sub(clock.slot),
+        );
+        return Err(AddressLookupError::TruncationTooSoon.into());
+    }
+
+    // Extending is rejected until the end of the cool-down, so the removed
+    // indexes are not reused before transactions compiled against them have
+    // expired. A pending cool-down from an earlier truncation is never
+    // shortened.
+    init_extension_account(
+        program_id,
+        lookup_table_info,
+        extension_info,
+        account_info_iter,
+    )?;
+    {
+        let mut extension_data = extension_info.try_borrow_mut_data()?;
+        let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+        let cool_down_end_slot = clock.slot.saturating_add(cool_down_slots).max(
+            extension
+                .truncation_cool_down_end_slot()
+                .unwrap_or_default(),
+        );
+        extension.set_truncation_cool_down_end_slot(cool_down_end_slot);
+    }
+    lookup_table_meta.truncation_cool_down = true.into();
+
+    let new_table_data_len = LOOKUP_TABLE_META_SIZE
+        .checked_add(new_len.saturating_mul(PUBKEY_BYTES))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    lookup_table_info.realloc(new_table_data_len, false)?;
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+
+    let reclaimed_lamports = lookup_table_info.lamports().saturating_sub(
+        <Rent as Sysvar>::get()?
+            .minimum_balance(new_table_data_len)
+            .max(1),
+    );
+
+    if reclaimed_lamports > 0 {
+        let new_recipient_lamports = recipient_info
+            .lamports()
+            .checked_add(reclaimed_lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        **lookup_table_info.try_borrow_mut_lamports()? = lookup_table_info
+            .lamports()
+            .saturating_sub(reclaimed_lamports);
+        **recipient_info.try_borrow_mut_lamports()? = new_recipient_lamports;
+    }
+
+    log_event(
+        EventKind::Truncate,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        addresses_len,
+        new_len,
+    )
+}
+
+fn process_set_extender(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    extender: Pubkey,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(3..))?;
+
+    let LookupTableState {
+        meta: lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+
+    init_extension_account(
+        program_id,
+        lookup_table_info,
+        extension_info,
+        account_info_iter,
+    )?;
+
+    let mut extension_data = extension_info.try_borrow_mut_data()?;
+    let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+    extension.extender = Some(extender).try_into()?;
+
+    log_account_event(
+        EventKind::SetExtender,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        addresses_len,
+        Some(extender),
+    )
+}
+
+fn process_remove_extender(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
+
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    check_authority_signer(program_id, authority_info, accounts.get(3..))?;
+
+    let LookupTableState {
+        meta: lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
+        return Err(AddressLookupError::LookupTableImmutable.into());
+    }
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
+        return Err(AddressLookupError::IncorrectAuthority.into());
+    }
+
+    check_extension_address(program_id, lookup_table_info.key, extension_info)?;
+    if extension_info.owner != program_id {
+        msg!("Lookup table has no extender");
+        return Err(AddressLookupError::NoExtender.into());
+    }
+
+    let mut extension_data = extension_info.try_borrow_mut_data()?;
+    let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+    let Some(extender) = Option::<Pubkey>::from(extension.extender) else {
+        msg!("Lookup table has no extender");
+        return Err(AddressLookupError::NoExtender.into());
+    };
+    extension.extender = None.try_into()?;
+
+    log_account_event(
+        EventKind::RemoveExtender,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        addresses_len,
+        Some(extender),
+    )
+}
+
+fn process_initialize_multisig(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    recent_slot: Slot,
-    bump_seed: u8,
+    m: u8,
 ) -> Result<(), ProgramError> {
     let account_info_iter = &mut accounts.iter();
-    let lookup_table_info = next_account_info(account_info_iter)?;
-    let authority_info = next_account_info(account_info_iter)?;
-    let payer_info = next_account_info(account_info_iter)?;
+    let multisig_info = next_account_info(account_info_iter)?;
+    let signer_keys: Vec<Pubkey> = account_info_iter.map(|info| *info.key).collect();
 
-    if AddressLookupTable::deserialize(&lookup_table_info.data.borrow()).is_ok() {
-        msg!("Table account must not be allocated");
-        return Err(ProgramError::AccountAlreadyInitialized);
+    if multisig_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
     }
 
-    if !authority_info.is_signer {
-        msg!("Authority account must be a signer");
-        return Err(ProgramError::MissingRequiredSignature);
-    }
-    if !payer_info.is_signer {
-        msg!("Payer account must be a signer");
-        return Err(ProgramError::MissingRequiredSignature);
+    let rent = <Rent as Sysvar>::get()?;
+    if !rent.is_exempt(multisig_info.lamports(), multisig_info.data_len()) {
+        msg!("Multisig account must be rent exempt");
+        return Err(ProgramError::AccountNotRentExempt);
     }
 
-    if <SlotHashes as Sysvar>::get()?.get(&recent_slot).is_none() {
-        msg!("{} is not a recent slot", recent_slot);
-        return Err(ProgramError::InvalidInstructionData);
+    let mut multisig_data = multisig_info.try_borrow_mut_data()?;
+    if multisig_data.len() != LookupTableMultisig::LEN {
+        msg!(
+            "Multisig account data must be {} bytes",
+            LookupTableMultisig::LEN
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if multisig_data.iter().any(|byte| *byte != 0) {
+        msg!("Multisig account must not be initialized");
+        return Err(ProgramError::AccountAlreadyInitialized);
     }
 
-    let derived_table_key = Pubkey::create_program_address(
-        &[
-            authority_info.key.as_ref(),
-            &recent_slot.to_le_bytes(),
-            &[bump_seed],
-        ],
-        program_id,
-    )?;
+    let multisig = LookupTableMultisig::new(m, &signer_keys).map_err(|err| {
+        msg!(
+            "Multisig requires between 1 and {} distinct signers and a threshold of at most the \
+             number of signers",
+            MAX_MULTISIG_SIGNERS,
+        );
+        err
+    })?;
+    multisig_data.copy_from_slice(bytemuck::bytes_of(&multisig));
 
-    if lookup_table_info.key != &derived_table_key {
+    let clock = <Clock as Sysvar>::get()?;
+    LookupTableEvent::new_multisig(*multisig_info.key, clock.slot).log();
+
+    Ok(())
+}
+
+fn process_resolve_addresses(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    indexes: Vec<u8>,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+
+    if indexes.len() > MAX_RESOLVED_ADDRESSES {
         msg!(
-            "Table address must match derived address: {}",
-            derived_table_key
+            "Cannot resolve more than {} addresses at once",
+            MAX_RESOLVED_ADDRESSES
         );
-        return Err(ProgramError::InvalidArgument);
+        return Err(ProgramError::InvalidInstructionData);
     }
 
-    if lookup_table_info.owner == program_id {
-        return Ok(());
+    // Mirror the errors the runtime reports when loading a lookup table for
+    // a transaction
+    if lookup_table_info.lamports() == 0 {
+        return Err(AddressLookupError::LookupTableAccountNotFound.into());
     }
-
-    let table_account_data_len = LOOKUP_TABLE_META_SIZE;
-    let lookup_table_lamports = lookup_table_info.lamports();
-    let required_lamports = <Rent as Sysvar>::get()?
-        .minimum_balance(table_account_data_len)
-        .max(1)
-        .saturating_sub(lookup_table_lamports);
-
-    if required_lamports > 0 {
-        invoke(
-            &system_instruction::transfer(payer_info.key, lookup_table_info.key, required_lamports),
-            &[payer_info.clone(), lookup_table_info.clone()],
-        )?;
+    if lookup_table_info.owner != program_id {
+        return Err(AddressLookupError::InvalidAccountOwner.into());
     }
 
-    invoke(
-        &system_instruction::allocate(lookup_table_info.key, table_account_data_len as u64),
-        &[lookup_table_info.clone()],
-    )?;
+    let lookup_table_data = lookup_table_info.try_borrow_data()?;
+    let lookup_table = AddressLookupTable::unpack(&lookup_table_data)
+        .map_err(|_| AddressLookupError::InvalidAccountData)?;
 
-    invoke(
-        &system_instruction::assign(lookup_table_info.key, program_id),
-        &[lookup_table_info.clone()],
-    )?;
+    let clock = <Clock as Sysvar>::get()?;
+    let slot_hashes = <SlotHashes as Sysvar>::get()?;
+    let addresses = lookup_table.lookup(clock.slot, &indexes, &slot_hashes)?;
 
-    let lookup_table = ProgramState::LookupTable(LookupTableMeta::new(*authority_info.key));
-    let mut data = lookup_table_info.try_borrow_mut_data()?;
-    bincode::serialize_into(&mut *data, &lookup_table)
-        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToSerialize.into())?;
+    set_return_data(bytemuck::cast_slice(&addresses));
 
     Ok(())
 }
 
-fn process_freeze_lookup_table(
+fn process_set_metadata(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    name: String,
+    uri: String,
+    tags: Vec<String>,
 ) -> Result<(), ProgramError> {
     let account_info_iter = &mut accounts.iter();
     let lookup_table_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
 
     if lookup_table_info.owner != program_id {
         return Err(ProgramError::InvalidAccountOwner);
     }
 
-    if !authority_info.is_signer {
-        msg!("Authority account must be a signer");
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    check_authority_signer(program_id, authority_info, accounts.get(3..))?;
 
-    let lookup_table_data = lookup_table_info.data.borrow();
-    let lookup_table = AddressLookupTable::deserialize(&lookup_table_data)
-        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToDeserialize.into())?;
+    let LookupTableState {
+        meta: lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
 
-    if lookup_table.meta.authority.is_none() {
-        msg!("Lookup table is already frozen");
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen");
         return Err(AddressLookupError::LookupTableImmutable.into());
     }
-    if lookup_table.meta.authority != Some(*authority_info.key) {
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
         return Err(AddressLookupError::IncorrectAuthority.into());
     }
-    if lookup_table.meta.deactivation_slot != Slot::MAX {
-        msg!("Deactivated tables cannot be frozen");
-        return Err(ProgramError::InvalidArgument);
+
+    let metadata =
+        LookupTableMetadata::new(*lookup_table_info.key, &name, &uri, &tags).map_err(|_| {
+            msg!("Metadata exceeds the length limits or contains a NUL character");
+            ProgramError::from(AddressLookupError::InvalidMetadata)
+        })?;
+
+    let (metadata_key, bump_seed) = find_metadata_address(lookup_table_info.key, program_id);
+    if metadata_info.key != &metadata_key {
+        msg!(
+            "Metadata address must match derived address: {}",
+            metadata_key
+        );
+        return Err(AddressLookupError::AddressMismatch.into());
     }
-    if lookup_table.addresses.is_empty() {
-        msg!("Empty lookup tables cannot be frozen");
-        return Err(ProgramError::InvalidInstructionData);
+
+    if metadata_info.owner != program_id {
+        let payer_info = next_account_info(account_info_iter)?;
+        if !payer_info.is_signer {
+            msg!("Payer account must be a signer");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        create_pda_account(
+            program_id,
+            payer_info,
+            metadata_info,
+            LookupTableMetadata::LEN,
+            &[METADATA_SEED, lookup_table_info.key.as_ref(), &[bump_seed]],
+        )?;
+    } else {
+        let metadata_data = metadata_info.try_borrow_data()?;
+        LookupTableMetadata::unpack(&metadata_data)?;
     }
 
-    let mut lookup_table_meta = lookup_table.meta;
-    lookup_table_meta.authority = None;
-    AddressLookupTable::overwrite_meta_data(
-        &mut lookup_table_info.try_borrow_mut_data()?,
-        lookup_table_meta,
-    )
-    .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToSerialize.into())?;
+    metadata_info
+        .try_borrow_mut_data()?
+        .copy_from_slice(bytemuck::bytes_of(&metadata));
 
-    Ok(())
+    log_account_event(
+        EventKind::SetMetadata,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        addresses_len,
+        Some(metadata_key),
+    )
 }
 
-fn process_extend_lookup_table(
+fn process_migrate_lookup_table(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    new_addresses: Vec<Pubkey>,
 ) -> Result<(), ProgramError> {
     let account_info_iter = &mut accounts.iter();
     let lookup_table_info = next_account_info(account_info_iter)?;
-    let authority_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
 
     if lookup_table_info.owner != program_id {
         return Err(ProgramError::InvalidAccountOwner);
     }
+    read_lookup_table(lookup_table_info)?;
 
-    if !authority_info.is_signer {
-        msg!("Authority account must be a signer");
-        return Err(ProgramError::MissingRequiredSignature);
-    }
-
-    let lookup_table_data = lookup_table_info.data.borrow();
-    let mut lookup_table = AddressLookupTable::deserialize(&lookup_table_data)
-        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToDeserialize.into())?;
-
-    if lookup_table.meta.authority.is_none() {
-        return Err(AddressLookupError::LookupTableImmutable.into());
-    }
-    if lookup_table.meta.authority != Some(*authority_info.key) {
-        return Err(AddressLookupError::IncorrectAuthority.into());
-    }
-    if lookup_table.meta.deactivation_slot != Slot::MAX {
-        msg!("Deactivated tables cannot be extended");
-        return Err(ProgramError::InvalidArgument);
-    }
-    if lookup_table.addresses.len() >= LOOKUP_TABLE_MAX_ADDRESSES {
-        msg!("Lookup table is full and cannot contain more addresses");
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    if new_addresses.is_empty() {
-        msg!("Must extend with at least one address");
-        return Err(ProgramError::InvalidInstructionData);
-    }
-
-    let new_table_address_len = lookup_table
-        .addresses
-        .len()
-        .saturating_add(new_addresses.len());
-    if new_table_address_len > LOOKUP_TABLE_MAX_ADDRESSES {
-        msg!(
-            "Extended lookup table length {} would exceed max capacity of {}",
-            new_table_address_len,
-            LOOKUP_TABLE_MAX_ADDRESSES,
+    if extension_info.owner != program_id {
+        msg!("Creating extension account");
+        return init_extension_account(
+            program_id,
+            lookup_table_info,
+            extension_info,
+            account_info_iter,
         );
-        return Err(ProgramError::InvalidInstructionData);
     }
+    check_extension_address(program_id, lookup_table_info.key, extension_info)?;
 
-    let clock = <Clock as Sysvar>::get()?;
-    if clock.slot != lookup_table.meta.last_extended_slot {
-        lookup_table.meta.last_extended_slot = clock.slot;
-        lookup_table.meta.last_extended_slot_start_index =
-            u8::try_from(lookup_table.addresses.len()).map_err(|_| {
-                // This is impossible as long as the length of new_addresses
-                // is non-zero and LOOKUP_TABLE_MAX_ADDRESSES == u8::MAX + 1.
-                ProgramError::InvalidAccountData
-            })?;
-    }
+    let extension = {
+        let extension_data = extension_info.try_borrow_data()?;
+        if !LookupTableExtension::is_legacy(&extension_data) {
+            let extension = LookupTableExtension::unpack(&extension_data)?;
+            msg!(
+                "Extension account is already at version {}",
+                extension.version()
+            );
+            return Ok(());
+        }
+        LookupTableExtension::from_legacy(&extension_data)?
+    };
 
-    let lookup_table_meta = lookup_table.meta;
-    let new_table_data_len = LOOKUP_TABLE_META_SIZE
-        .checked_add(new_table_address_len.saturating_mul(PUBKEY_BYTES))
-        .ok_or(ProgramError::ArithmeticOverflow)?;
-    {
-        AddressLookupTable::overwrite_meta_data(
-            &mut lookup_table_info.try_borrow_mut_data()?,
-            lookup_table_meta,
-        )
-        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToSerialize.into())?;
-    }
+    msg!(
+        "Upgrading extension account to version {}",
+        extension.version()
+    );
+    extension_info.realloc(LookupTableExtension::LEN, true)?;
+    extension_info
+        .try_borrow_mut_data()?
+        .copy_from_slice(bytemuck::bytes_of(&extension));
 
     let required_lamports = <Rent as Sysvar>::get()?
-        .minimum_balance(new_table_data_len)
-        .max(1)
-        .saturating_sub(lookup_table_info.lamports());
-
+        .minimum_balance(LookupTableExtension::LEN)
+        .saturating_sub(extension_info.lamports());
     if required_lamports > 0 {
         let payer_info = next_account_info(account_info_iter)?;
         if !payer_info.is_signer {
@@ -264,125 +1989,209 @@ fn process_extend_lookup_table(
             return Err(ProgramError::MissingRequiredSignature);
         }
         invoke(
-            &system_instruction::transfer(payer_info.key, lookup_table_info.key, required_lamports),
-            &[payer_info.clone(), lookup_table_info.clone()],
+            &system_instruction::transfer(payer_info.key, extension_info.key, required_lamports),
+            &[payer_info.clone(), extension_info.clone()],
         )?;
     }
 
     Ok(())
 }
 
-fn process_deactivate_lookup_table(
+fn process_schedule_deactivation(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    slot: Slot,
 ) -> Result<(), ProgramError> {
     let account_info_iter = &mut accounts.iter();
     let lookup_table_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
 
     if lookup_table_info.owner != program_id {
         return Err(ProgramError::InvalidAccountOwner);
     }
 
-    if !authority_info.is_signer {
-        msg!("Authority account must be a signer");
-        return Err(ProgramError::MissingRequiredSignature);
-    }
+    check_authority_signer(program_id, authority_info, accounts.get(3..))?;
 
-    let lookup_table_data = lookup_table_info.data.borrow();
-    let lookup_table = AddressLookupTable::deserialize(&lookup_table_data)
-        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToDeserialize.into())?;
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
 
-    if lookup_table.meta.authority.is_none() {
+    if lookup_table_meta.authority().is_none() {
         msg!("Lookup table is frozen");
         return Err(AddressLookupError::LookupTableImmutable.into());
     }
-    if lookup_table.meta.authority != Some(*authority_info.key) {
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
         return Err(AddressLookupError::IncorrectAuthority.into());
     }
-    if lookup_table.meta.deactivation_slot != Slot::MAX {
+    if u64::from(lookup_table_meta.deactivation_slot) != Slot::MAX {
         msg!("Lookup table is already deactivated");
-        return Err(ProgramError::InvalidArgument);
+        return Err(AddressLookupError::TableDeactivated.into());
     }
 
-    let mut lookup_table_meta = lookup_table.meta;
     let clock = <Clock as Sysvar>::get()?;
-    lookup_table_meta.deactivation_slot = clock.slot;
-    AddressLookupTable::overwrite_meta_data(
-        &mut lookup_table_info.try_borrow_mut_data()?,
-        lookup_table_meta,
-    )
-    .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToSerialize.into())?;
+    if slot <= clock.slot {
+        msg!(
+            "Scheduled slot {} must be later than the current slot {}",
+            slot,
+            clock.slot
+        );
+        return Err(AddressLookupError::ScheduledSlotNotInFuture.into());
+    }
 
-    Ok(())
+    init_extension_account(
+        program_id,
+        lookup_table_info,
+        extension_info,
+        account_info_iter,
+    )?;
+
+    let mut extension_data = extension_info.try_borrow_mut_data()?;
+    let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+    extension.set_scheduled_deactivation_slot(Some(slot));
+
+    lookup_table_meta.deactivation_scheduled = true.into();
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+
+    log_event(
+        EventKind::ScheduleDeactivation,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        addresses_len,
+        addresses_len,
+    )
 }
 
-fn process_close_lookup_table(
+fn process_cancel_scheduled_deactivation(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
 ) -> Result<(), ProgramError> {
     let account_info_iter = &mut accounts.iter();
     let lookup_table_info = next_account_info(account_info_iter)?;
     let authority_info = next_account_info(account_info_iter)?;
-    let recipient_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
 
     if lookup_table_info.owner != program_id {
         return Err(ProgramError::InvalidAccountOwner);
     }
 
-    if !authority_info.is_signer {
-        msg!("Authority account must be a signer");
-        return Err(ProgramError::MissingRequiredSignature);
-    }
-
-    if recipient_info.key == lookup_table_info.key {
-        msg!("Lookup table cannot be the recipient of reclaimed lamports");
-        return Err(ProgramError::InvalidArgument);
-    }
+    check_authority_signer(program_id, authority_info, accounts.get(3..))?;
 
-    let lookup_table_data = lookup_table_info.data.borrow();
-    let lookup_table = AddressLookupTable::deserialize(&lookup_table_data)
-        .map_err::<ProgramError, _>(|_| AddressLookupError::FailedToDeserialize.into())?;
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
 
-    if lookup_table.meta.authority.is_none() {
+    if lookup_table_meta.authority().is_none() {
         msg!("Lookup table is frozen");
         return Err(AddressLookupError::LookupTableImmutable.into());
     }
-    if lookup_table.meta.authority != Some(*authority_info.key) {
+    if lookup_table_meta.authority() != Some(*authority_info.key) {
         return Err(AddressLookupError::IncorrectAuthority.into());
     }
 
-    let clock = <Clock as Sysvar>::get()?;
-    let slot_hashes = <SlotHashes as Sysvar>::get()?;
+    check_extension_address(program_id, lookup_table_info.key, extension_info)?;
+    if extension_info.owner != program_id {
+        msg!("Lookup table has no scheduled deactivation");
+        return Err(AddressLookupError::NoScheduledDeactivation.into());
+    }
 
-    match lookup_table.meta.status(clock.slot, &slot_hashes) {
-        LookupTableStatus::Activated => {
-            msg!("Lookup table is not deactivated");
-            Err(ProgramError::InvalidArgument)
-        }
-        LookupTableStatus::Deactivating { remaining_blocks } => {
-            msg!(
-                "Table cannot be closed until it's fully deactivated in {} blocks",
-                remaining_blocks
-            );
-            Err(ProgramError::InvalidArgument)
-        }
-        LookupTableStatus::Deactivated => Ok(()),
-    }?;
+    let mut extension_data = extension_info.try_borrow_mut_data()?;
+    let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+    if extension.scheduled_deactivation_slot().is_none() {
+        msg!("Lookup table has no scheduled deactivation");
+        return Err(AddressLookupError::NoScheduledDeactivation.into());
+    }
+    extension.set_scheduled_deactivation_slot(None);
 
-    let new_recipient_lamports = lookup_table_info
-        .lamports()
-        .checked_add(recipient_info.lamports())
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+    lookup_table_meta.deactivation_scheduled = false.into();
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
 
-    **lookup_table_info.try_borrow_mut_lamports()? = 0;
-    **recipient_info.try_borrow_mut_lamports()? = new_recipient_lamports;
+    log_event(
+        EventKind::CancelScheduledDeactivation,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        <Clock as Sysvar>::get()?.slot,
+        addresses_len,
+        addresses_len,
+    )
+}
 
-    // Address Lookup Tables are not reassigned since they are derived from
-    // a particular slot.
-    lookup_table_info.realloc(0, true)?;
+fn process_execute_scheduled_deactivation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+    let lookup_table_info = next_account_info(account_info_iter)?;
+    let extension_info = next_account_info(account_info_iter)?;
 
-    Ok(())
+    if lookup_table_info.owner != program_id {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+
+    let LookupTableState {
+        meta: mut lookup_table_meta,
+        addresses_len,
+    } = read_lookup_table(lookup_table_info)?;
+
+    if u64::from(lookup_table_meta.deactivation_slot) != Slot::MAX {
+        msg!("Lookup table is already deactivated");
+        return Err(AddressLookupError::TableDeactivated.into());
+    }
+
+    check_extension_address(program_id, lookup_table_info.key, extension_info)?;
+    if extension_info.owner != program_id {
+        msg!("Lookup table has no scheduled deactivation");
+        return Err(AddressLookupError::NoScheduledDeactivation.into());
+    }
+
+    let mut extension_data = extension_info.try_borrow_mut_data()?;
+    let extension = LookupTableExtension::unpack_mut(&mut extension_data)?;
+    let Some(scheduled_slot) = extension.scheduled_deactivation_slot() else {
+        msg!("Lookup table has no scheduled deactivation");
+        return Err(AddressLookupError::NoScheduledDeactivation.into());
+    };
+
+    // A table frozen before freezing had to clear its schedule may still
+    // have one, and a frozen table can neither be deactivated nor cancel its schedule,
+    // so the stale schedule is discarded instead
+    let clock = <Clock as Sysvar>::get()?;
+    if lookup_table_meta.authority().is_none() {
+        msg!("Lookup table is frozen, discarding its scheduled deactivation");
+        extension.set_scheduled_deactivation_slot(None);
+        return log_event(
+            EventKind::CancelScheduledDeactivation,
+            lookup_table_info.key,
+            &lookup_table_meta,
+            clock.slot,
+            addresses_len,
+            addresses_len,
+        );
+    }
+
+    if clock.slot < scheduled_slot {
+        msg!(
+            "Lookup table cannot be deactivated until slot {}",
+            scheduled_slot
+        );
+        return Err(AddressLookupError::DeactivationNotDue.into());
+    }
+
+    extension.set_scheduled_deactivation_slot(None);
+    lookup_table_meta.deactivation_scheduled = false.into();
+    lookup_table_meta.deactivation_slot = clock.slot.into();
+    write_lookup_table_meta(lookup_table_info, &lookup_table_meta)?;
+
+    log_event(
+        EventKind::Deactivate,
+        lookup_table_info.key,
+        &lookup_table_meta,
+        clock.slot,
+        addresses_len,
+        addresses_len,
+    )
 }
 
 /// Processes an `AddressLookupInstruction`
@@ -402,7 +2211,12 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> P
         }
         AddressLookupInstruction::ExtendLookupTable { new_addresses } => {
             msg!("Instruction: ExtendLookupTable");
-            process_extend_lookup_table(program_id, accounts, new_addresses)
+            process_extend_lookup_table(
+                program_id,
+                accounts,
+                new_addresses,
+                DuplicateAddresses::Allow,
+            )
         }
         AddressLookupInstruction::DeactivateLookupTable => {
             msg!("Instruction: DeactivateLookupTable");
@@ -412,5 +2226,120 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], input: &[u8]) -> P
             msg!("Instruction: CloseLookupTable");
             process_close_lookup_table(program_id, accounts)
         }
+        AddressLookupInstruction::ProposeAuthority { new_authority } => {
+            msg!("Instruction: ProposeAuthority");
+            process_propose_authority(program_id, accounts, new_authority)
+        }
+        AddressLookupInstruction::AcceptAuthority => {
+            msg!("Instruction: AcceptAuthority");
+            process_accept_authority(program_id, accounts)
+        }
+        AddressLookupInstruction::CancelAuthorityProposal => {
+            msg!("Instruction: CancelAuthorityProposal");
+            process_cancel_authority_proposal(program_id, accounts)
+        }
+        AddressLookupInstruction::ReactivateLookupTable => {
+            msg!("Instruction: ReactivateLookupTable");
+            process_reactivate_lookup_table(program_id, accounts)
+        }
+        AddressLookupInstruction::TruncateLookupTable {
+            new_len,
+            cool_down_slots,
+        } => {
+            msg!("Instruction: TruncateLookupTable");
+            process_truncate_lookup_table(program_id, accounts, new_len, cool_down_slots)
+        }
+        AddressLookupInstruction::CreateLookupTableWithAddresses {
+            recent_slot,
+            bump_seed,
+            addresses,
+            freeze,
+        } => {
+            msg!("Instruction: CreateLookupTableWithAddresses");
+            process_create_lookup_table_with_addresses(
+                program_id,
+                accounts,
+                recent_slot,
+                bump_seed,
+                addresses,
+                freeze,
+            )
+        }
+        AddressLookupInstruction::CreateLookupTableWithSeed { seed, bump_seed } => {
+            msg!("Instruction: CreateLookupTableWithSeed");
+            process_create_lookup_table_with_seed(program_id, accounts, seed, bump_seed)
+        }
+        AddressLookupInstruction::ExtendLookupTableUnique {
+            new_addresses,
+            skip_duplicates,
+        } => {
+            msg!("Instruction: ExtendLookupTableUnique");
+            let duplicates = if skip_duplicates {
+                DuplicateAddresses::Skip
+            } else {
+                DuplicateAddresses::Reject
+            };
+            process_extend_lookup_table(program_id, accounts, new_addresses, duplicates)
+        }
+        AddressLookupInstruction::SetExtender { extender } => {
+            msg!("Instruction: SetExtender");
+            process_set_extender(program_id, accounts, extender)
+        }
+        AddressLookupInstruction::RemoveExtender => {
+            msg!("Instruction: RemoveExtender");
+            process_remove_extender(program_id, accounts)
+        }
+        AddressLookupInstruction::InitializeMultisig { m } => {
+            msg!("Instruction: InitializeMultisig");
+            process_initialize_multisig(program_id, accounts, m)
+        }
+        AddressLookupInstruction::ResolveAddresses { indexes } => {
+            msg!("Instruction: ResolveAddresses");
+            process_resolve_addresses(program_id, accounts, indexes)
+        }
+        AddressLookupInstruction::SetMetadata { name, uri, tags } => {
+            msg!("Instruction: SetMetadata");
+            process_set_metadata(program_id, accounts, name, uri, tags)
+        }
+        AddressLookupInstruction::MigrateLookupTable => {
+            msg!("Instruction: MigrateLookupTable");
+            process_migrate_lookup_table(program_id, accounts)
+        }
+        AddressLookupInstruction::ScheduleDeactivation { slot } => {
+            msg!("Instruction: ScheduleDeactivation");
+            process_schedule_deactivation(program_id, accounts, slot)
+        }
+        AddressLookupInstruction::CancelScheduledDeactivation => {
+            msg!("Instruction: CancelScheduledDeactivation");
+            process_cancel_scheduled_deactivation(program_id, accounts)
+        }
+        AddressLookupInstruction::ExecuteScheduledDeactivation => {
+            msg!("Instruction: ExecuteScheduledDeactivation");
+            process_execute_scheduled_deactivation(program_id, accounts)
+        }
+        AddressLookupInstruction::CloneLookupTable {
+            recent_slot,
+            bump_seed,
+            start_index,
+            num_addresses,
+        } => {
+            msg!("Instruction: CloneLookupTable");
+            process_clone_lookup_table(
+                program_id,
+                accounts,
+                recent_slot,
+                bump_seed,
+                start_index,
+                num_addresses,
+            )
+        }
+        AddressLookupInstruction::DeactivateLookupTables { num_lookup_tables } => {
+            msg!("Instruction: DeactivateLookupTables");
+            process_deactivate_lookup_tables(program_id, accounts, num_lookup_tables)
+        }
+        AddressLookupInstruction::CloseLookupTables { num_lookup_tables } => {
+            msg!("Instruction: CloseLookupTables");
+            process_close_lookup_tables(program_id, accounts, num_lookup_tables)
+        }
     }
 }
@@ -0,0 +1,244 @@
+//! Return data of instructions that change a lookup table
+//!
+//! Create and extend instructions describe what they changed through the
+//! return data, so that callers do not have to read the table account back.
+//! Every payload starts with a two-byte header, followed by a body that
+//! depends on the kind:
+//!
+//! | Offset | Size | Field                                    |
+//! |--------|------|------------------------------------------|
+//! | 0      | 1    | Version, currently `RETURN_DATA_VERSION` |
+//! | 1      | 1    | Kind, `0` for create and `1` for extend  |
+//!
+//! Create (35 bytes in total):
+//!
+//! | Offset | Size | Field                          |
+//! |--------|------|--------------------------------|
+//! | 2      | 32   | Address of the lookup table    |
+//! | 34     | 1    | Bump seed of the table address |
+//!
+//! Extend (15 bytes in total, little-endian integers):
+//!
+//! | Offset | Size | Field                                            |
+//! |--------|------|--------------------------------------------------|
+//! | 2      | 1    | Index of the first address appended by the call  |
+//! | 3      | 2    | Number of addresses in the table after the call  |
+//! | 5      | 8    | `last_extended_slot` of the table after the call |
+//! | 13     | 2    | Number of addresses appended by the call         |
+//!
+//! New fields are only ever appended to a body. Decoders accept longer
+//! payloads of a known version and ignore the trailing bytes.
+
+use solana_program::{
+    clock::Slot,
+    program_error::ProgramError,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
+
+/// Version of the return data layout
+pub const RETURN_DATA_VERSION: u8 = 1;
+
+const CREATE_KIND: u8 = 0;
+const EXTEND_KIND: u8 = 1;
+const HEADER_LEN: usize = 2;
+const CREATE_BODY_LEN: usize = PUBKEY_BYTES + 1;
+const EXTEND_BODY_LEN: usize = 1 + 2 + 8 + 2;
+
+/// Decoded return data of a create or extend instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReturnData {
+    /// Returned by all create instructions
+    CreateLookupTable {
+        /// Address of the created table
+        lookup_table_address: Pubkey,
+        /// Bump seed of the table address
+        bump_seed: u8,
+    },
+    /// Returned by all extend instructions. If every new address was skipped
+    /// as a duplicate, `appended_len` and `start_index` are zero, since a full
+    /// table has no index left to report.
+    ExtendLookupTable {
+        /// Index at which the first appended address was stored
+        start_index: u8,
+        /// Number of addresses in the table after extending it
+        new_len: u16,
+        /// Slot in which the table was last extended
+        last_extended_slot: Slot,
+        /// Number of addresses appended to the table
+        appended_len: u16,
+    },
+}
+
+impl ReturnData {
+    /// Serializes the return data
+    pub fn pack(&self) -> Vec<u8> {
+        match self {
+            Self::CreateLookupTable {
+                lookup_table_address,
+                bump_seed,
+            } => {
+                let mut data = Vec::with_capacity(HEADER_LEN.saturating_add(CREATE_BODY_LEN));
+                data.extend_from_slice(&[RETURN_DATA_VERSION, CREATE_KIND]);
+                data.extend_from_slice(lookup_table_address.as_ref());
+                data.push(*bump_seed);
+                data
+            }
+            Self::ExtendLookupTable {
+                start_index,
+                new_len,
+                last_extended_slot,
+                appended_len,
+            } => {
+                let mut data = Vec::with_capacity(HEADER_LEN.saturating_add(EXTEND_BODY_LEN));
+                data.extend_from_slice(&[RETURN_DATA_VERSION, EXTEND_KIND, *start_index]);
+                data.extend_from_slice(&new_len.to_le_bytes());
+                data.extend_from_slice(&last_extended_slot.to_le_bytes());
+                data.extend_from_slice(&appended_len.to_le_bytes());
+                data
+            }
+        }
+    }
+
+    /// Deserializes return data written by any version of the program that
+    /// uses a layout this crate knows about
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() < HEADER_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let (header, body) = data.split_at(HEADER_LEN);
+        if header[0] != RETURN_DATA_VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        match header[1] {
+            CREATE_KIND if body.len() >= CREATE_BODY_LEN => {
+                let (lookup_table_address, bump_seed) = body.split_at(PUBKEY_BYTES);
+                Ok(Self::CreateLookupTable {
+                    lookup_table_address: Pubkey::try_from(lookup_table_address)
+                        .map_err(|_| ProgramError::InvalidAccountData)?,
+                    bump_seed: bump_seed[0],
+                })
+            }
+            EXTEND_KIND if body.len() >= EXTEND_BODY_LEN => {
+                let mut new_len = [0; 2];
+                new_len.copy_from_slice(&body[1..3]);
+                let mut last_extended_slot = [0; 8];
+                last_extended_slot.copy_from_slice(&body[3..11]);
+                let mut appended_len = [0; 2];
+                appended_len.copy_from_slice(&body[11..13]);
+                Ok(Self::ExtendLookupTable {
+                    start_index: body[0],
+                    new_len: u16::from_le_bytes(new_len),
+                    last_extended_slot: Slot::from_le_bytes(last_extended_slot),
+                    appended_len: u16::from_le_bytes(appended_len),
+                })
+            }
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Decodes return data as reported by the runtime, checking that it was
+    /// set by this program
+    pub fn decode(program_id: &Pubkey, data: &[u8]) -> Result<Self, ProgramError> {
+        if program_id != &crate::id() {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Self::unpack(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack() {
+        let create = ReturnData::CreateLookupTable {
+            lookup_table_address: Pubkey::new_unique(),
+            bump_seed: 254,
+        };
+        let data = create.pack();
+        assert_eq!(data.len(), 35);
+        assert_eq!(ReturnData::unpack(&data), Ok(create));
+
+        let extend = ReturnData::ExtendLookupTable {
+            start_index: 255,
+            new_len: 256,
+            last_extended_slot: Slot::MAX - 1,
+            appended_len: 1,
+        };
+        let data = extend.pack();
+        assert_eq!(data.len(), 15);
+        assert_eq!(
+            data,
+            [
+                &[RETURN_DATA_VERSION, EXTEND_KIND, 255][..],
+                &256u16.to_le_bytes(),
+                &(Slot::MAX - 1).to_le_bytes(),
+                &1u16.to_le_bytes(),
+            ]
+            .concat()
+        );
+        assert_eq!(ReturnData::unpack(&data), Ok(extend));
+    }
+
+    #[test]
+    fn test_unpack_ignores_appended_fields() {
+        let extend = ReturnData::ExtendLookupTable {
+            start_index: 1,
+            new_len: 2,
+            last_extended_slot: 3,
+            appended_len: 1,
+        };
+        let mut data = extend.pack();
+        data.extend_from_slice(&[7; 8]);
+        assert_eq!(ReturnData::unpack(&data), Ok(extend));
+    }
+
+    #[test]
+    fn test_unpack_invalid_data() {
+        let data = ReturnData::CreateLookupTable {
+            lookup_table_address: Pubkey::new_unique(),
+            bump_seed: 1,
+        }
+        .pack();
+
+        assert_eq!(
+            ReturnData::unpack(&data[..data.len() - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            ReturnData::unpack(&[]),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let mut unknown_version = data.clone();
+        unknown_version[0] = RETURN_DATA_VERSION + 1;
+        assert_eq!(
+            ReturnData::unpack(&unknown_version),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let mut unknown_kind = data;
+        unknown_kind[1] = 2;
+        assert_eq!(
+            ReturnData::unpack(&unknown_kind),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_decode_checks_program_id() {
+        let data = ReturnData::ExtendLookupTable {
+            start_index: 0,
+            new_len: 1,
+            last_extended_slot: 0,
+            appended_len: 1,
+        }
+        .pack();
+        assert!(ReturnData::decode(&crate::id(), &data).is_ok());
+        assert_eq!(
+            ReturnData::decode(&Pubkey::new_unique(), &data),
+            Err(ProgramError::IncorrectProgramId)
+        );
+    }
+}
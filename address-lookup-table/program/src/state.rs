@@ -0,0 +1,1083 @@
+//! Program state
+//!
+//! Zero-copy views over address lookup table account data. The layout is
+//! byte-for-byte compatible with the bincode encoding of the SDK's
+//! `ProgramState::LookupTable(LookupTableMeta)`, followed by the raw list of
+//! addresses, so existing table accounts can be read and written in place.
+
+use {
+    crate::error::AddressLookupError,
+    bytemuck::{Pod, Zeroable},
+    solana_program::{
+        clock::Slot,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        slot_hashes::{SlotHashes, MAX_ENTRIES},
+    },
+    spl_pod::{
+        optional_keys::OptionalNonZeroPubkey,
+        primitives::{PodBool, PodU32, PodU64},
+    },
+};
+
+/// The maximum number of addresses that a lookup table can hold
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+
+/// The serialized size of lookup table metadata
+pub const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+/// Minimum truncation cool-down: the number of slots that must pass after a
+/// lookup table was last extended before it can be truncated, and after it
+/// was truncated before it can be extended again. It spans the slot hashes
+/// window, so transactions compiled against the removed addresses expire
+/// before their indexes can be reused.
+pub const MIN_TRUNCATION_COOL_DOWN_SLOTS: Slot = MAX_ENTRIES as Slot;
+
+/// Bincode enum discriminator of `ProgramState::Uninitialized`
+const UNINITIALIZED_DISCRIMINATOR: u32 = 0;
+/// Bincode enum discriminator of `ProgramState::LookupTable`
+const LOOKUP_TABLE_DISCRIMINATOR: u32 = 1;
+/// Discriminator of lookup table extension accounts. It continues the
+/// `ProgramState` numbering so that the runtime can never deserialize an
+/// extension account as a lookup table.
+const EXTENSION_DISCRIMINATOR: u32 = 2;
+
+/// Discriminator of multisig authority accounts
+const MULTISIG_DISCRIMINATOR: u32 = 3;
+
+/// Discriminator of lookup table metadata accounts
+const METADATA_DISCRIMINATOR: u32 = 4;
+
+/// The maximum number of signers of a multisig authority
+pub const MAX_MULTISIG_SIGNERS: usize = 11;
+
+/// Seed prefix for deriving the extension account of a lookup table
+pub const EXTENSION_SEED: &[u8] = b"extension";
+
+/// Current layout version of lookup table extension accounts
+pub const EXTENSION_VERSION: u8 = 1;
+
+/// Size of the data of an extension account written before the `version`
+/// field was added
+pub const LEGACY_EXTENSION_LEN: usize = 100;
+
+/// Extension flag marking the extension account of a seed-derived lookup
+/// table. The account is created along with the table and outlives it, so
+/// that the table's address can never be reused once the table is closed.
+pub const EXTENSION_FLAG_SEED_DERIVED: u64 = 1;
+
+/// Seed prefix for deriving the metadata account of a lookup table
+pub const METADATA_SEED: &[u8] = b"table_metadata";
+
+/// The maximum length in bytes of a lookup table's name
+pub const MAX_METADATA_NAME_LEN: usize = 32;
+
+/// The maximum length in bytes of a lookup table's URI
+pub const MAX_METADATA_URI_LEN: usize = 256;
+
+/// The maximum number of tags of a lookup table
+pub const MAX_METADATA_TAGS: usize = 8;
+
+/// The maximum length in bytes of each tag of a lookup table
+pub const MAX_METADATA_TAG_LEN: usize = 32;
+
+/// Length of the caller-chosen seed of a seed-derived lookup table
+pub const LOOKUP_TABLE_SEED_LEN: usize = 32;
+
+/// Version byte mixed into the derivation of seed-derived lookup table
+/// addresses.
+///
+/// Program addresses hash the plain concatenation of their seeds. Seed-derived
+/// tables hash 66 bytes of seeds (authority, version, seed and bump), while
+/// slot-derived tables hash 41 (authority, slot and bump), extension accounts
+/// 42 and metadata accounts 47, so the derivations can never produce the same
+/// address.
+pub const LOOKUP_TABLE_SEED_VERSION: u8 = 1;
+
+/// Activation status of a lookup table
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LookupTableStatus {
+    Activated,
+    /// The table is active, and deactivation has been scheduled for a slot
+    /// that has not been reached yet
+    DeactivationScheduled {
+        remaining_slots: Slot,
+    },
+    Deactivating {
+        remaining_blocks: usize,
+    },
+    Deactivated,
+}
+
+/// Address lookup table metadata
+///
+/// Mirrors the bincode layout of the SDK's `ProgramState::LookupTable`. When
+/// the table has no authority, bincode omits the authority key entirely and
+/// writes the padding directly after the option tag, which lands in the first
+/// two bytes of `authority`. Keeping the key zeroed in that case preserves the
+/// SDK's view of `_padding` as zero.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct LookupTableMeta {
+    /// `ProgramState` enum discriminator
+    discriminator: PodU32,
+    /// Lookup tables cannot be closed until the deactivation slot is
+    /// no longer "recent" (not accessible in the `SlotHashes` sysvar).
+    pub deactivation_slot: PodU64,
+    /// The slot that the table was last extended. Address tables may
+    /// only be used to lookup addresses that were extended before
+    /// the current bank's slot.
+    pub last_extended_slot: PodU64,
+    /// The start index where the table was last extended from during
+    /// the `last_extended_slot`.
+    pub last_extended_slot_start_index: u8,
+    /// Option tag for `authority`
+    has_authority: PodBool,
+    /// Authority address which must sign for each modification.
+    authority: Pubkey,
+    /// Set by `TruncateLookupTable` while the end of the truncation cool-down
+    /// is recorded in the table's extension account, which must then be
+    /// passed to extend the table. The SDK reads it as part of `_padding`.
+    pub truncation_cool_down: PodBool,
+    /// Set by `ScheduleDeactivation` while a scheduled deactivation is
+    /// recorded in the table's extension account, which must then be passed
+    /// to freeze the table. The SDK reads it as part of `_padding`.
+    pub deactivation_scheduled: PodBool,
+    // Raw list of addresses follows this structure in the account's data,
+    // starting from `LOOKUP_TABLE_META_SIZE`.
+}
+
+impl LookupTableMeta {
+    /// Creates the metadata for a new, active lookup table
+    pub fn new(authority: Pubkey) -> Self {
+        Self {
+            discriminator: LOOKUP_TABLE_DISCRIMINATOR.into(),
+            deactivation_slot: Slot::MAX.into(),
+            has_authority: true.into(),
+            authority,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the authority of the table, or `None` if the table is frozen
+    pub fn authority(&self) -> Option<Pubkey> {
+        bool::from(self.has_authority).then_some(self.authority)
+    }
+
+    /// Sets the authority of the table. Setting `None` freezes the table.
+    pub fn set_authority(&mut self, authority: Option<Pubkey>) {
+        self.has_authority = authority.is_some().into();
+        self.authority = authority.unwrap_or_default();
+    }
+
+    /// Returns whether the table is considered active for address lookups
+    pub fn is_active(&self, current_slot: Slot, slot_hashes: &SlotHashes) -> bool {
+        match self.status(current_slot, slot_hashes) {
+            LookupTableStatus::Activated => true,
+            LookupTableStatus::DeactivationScheduled { .. } => true,
+            LookupTableStatus::Deactivating { .. } => true,
+            LookupTableStatus::Deactivated => false,
+        }
+    }
+
+    /// Return the current status of the lookup table
+    pub fn status(&self, current_slot: Slot, slot_hashes: &SlotHashes) -> LookupTableStatus {
+        let deactivation_slot = u64::from(self.deactivation_slot);
+        if deactivation_slot == Slot::MAX {
+            LookupTableStatus::Activated
+        } else if deactivation_slot == current_slot {
+            LookupTableStatus::Deactivating {
+                remaining_blocks: MAX_ENTRIES.saturating_add(1),
+            }
+        } else if let Some(slot_hash_position) = slot_hashes.position(&deactivation_slot) {
+            // Deactivation requires a cool-down period to give in-flight
+            // transactions enough time to land and to remove indeterminism
+            // caused by transactions loading addresses in the same slot when a
+            // table is closed. The cool-down period is equivalent to the amount
+            // of time it takes for a slot to be removed from the slot hash
+            // list.
+            //
+            // By using the slot hash to enforce the cool-down, there is a side
+            // effect of not allowing lookup tables to be recreated at the same
+            // derived address because tables must be created at an address
+            // derived from a recent slot.
+            LookupTableStatus::Deactivating {
+                remaining_blocks: MAX_ENTRIES.saturating_sub(slot_hash_position),
+            }
+        } else {
+            LookupTableStatus::Deactivated
+        }
+    }
+
+    /// Return the current status of the lookup table, taking into account
+    /// the deactivation slot scheduled in its extension account.
+    ///
+    /// The runtime only reads the table account, so it keeps treating the
+    /// table as active until the scheduled deactivation is executed. A table
+    /// whose scheduled slot has passed is therefore still `Activated`.
+    pub fn status_with_schedule(
+        &self,
+        current_slot: Slot,
+        slot_hashes: &SlotHashes,
+        scheduled_deactivation_slot: Option<Slot>,
+    ) -> LookupTableStatus {
+        match (
+            self.status(current_slot, slot_hashes),
+            scheduled_deactivation_slot,
+        ) {
+            (LookupTableStatus::Activated, Some(scheduled_slot))
+                if scheduled_slot > current_slot =>
+            {
+                LookupTableStatus::DeactivationScheduled {
+                    remaining_slots: scheduled_slot.saturating_sub(current_slot),
+                }
+            }
+            (status, _) => status,
+        }
+    }
+
+    fn check_initialized(&self) -> Result<(), ProgramError> {
+        match u32::from(self.discriminator) {
+            LOOKUP_TABLE_DISCRIMINATOR => {}
+            UNINITIALIZED_DISCRIMINATOR => return Err(ProgramError::UninitializedAccount),
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+        // bincode rejects any option tag other than 0 or 1
+        if self.has_authority != true.into() && self.has_authority != false.into() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+}
+
+/// Splits lookup table account data into its metadata and address regions
+fn split_data(data: &[u8]) -> Result<(&[u8], &[u8]), ProgramError> {
+    if data.len() < LOOKUP_TABLE_META_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data.split_at(LOOKUP_TABLE_META_SIZE))
+}
+
+/// Splits mutable lookup table account data into its metadata and address
+/// regions
+fn split_data_mut(data: &mut [u8]) -> Result<(&mut [u8], &mut [u8]), ProgramError> {
+    if data.len() < LOOKUP_TABLE_META_SIZE {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(data.split_at_mut(LOOKUP_TABLE_META_SIZE))
+}
+
+/// Read-only view of an initialized address lookup table account
+#[derive(Debug, PartialEq)]
+pub struct AddressLookupTable<'a> {
+    pub meta: &'a LookupTableMeta,
+    pub addresses: &'a [Pubkey],
+}
+
+impl<'a> AddressLookupTable<'a> {
+    /// Unpacks a view of the table from account data without copying
+    pub fn unpack(data: &'a [u8]) -> Result<Self, ProgramError> {
+        let (meta_data, addresses_data) = split_data(data)?;
+        let meta = bytemuck::try_from_bytes::<LookupTableMeta>(meta_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        meta.check_initialized()?;
+        let addresses = bytemuck::try_cast_slice(addresses_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(Self { meta, addresses })
+    }
+
+    /// Returns the number of addresses that can be looked up in the current
+    /// slot. Addresses appended in the current slot are not active yet.
+    pub fn get_active_addresses_len(
+        &self,
+        current_slot: Slot,
+        slot_hashes: &SlotHashes,
+    ) -> Result<usize, AddressLookupError> {
+        if !self.meta.is_active(current_slot, slot_hashes) {
+            // Once a lookup table is no longer active, it can be closed
+            // at any point, so returning a specific error for deactivated
+            // lookup tables could result in a race condition.
+            return Err(AddressLookupError::LookupTableAccountNotFound);
+        }
+
+        let active_addresses_len = if current_slot > u64::from(self.meta.last_extended_slot) {
+            self.addresses.len()
+        } else {
+            usize::from(self.meta.last_extended_slot_start_index)
+        };
+
+        Ok(active_addresses_len)
+    }
+
+    /// Looks up addresses by index with the same semantics as the runtime
+    /// uses when loading addresses for a transaction
+    pub fn lookup(
+        &self,
+        current_slot: Slot,
+        indexes: &[u8],
+        slot_hashes: &SlotHashes,
+    ) -> Result<Vec<Pubkey>, AddressLookupError> {
+        let active_addresses_len = self.get_active_addresses_len(current_slot, slot_hashes)?;
+        let active_addresses = self
+            .addresses
+            .get(..active_addresses_len)
+            .ok_or(AddressLookupError::InvalidAccountData)?;
+        indexes
+            .iter()
+            .map(|index| active_addresses.get(usize::from(*index)).copied())
+            .collect::<Option<_>>()
+            .ok_or(AddressLookupError::InvalidLookupIndex)
+    }
+}
+
+/// Mutable view of an initialized address lookup table account
+#[derive(Debug, PartialEq)]
+pub struct AddressLookupTableMut<'a> {
+    pub meta: &'a mut LookupTableMeta,
+    pub addresses: &'a mut [Pubkey],
+}
+
+impl<'a> AddressLookupTableMut<'a> {
+    /// Unpacks a mutable view of the table from account data without copying
+    pub fn unpack(data: &'a mut [u8]) -> Result<Self, ProgramError> {
+        let (meta_data, addresses_data) = split_data_mut(data)?;
+        let meta = bytemuck::try_from_bytes_mut::<LookupTableMeta>(meta_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        meta.check_initialized()?;
+        let addresses = bytemuck::try_cast_slice_mut(addresses_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(Self { meta, addresses })
+    }
+
+    /// Writes the metadata of a new table into uninitialized account data
+    pub fn init(data: &'a mut [u8], meta: LookupTableMeta) -> Result<Self, ProgramError> {
+        let (meta_data, _) = split_data_mut(data)?;
+        meta_data.copy_from_slice(bytemuck::bytes_of(&meta));
+        Self::unpack(data)
+    }
+}
+
+/// Finds the address of a lookup table derived from its authority and a
+/// caller-chosen seed
+pub fn find_lookup_table_address_with_seed(
+    authority_address: &Pubkey,
+    seed: &[u8; LOOKUP_TABLE_SEED_LEN],
+    program_id: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            authority_address.as_ref(),
+            &[LOOKUP_TABLE_SEED_VERSION],
+            seed,
+        ],
+        program_id,
+    )
+}
+
+/// Finds the address of the extension account belonging to a lookup table
+pub fn find_extension_address(lookup_table_address: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[EXTENSION_SEED, lookup_table_address.as_ref()], program_id)
+}
+
+/// Finds the address of the metadata account belonging to a lookup table
+pub fn find_metadata_address(lookup_table_address: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[METADATA_SEED, lookup_table_address.as_ref()], program_id)
+}
+
+/// Per-table state that does not fit in the lookup table account.
+///
+/// The runtime reads lookup table accounts directly when resolving addresses,
+/// treating everything after the metadata as the address list, so the table
+/// account itself has no room for new fields. This state lives instead in a
+/// program-owned account derived from the table address with
+/// `find_extension_address`.
+///
+/// The layout is versioned. Legacy accounts predate the `version` field and
+/// end after `extender`, at `LEGACY_EXTENSION_LEN` bytes; they must be
+/// upgraded to `EXTENSION_VERSION` with `MigrateLookupTable` before use. Later
+/// versions carve new fields out of `_reserved` without resizing the account,
+/// so a zeroed field must always mean "unset".
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct LookupTableExtension {
+    /// Account discriminator
+    discriminator: PodU32,
+    /// Lookup table that this extension belongs to
+    pub lookup_table: Pubkey,
+    /// Authority nominated by the current authority, which must sign to
+    /// accept the handover
+    pub pending_authority: OptionalNonZeroPubkey,
+    /// Account allowed to extend the table in addition to its authority
+    pub extender: OptionalNonZeroPubkey,
+    /// Layout version, `EXTENSION_VERSION` for accounts this program writes
+    version: u8,
+    /// Per-table option flags, see `EXTENSION_FLAG_SEED_DERIVED`
+    pub flags: PodU64,
+    /// Slot from which anyone may deactivate the table, or zero if no
+    /// deactivation is scheduled
+    scheduled_deactivation_slot: PodU64,
+    /// Slot until which the table may not be extended after it was
+    /// truncated, or zero if it was never truncated
+    truncation_cool_down_end_slot: PodU64,
+    /// Space for fields added by later versions
+    _reserved: [PodU64; 6],
+}
+
+impl LookupTableExtension {
+    /// Size of an extension account's data
+    pub const LEN: usize = std::mem::size_of::<Self>();
+
+    /// Creates an empty extension for a lookup table
+    pub fn new(lookup_table: Pubkey) -> Self {
+        Self {
+            discriminator: EXTENSION_DISCRIMINATOR.into(),
+            lookup_table,
+            version: EXTENSION_VERSION,
+            ..Self::zeroed()
+        }
+    }
+
+    /// Layout version of the extension
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Returns whether the extension belongs to a seed-derived lookup table
+    pub fn is_seed_derived(&self) -> bool {
+        u64::from(self.flags) & EXTENSION_FLAG_SEED_DERIVED != 0
+    }
+
+    /// Slot from which anyone may deactivate the table, if scheduled
+    pub fn scheduled_deactivation_slot(&self) -> Option<Slot> {
+        let slot = u64::from(self.scheduled_deactivation_slot);
+        (slot != 0).then_some(slot)
+    }
+
+    /// Schedules deactivation of the table at `slot`, or cancels the schedule
+    /// if `None`. Slot 0 is never in the future, so it is used to mark an
+    /// empty schedule.
+    pub fn set_scheduled_deactivation_slot(&mut self, slot: Option<Slot>) {
+        self.scheduled_deactivation_slot = slot.unwrap_or_default().into();
+    }
+
+    /// Slot until which the table may not be extended after it was
+    /// truncated, if it was ever truncated
+    pub fn truncation_cool_down_end_slot(&self) -> Option<Slot> {
+        let slot = u64::from(self.truncation_cool_down_end_slot);
+        (slot != 0).then_some(slot)
+    }
+
+    /// Records the end of the cool-down of a truncation
+    pub fn set_truncation_cool_down_end_slot(&mut self, slot: Slot) {
+        self.truncation_cool_down_end_slot = slot.into();
+    }
+
+    /// Returns whether account data holds a legacy extension, which must be
+    /// migrated before it can be unpacked
+    pub fn is_legacy(data: &[u8]) -> bool {
+        data.len() == LEGACY_EXTENSION_LEN && data[..4] == EXTENSION_DISCRIMINATOR.to_le_bytes()
+    }
+
+    /// Upgrades a legacy extension to the current layout, keeping all of its
+    /// fields
+    pub fn from_legacy(data: &[u8]) -> Result<Self, ProgramError> {
+        if !Self::is_legacy(data) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let mut extension = Self::zeroed();
+        bytemuck::bytes_of_mut(&mut extension)[..LEGACY_EXTENSION_LEN].copy_from_slice(data);
+        extension.version = EXTENSION_VERSION;
+        Ok(extension)
+    }
+
+    /// Unpacks an extension from account data without copying
+    pub fn unpack(data: &[u8]) -> Result<&Self, ProgramError> {
+        let extension =
+            bytemuck::try_from_bytes::<Self>(data).map_err(|_| ProgramError::InvalidAccountData)?;
+        extension.check_initialized()?;
+        Ok(extension)
+    }
+
+    /// Unpacks a mutable extension from account data without copying
+    pub fn unpack_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let extension = bytemuck::try_from_bytes_mut::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        extension.check_initialized()?;
+        Ok(extension)
+    }
+
+    fn check_initialized(&self) -> Result<(), ProgramError> {
+        match u32::from(self.discriminator) {
+            EXTENSION_DISCRIMINATOR if self.version == EXTENSION_VERSION => Ok(()),
+            UNINITIALIZED_DISCRIMINATOR => Err(ProgramError::UninitializedAccount),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// M-of-N multisig that can be set as the authority of lookup tables.
+///
+/// When a table's authority is a multisig account, instructions that require
+/// the authority are approved by at least `m` of its signers, passed as signer
+/// accounts after the instruction's other accounts.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Pod, Zeroable)]
+pub struct LookupTableMultisig {
+    /// Account discriminator
+    discriminator: PodU32,
+    /// Number of signers required
+    pub m: u8,
+    /// Number of valid signers
+    pub n: u8,
+    /// Signer public keys, of which only the first `n` are valid
+    signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+}
+
+impl LookupTableMultisig {
+    /// Size of a multisig account's data
+    pub const LEN: usize = std::mem::size_of::<Self>();
+
+    /// Creates an `m`-of-`signers.len()` multisig. Signers must be distinct.
+    pub fn new(m: u8, signers: &[Pubkey]) -> Result<Self, ProgramError> {
+        let n = u8::try_from(signers.len()).map_err(|_| ProgramError::InvalidArgument)?;
+        if signers.len() > MAX_MULTISIG_SIGNERS || m == 0 || m > n {
+            return Err(ProgramError::InvalidArgument);
+        }
+        for (index, signer) in signers.iter().enumerate() {
+            if signers[..index].contains(signer) {
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+
+        let mut multisig = Self {
+            discriminator: MULTISIG_DISCRIMINATOR.into(),
+            m,
+            n,
+            ..Self::default()
+        };
+        multisig.signers[..signers.len()].copy_from_slice(signers);
+        Ok(multisig)
+    }
+
+    /// Valid signer public keys
+    pub fn signers(&self) -> &[Pubkey] {
+        self.signers
+            .get(..usize::from(self.n))
+            .unwrap_or(&self.signers)
+    }
+
+    /// Unpacks a multisig from account data without copying
+    pub fn unpack(data: &[u8]) -> Result<&Self, ProgramError> {
+        let multisig =
+            bytemuck::try_from_bytes::<Self>(data).map_err(|_| ProgramError::InvalidAccountData)?;
+        match u32::from(multisig.discriminator) {
+            MULTISIG_DISCRIMINATOR => Ok(multisig),
+            UNINITIALIZED_DISCRIMINATOR => Err(ProgramError::UninitializedAccount),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Human-readable labels of a lookup table, stored in a program-owned account
+/// derived from the table address with `find_metadata_address`.
+///
+/// Strings are stored as UTF-8, padded with zeros to the size of their field,
+/// so they may not contain NUL characters.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct LookupTableMetadata {
+    /// Account discriminator
+    discriminator: PodU32,
+    /// Lookup table that this metadata belongs to
+    pub lookup_table: Pubkey,
+    /// Name of the table
+    name: [u8; MAX_METADATA_NAME_LEN],
+    /// URI pointing to further information about the table
+    uri: [u8; MAX_METADATA_URI_LEN],
+    /// Tags of the table, of which only the non-empty ones are set
+    tags: [[u8; MAX_METADATA_TAG_LEN]; MAX_METADATA_TAGS],
+}
+
+impl LookupTableMetadata {
+    /// Size of a metadata account's data
+    pub const LEN: usize = std::mem::size_of::<Self>();
+
+    /// Creates the metadata of a lookup table. Fails if a string is too long
+    /// or contains a NUL character, if a tag is empty, or if there are too
+    /// many tags.
+    pub fn new(
+        lookup_table: Pubkey,
+        name: &str,
+        uri: &str,
+        tags: &[String],
+    ) -> Result<Self, ProgramError> {
+        if tags.len() > MAX_METADATA_TAGS || tags.iter().any(String::is_empty) {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut metadata = Self::zeroed();
+        metadata.discriminator = METADATA_DISCRIMINATOR.into();
+        metadata.lookup_table = lookup_table;
+        write_padded(&mut metadata.name, name)?;
+        write_padded(&mut metadata.uri, uri)?;
+        for (field, tag) in metadata.tags.iter_mut().zip(tags) {
+            write_padded(field, tag)?;
+        }
+        Ok(metadata)
+    }
+
+    /// Name of the table
+    pub fn name(&self) -> &str {
+        read_padded(&self.name)
+    }
+
+    /// URI pointing to further information about the table
+    pub fn uri(&self) -> &str {
+        read_padded(&self.uri)
+    }
+
+    /// Tags of the table
+    pub fn tags(&self) -> Vec<&str> {
+        self.tags
+            .iter()
+            .map(|tag| read_padded(tag))
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+
+    /// Unpacks metadata from account data without copying
+    pub fn unpack(data: &[u8]) -> Result<&Self, ProgramError> {
+        let metadata =
+            bytemuck::try_from_bytes::<Self>(data).map_err(|_| ProgramError::InvalidAccountData)?;
+        metadata.check_initialized()?;
+        Ok(metadata)
+    }
+
+    /// Unpacks mutable metadata from account data without copying
+    pub fn unpack_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let metadata = bytemuck::try_from_bytes_mut::<Self>(data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        metadata.check_initialized()?;
+        Ok(metadata)
+    }
+
+    fn check_initialized(&self) -> Result<(), ProgramError> {
+        match u32::from(self.discriminator) {
+            METADATA_DISCRIMINATOR => Ok(()),
+            UNINITIALIZED_DISCRIMINATOR => Err(ProgramError::UninitializedAccount),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// Copies a string into a zero-padded field
+fn write_padded(field: &mut [u8], value: &str) -> Result<(), ProgramError> {
+    if value.len() > field.len() || value.contains('\0') {
+        return Err(ProgramError::InvalidArgument);
+    }
+    field.fill(0);
+    field[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(())
+}
+
+/// Reads a string from a zero-padded field. Invalid UTF-8, which the program
+/// never writes, reads as an empty string.
+fn read_padded(field: &[u8]) -> &str {
+    let len = field
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |index| index.saturating_add(1));
+    std::str::from_utf8(&field[..len]).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_program::address_lookup_table::state as sdk,
+        std::{borrow::Cow, mem::size_of},
+    };
+
+    fn new_sdk_table(authority: Option<Pubkey>, num_addresses: usize) -> Vec<u8> {
+        let mut addresses = Vec::with_capacity(num_addresses);
+        addresses.resize_with(num_addresses, Pubkey::new_unique);
+        sdk::AddressLookupTable {
+            meta: sdk::LookupTableMeta {
+                deactivation_slot: 1234,
+                last_extended_slot: 5678,
+                last_extended_slot_start_index: 9,
+                authority,
+                _padding: 0,
+            },
+            addresses: Cow::Owned(addresses),
+        }
+        .serialize_for_tests()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lookup_table_meta_size() {
+        assert_eq!(size_of::<LookupTableMeta>(), LOOKUP_TABLE_META_SIZE);
+        assert_eq!(LOOKUP_TABLE_META_SIZE, sdk::LOOKUP_TABLE_META_SIZE);
+        assert_eq!(LOOKUP_TABLE_MAX_ADDRESSES, sdk::LOOKUP_TABLE_MAX_ADDRESSES);
+    }
+
+    #[test]
+    fn test_unpack_sdk_table() {
+        for authority in [None, Some(Pubkey::new_unique())] {
+            let data = new_sdk_table(authority, 10);
+            let expected = sdk::AddressLookupTable::deserialize(&data).unwrap();
+
+            let lookup_table = AddressLookupTable::unpack(&data).unwrap();
+            assert_eq!(
+                u64::from(lookup_table.meta.deactivation_slot),
+                expected.meta.deactivation_slot
+            );
+            assert_eq!(
+                u64::from(lookup_table.meta.last_extended_slot),
+                expected.meta.last_extended_slot
+            );
+            assert_eq!(
+                lookup_table.meta.last_extended_slot_start_index,
+                expected.meta.last_extended_slot_start_index
+            );
+            assert_eq!(lookup_table.meta.authority(), expected.meta.authority);
+            assert_eq!(lookup_table.addresses, &*expected.addresses);
+        }
+    }
+
+    #[test]
+    fn test_write_is_sdk_compatible() {
+        for authority in [None, Some(Pubkey::new_unique())] {
+            let mut data = new_sdk_table(Some(Pubkey::new_unique()), 3);
+            let mut expected = sdk::AddressLookupTable::deserialize(&data).unwrap().meta;
+            expected.authority = authority;
+
+            let lookup_table = AddressLookupTableMut::unpack(&mut data).unwrap();
+            lookup_table.meta.set_authority(authority);
+
+            let lookup_table = sdk::AddressLookupTable::deserialize(&data).unwrap();
+            assert_eq!(lookup_table.meta, expected);
+        }
+    }
+
+    #[test]
+    fn test_init_matches_sdk() {
+        let authority = Pubkey::new_unique();
+        let mut data = vec![0; LOOKUP_TABLE_META_SIZE];
+        AddressLookupTableMut::init(&mut data, LookupTableMeta::new(authority)).unwrap();
+
+        let mut expected = vec![0; LOOKUP_TABLE_META_SIZE];
+        sdk::AddressLookupTable::overwrite_meta_data(
+            &mut expected,
+            sdk::LookupTableMeta::new(authority),
+        )
+        .unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn test_extension_is_not_a_lookup_table() {
+        let mut data =
+            bytemuck::bytes_of(&LookupTableExtension::new(Pubkey::new_unique())).to_vec();
+        assert!(sdk::AddressLookupTable::deserialize(&data).is_err());
+        data.resize(LOOKUP_TABLE_META_SIZE, 0);
+        assert!(sdk::AddressLookupTable::deserialize(&data).is_err());
+        assert_eq!(
+            AddressLookupTable::unpack(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let data = new_sdk_table(Some(Pubkey::new_unique()), 0);
+        assert_eq!(
+            LookupTableExtension::unpack(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_extension_from_legacy() {
+        let mut extension = LookupTableExtension::new(Pubkey::new_unique());
+        extension.pending_authority = Some(Pubkey::new_unique()).try_into().unwrap();
+        extension.extender = Some(Pubkey::new_unique()).try_into().unwrap();
+        let legacy_data = &bytemuck::bytes_of(&extension)[..LEGACY_EXTENSION_LEN];
+
+        assert!(LookupTableExtension::is_legacy(legacy_data));
+        assert_eq!(
+            LookupTableExtension::unpack(legacy_data),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            LookupTableExtension::from_legacy(legacy_data),
+            Ok(extension)
+        );
+
+        let data = bytemuck::bytes_of(&extension);
+        assert!(!LookupTableExtension::is_legacy(data));
+        assert_eq!(
+            LookupTableExtension::from_legacy(data),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            LookupTableExtension::from_legacy(&[0; LEGACY_EXTENSION_LEN]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_extension_rejects_unknown_version() {
+        let mut extension = LookupTableExtension::new(Pubkey::new_unique());
+        assert_eq!(extension.version(), EXTENSION_VERSION);
+        extension.version = EXTENSION_VERSION.saturating_add(1);
+        assert_eq!(
+            LookupTableExtension::unpack(bytemuck::bytes_of(&extension)),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_status_with_schedule() {
+        let slot_hashes = SlotHashes::new(&[(99, Default::default())]);
+        let active = LookupTableMeta::new(Pubkey::new_unique());
+        let deactivating = LookupTableMeta {
+            deactivation_slot: 99.into(),
+            ..active
+        };
+
+        assert_eq!(
+            active.status_with_schedule(100, &slot_hashes, None),
+            LookupTableStatus::Activated
+        );
+        assert_eq!(
+            active.status_with_schedule(100, &slot_hashes, Some(150)),
+            LookupTableStatus::DeactivationScheduled {
+                remaining_slots: 50
+            }
+        );
+        assert!(active.is_active(100, &slot_hashes));
+
+        // The table stays active until the schedule is executed
+        for scheduled_slot in [100, 50] {
+            assert_eq!(
+                active.status_with_schedule(100, &slot_hashes, Some(scheduled_slot)),
+                LookupTableStatus::Activated
+            );
+        }
+
+        assert_eq!(
+            deactivating.status_with_schedule(100, &slot_hashes, Some(150)),
+            deactivating.status(100, &slot_hashes)
+        );
+    }
+
+    #[test]
+    fn test_scheduled_deactivation_slot() {
+        let mut extension = LookupTableExtension::new(Pubkey::new_unique());
+        assert_eq!(extension.scheduled_deactivation_slot(), None);
+        extension.set_scheduled_deactivation_slot(Some(123));
+        assert_eq!(extension.scheduled_deactivation_slot(), Some(123));
+        extension.set_scheduled_deactivation_slot(None);
+        assert_eq!(extension, LookupTableExtension::new(extension.lookup_table));
+    }
+
+    #[test]
+    fn test_truncation_cool_down_end_slot() {
+        let mut extension = LookupTableExtension::new(Pubkey::new_unique());
+        assert_eq!(extension.truncation_cool_down_end_slot(), None);
+        extension.set_truncation_cool_down_end_slot(456);
+        assert_eq!(extension.truncation_cool_down_end_slot(), Some(456));
+        assert_eq!(extension.scheduled_deactivation_slot(), None);
+    }
+
+    #[test]
+    fn test_multisig_is_not_a_lookup_table() {
+        let multisig = LookupTableMultisig::new(1, &[Pubkey::new_unique()]).unwrap();
+        let data = bytemuck::bytes_of(&multisig);
+        assert!(sdk::AddressLookupTable::deserialize(data).is_err());
+        assert_eq!(
+            AddressLookupTable::unpack(data),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(LookupTableMultisig::unpack(data), Ok(&multisig));
+    }
+
+    #[test]
+    fn test_new_multisig() {
+        let signers: Vec<Pubkey> = (0..MAX_MULTISIG_SIGNERS)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+
+        let multisig = LookupTableMultisig::new(2, &signers[..3]).unwrap();
+        assert_eq!(multisig.m, 2);
+        assert_eq!(multisig.n, 3);
+        assert_eq!(multisig.signers(), &signers[..3]);
+
+        assert!(LookupTableMultisig::new(1, &signers[..1]).is_ok());
+        assert!(LookupTableMultisig::new(11, &signers).is_ok());
+        assert_eq!(
+            LookupTableMultisig::new(0, &signers[..3]),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            LookupTableMultisig::new(4, &signers[..3]),
+            Err(ProgramError::InvalidArgument)
+        );
+        assert_eq!(
+            LookupTableMultisig::new(1, &[]),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        let mut too_many_signers = signers.clone();
+        too_many_signers.push(Pubkey::new_unique());
+        assert_eq!(
+            LookupTableMultisig::new(1, &too_many_signers),
+            Err(ProgramError::InvalidArgument)
+        );
+
+        let duplicate_signers = [signers[0], signers[1], signers[0]];
+        assert_eq!(
+            LookupTableMultisig::new(1, &duplicate_signers),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    #[test]
+    fn test_lookup_matches_sdk() {
+        let mut slot_hashes = SlotHashes::default();
+        slot_hashes.add(90, Default::default());
+
+        // (deactivation slot, last extended slot, current slot, indexes)
+        let cases: &[(Slot, Slot, Slot, &[u8])] = &[
+            (Slot::MAX, 5678, 5679, &[0, 9, 19]),
+            (Slot::MAX, 5678, 5679, &[20]),
+            (Slot::MAX, 5678, 5678, &[0, 8]),
+            (Slot::MAX, 5678, 5678, &[9]),
+            (100, 5678, 100, &[0]),
+            (90, 5678, 100, &[0]),
+            (80, 5678, 100, &[0]),
+            (Slot::MAX, 5678, 5679, &[]),
+        ];
+        for (deactivation_slot, last_extended_slot, current_slot, indexes) in cases {
+            let mut addresses = Vec::with_capacity(20);
+            addresses.resize_with(20, Pubkey::new_unique);
+            let sdk_table = sdk::AddressLookupTable {
+                meta: sdk::LookupTableMeta {
+                    deactivation_slot: *deactivation_slot,
+                    last_extended_slot: *last_extended_slot,
+                    last_extended_slot_start_index: 9,
+                    ..sdk::LookupTableMeta::default()
+                },
+                addresses: Cow::Owned(addresses),
+            };
+            let data = sdk_table.clone().serialize_for_tests().unwrap();
+            let table = AddressLookupTable::unpack(&data).unwrap();
+
+            let expected = sdk_table
+                .lookup(*current_slot, indexes, &slot_hashes)
+                .map_err(|err| format!("{err:?}"));
+            let actual = table
+                .lookup(*current_slot, indexes, &slot_hashes)
+                .map_err(|err| format!("{err:?}"));
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_unpack_invalid_data() {
+        assert_eq!(
+            AddressLookupTable::unpack(&[0; LOOKUP_TABLE_META_SIZE]),
+            Err(ProgramError::UninitializedAccount)
+        );
+        assert_eq!(
+            AddressLookupTable::unpack(&[0; LOOKUP_TABLE_META_SIZE - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let mut data = new_sdk_table(None, 1);
+        data.push(0);
+        assert_eq!(
+            AddressLookupTable::unpack(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let mut data = new_sdk_table(None, 1);
+        data[21] = 2;
+        assert!(sdk::AddressLookupTable::deserialize(&data).is_err());
+        assert_eq!(
+            AddressLookupTable::unpack(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_new_metadata() {
+        let lookup_table = Pubkey::new_unique();
+        let tags = vec!["defi".to_string(), "amm".to_string()];
+        let metadata =
+            LookupTableMetadata::new(lookup_table, "My table", "https://example.com", &tags)
+                .unwrap();
+        assert_eq!(metadata.lookup_table, lookup_table);
+        assert_eq!(metadata.name(), "My table");
+        assert_eq!(metadata.uri(), "https://example.com");
+        assert_eq!(metadata.tags(), vec!["defi", "amm"]);
+
+        let data = bytemuck::bytes_of(&metadata);
+        assert_eq!(data.len(), LookupTableMetadata::LEN);
+        assert_eq!(LookupTableMetadata::unpack(data), Ok(&metadata));
+
+        let empty = LookupTableMetadata::new(lookup_table, "", "", &[]).unwrap();
+        assert_eq!(empty.name(), "");
+        assert_eq!(empty.uri(), "");
+        assert!(empty.tags().is_empty());
+    }
+
+    #[test]
+    fn test_new_metadata_limits() {
+        let lookup_table = Pubkey::new_unique();
+        let max_name = "n".repeat(MAX_METADATA_NAME_LEN);
+        let max_uri = "u".repeat(MAX_METADATA_URI_LEN);
+        let max_tags = vec!["t".repeat(MAX_METADATA_TAG_LEN); MAX_METADATA_TAGS];
+        let metadata =
+            LookupTableMetadata::new(lookup_table, &max_name, &max_uri, &max_tags).unwrap();
+        assert_eq!(metadata.name(), max_name);
+        assert_eq!(metadata.uri(), max_uri);
+        assert_eq!(metadata.tags(), max_tags);
+
+        let too_long_name = format!("{max_name}n");
+        let too_long_uri = format!("{max_uri}u");
+        let too_long_tag = vec!["t".repeat(MAX_METADATA_TAG_LEN.saturating_add(1))];
+        let mut too_many_tags = max_tags.clone();
+        too_many_tags.push("t".to_string());
+        for (name, uri, tags) in [
+            (too_long_name.as_str(), "", &[][..]),
+            ("", too_long_uri.as_str(), &[][..]),
+            ("", "", &too_long_tag[..]),
+            ("", "", &too_many_tags[..]),
+            ("nul\0", "", &[][..]),
+            ("", "", &[String::new()][..]),
+        ] {
+            assert_eq!(
+                LookupTableMetadata::new(lookup_table, name, uri, tags),
+                Err(ProgramError::InvalidArgument)
+            );
+        }
+    }
+
+    #[test]
+    fn test_metadata_is_not_a_lookup_table() {
+        let metadata = LookupTableMetadata::new(Pubkey::new_unique(), "name", "", &[]).unwrap();
+        let data = bytemuck::bytes_of(&metadata);
+        assert_eq!(
+            AddressLookupTable::unpack(data),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            LookupTableExtension::unpack(&data[..LookupTableExtension::LEN]),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            LookupTableMetadata::unpack(&[0; LookupTableMetadata::LEN]),
+            Err(ProgramError::UninitializedAccount)
+        );
+    }
+}
@@ -0,0 +1,190 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, add_lookup_table_extension_account, assert_ix_error,
+        get_lookup_table_extension, lookup_error, new_address_lookup_table, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::accept_authority,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::state::AddressLookupTable,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+#[tokio::test]
+async fn test_accept_authority() {
+    let mut context = setup_test_context().await;
+
+    let new_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(Pubkey::new_unique()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(
+        &mut context,
+        lookup_table_address,
+        Some(new_authority.pubkey()),
+    )
+    .await;
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[accept_authority(
+            lookup_table_address,
+            new_authority.pubkey(),
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &new_authority],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.meta.authority, Some(new_authority.pubkey()));
+    assert_eq!(lookup_table.addresses.len(), 10);
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.pending_authority), None);
+}
+
+#[tokio::test]
+async fn test_accept_authority_with_wrong_signer() {
+    let mut context = setup_test_context().await;
+
+    let wrong_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(Pubkey::new_unique()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(
+        &mut context,
+        lookup_table_address,
+        Some(Pubkey::new_unique()),
+    )
+    .await;
+
+    let ix = accept_authority(lookup_table_address, wrong_authority.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_accept_authority_without_proposal() {
+    let mut context = setup_test_context().await;
+
+    let new_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(Pubkey::new_unique()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    // No extension account.
+    let ix = accept_authority(lookup_table_address, new_authority.pubkey());
+    assert_ix_error(
+        &mut context,
+        ix.clone(),
+        Some(&new_authority),
+        lookup_error(
+            AddressLookupError::NoPendingAuthority,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+
+    // Extension account without a pending authority. A new blockhash makes the
+    // runtime process the same instruction again.
+    add_lookup_table_extension_account(&mut context, lookup_table_address, None).await;
+    context.last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&new_authority),
+        lookup_error(
+            AddressLookupError::NoPendingAuthority,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_accept_authority_for_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let new_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(None, 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(
+        &mut context,
+        lookup_table_address,
+        Some(new_authority.pubkey()),
+    )
+    .await;
+
+    let ix = accept_authority(lookup_table_address, new_authority.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&new_authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_accept_authority_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let new_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(Pubkey::new_unique()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(
+        &mut context,
+        lookup_table_address,
+        Some(new_authority.pubkey()),
+    )
+    .await;
+
+    let mut ix = accept_authority(lookup_table_address, new_authority.pubkey());
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "test-sbf")]
+//! Reruns the test cases of the instructions that the native address lookup
+//! table builtin supports against the builtin, so that every expectation the
+//! program is held to is also checked against the implementation it
+//! replaces. Cases of behavior that only the program has return early when
+//! `common::runtime()` is `Runtime::Builtin`.
+
+// Every included file declares the `common` module itself
+#![allow(clippy::duplicate_mod)]
+
+#[path = "close_lookup_table_ix.rs"]
+mod close_lookup_table_ix;
+#[path = "create_lookup_table_ix.rs"]
+mod create_lookup_table_ix;
+#[path = "deactivate_lookup_table_ix.rs"]
+mod deactivate_lookup_table_ix;
+#[path = "extend_lookup_table_ix.rs"]
+mod extend_lookup_table_ix;
+#[path = "freeze_lookup_table_ix.rs"]
+mod freeze_lookup_table_ix;
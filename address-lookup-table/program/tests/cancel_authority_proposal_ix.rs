@@ -0,0 +1,131 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, add_lookup_table_extension_account, assert_ix_error,
+        get_lookup_table_extension, lookup_error, new_address_lookup_table, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::cancel_authority_proposal,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::state::AddressLookupTable,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn test_cancel_authority_proposal_by(signer_is_authority: bool) {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let new_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(
+        &mut context,
+        lookup_table_address,
+        Some(new_authority.pubkey()),
+    )
+    .await;
+
+    let signer = if signer_is_authority {
+        &authority
+    } else {
+        &new_authority
+    };
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[cancel_authority_proposal(
+            lookup_table_address,
+            signer.pubkey(),
+        )],
+        Some(&payer.pubkey()),
+        &[payer, signer],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.meta.authority, Some(authority.pubkey()));
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.pending_authority), None);
+}
+
+#[tokio::test]
+async fn test_cancel_authority_proposal_by_authority() {
+    test_cancel_authority_proposal_by(true).await;
+}
+
+#[tokio::test]
+async fn test_cancel_authority_proposal_by_pending_authority() {
+    test_cancel_authority_proposal_by(false).await;
+}
+
+#[tokio::test]
+async fn test_cancel_authority_proposal_with_wrong_signer() {
+    let mut context = setup_test_context().await;
+
+    let wrong_signer = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(Pubkey::new_unique()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(
+        &mut context,
+        lookup_table_address,
+        Some(Pubkey::new_unique()),
+    )
+    .await;
+
+    let ix = cancel_authority_proposal(lookup_table_address, wrong_signer.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_signer),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_cancel_authority_proposal_without_proposal() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(&mut context, lookup_table_address, None).await;
+
+    let ix = cancel_authority_proposal(lookup_table_address, authority.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::NoPendingAuthority,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
@@ -0,0 +1,268 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, lookup_error, new_address_lookup_table,
+        overwrite_slot_hashes_with_slots, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::clone_lookup_table,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::AccountSharedData,
+        address_lookup_table::{
+            program::id,
+            state::{AddressLookupTable, LOOKUP_TABLE_META_SIZE},
+        },
+        clock::{Clock, Slot},
+        instruction::InstructionError,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program,
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+const TEST_RECENT_SLOT: Slot = 123;
+
+async fn add_source_lookup_table(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+    deactivation_slot: Slot,
+) -> (Pubkey, AddressLookupTable<'static>) {
+    let initialized_table = {
+        let mut table = new_address_lookup_table(authority, 10);
+        table.meta.deactivation_slot = deactivation_slot;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table.clone()).await;
+    (lookup_table_address, initialized_table)
+}
+
+async fn test_clone_lookup_table(source_authority: Option<Pubkey>, deactivation_slot: Slot) {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[TEST_RECENT_SLOT]);
+
+    let (source_address, source_table) =
+        add_source_lookup_table(&mut context, source_authority, deactivation_slot).await;
+    let source_account = context
+        .banks_client
+        .get_account(source_address)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let authority_keypair = Keypair::new();
+    let (ix, lookup_table_address) = clone_lookup_table(
+        source_address,
+        authority_keypair.pubkey(),
+        payer.pubkey(),
+        TEST_RECENT_SLOT,
+        3,
+        5,
+    );
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let lookup_table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let expected_data_len = LOOKUP_TABLE_META_SIZE.saturating_add(PUBKEY_BYTES.saturating_mul(5));
+    assert_eq!(lookup_table_account.owner, id());
+    assert_eq!(lookup_table_account.data.len(), expected_data_len);
+    assert_eq!(
+        lookup_table_account.lamports,
+        Rent::default().minimum_balance(expected_data_len)
+    );
+
+    let lookup_table = AddressLookupTable::deserialize(&lookup_table_account.data).unwrap();
+    assert_eq!(lookup_table.meta.deactivation_slot, Slot::MAX);
+    assert_eq!(
+        lookup_table.meta.authority,
+        Some(authority_keypair.pubkey())
+    );
+    assert_eq!(lookup_table.meta.last_extended_slot, clock.slot);
+    assert_eq!(lookup_table.meta.last_extended_slot_start_index, 0);
+    assert_eq!(lookup_table.addresses[..], source_table.addresses[3..8]);
+
+    // The source table is left unchanged
+    assert_eq!(
+        client.get_account(source_address).await.unwrap(),
+        Some(source_account)
+    );
+}
+
+#[tokio::test]
+async fn test_clone_active_lookup_table() {
+    test_clone_lookup_table(Some(Pubkey::new_unique()), Slot::MAX).await;
+}
+
+#[tokio::test]
+async fn test_clone_frozen_lookup_table() {
+    test_clone_lookup_table(None, Slot::MAX).await;
+}
+
+#[tokio::test]
+async fn test_clone_deactivated_lookup_table() {
+    test_clone_lookup_table(Some(Pubkey::new_unique()), 0).await;
+}
+
+#[tokio::test]
+async fn test_clone_lookup_table_out_of_bounds() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[TEST_RECENT_SLOT]);
+
+    let (source_address, _) = add_source_lookup_table(&mut context, None, Slot::MAX).await;
+
+    let authority_keypair = Keypair::new();
+    for (start_index, num_addresses) in [(0, 0), (0, 11), (9, 2), (10, 1), (255, u16::MAX)] {
+        let (ix, _) = clone_lookup_table(
+            source_address,
+            authority_keypair.pubkey(),
+            context.payer.pubkey(),
+            TEST_RECENT_SLOT,
+            start_index,
+            num_addresses,
+        );
+        assert_ix_error(
+            &mut context,
+            ix,
+            Some(&authority_keypair),
+            lookup_error(
+                AddressLookupError::InvalidAddressRange,
+                InstructionError::InvalidInstructionData,
+            ),
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn test_clone_lookup_table_with_invalid_source_owner() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[TEST_RECENT_SLOT]);
+
+    let source_address = Pubkey::new_unique();
+    let data = new_address_lookup_table(None, 10)
+        .serialize_for_tests()
+        .unwrap();
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), &system_program::id());
+    account.set_data_from_slice(&data);
+    context.set_account(&source_address, &account);
+
+    let authority_keypair = Keypair::new();
+    let (ix, _) = clone_lookup_table(
+        source_address,
+        authority_keypair.pubkey(),
+        context.payer.pubkey(),
+        TEST_RECENT_SLOT,
+        0,
+        1,
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        InstructionError::InvalidAccountOwner,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_clone_lookup_table_already_initialized() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[TEST_RECENT_SLOT]);
+
+    let (source_address, _) = add_source_lookup_table(&mut context, None, Slot::MAX).await;
+
+    let authority_keypair = Keypair::new();
+    let (ix, lookup_table_address) = clone_lookup_table(
+        source_address,
+        authority_keypair.pubkey(),
+        context.payer.pubkey(),
+        TEST_RECENT_SLOT,
+        0,
+        1,
+    );
+    let initialized_table = new_address_lookup_table(Some(authority_keypair.pubkey()), 0);
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        InstructionError::AccountAlreadyInitialized,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_clone_lookup_table_missing_signer() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[TEST_RECENT_SLOT]);
+
+    let (source_address, _) = add_source_lookup_table(&mut context, None, Slot::MAX).await;
+
+    let (mut ix, _) = clone_lookup_table(
+        source_address,
+        Pubkey::new_unique(),
+        context.payer.pubkey(),
+        TEST_RECENT_SLOT,
+        0,
+        1,
+    );
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_clone_lookup_table_not_recent_slot() {
+    let mut context = setup_test_context().await;
+
+    let (source_address, _) = add_source_lookup_table(&mut context, None, Slot::MAX).await;
+
+    let authority_keypair = Keypair::new();
+    let (ix, _) = clone_lookup_table(
+        source_address,
+        authority_keypair.pubkey(),
+        context.payer.pubkey(),
+        Slot::MAX,
+        0,
+        1,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::NotRecentSlot,
+            InstructionError::InvalidInstructionData,
+        ),
+    )
+    .await;
+}
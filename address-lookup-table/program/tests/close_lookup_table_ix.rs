@@ -3,12 +3,17 @@
 use {
     assert_matches::assert_matches,
     common::{
-        add_lookup_table_account, assert_ix_error, new_address_lookup_table,
-        overwrite_slot_hashes_with_slots, setup_test_context,
+        add_lookup_table_account, add_lookup_table_extension_account, assert_ix_error,
+        lookup_error, new_address_lookup_table, overwrite_slot_hashes_with_slots, runtime,
+        set_lookup_table_metadata_account, setup_test_context, Runtime,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{close_lookup_table, close_lookup_table_with_accounts},
+        state::LookupTableMetadata,
     },
     solana_program_test::*,
     solana_sdk::{
-        address_lookup_table::instruction::close_lookup_table,
         clock::Clock,
         instruction::InstructionError,
         pubkey::Pubkey,
@@ -75,7 +80,10 @@ async fn test_close_lookup_table_not_deactivated() {
         &mut context,
         ix,
         Some(&authority_keypair),
-        InstructionError::InvalidArgument,
+        lookup_error(
+            AddressLookupError::TableNotDeactivated,
+            InstructionError::InvalidArgument,
+        ),
     )
     .await;
 }
@@ -107,7 +115,10 @@ async fn test_close_lookup_table_deactivated_in_current_slot() {
         &mut context,
         ix,
         Some(&authority_keypair),
-        InstructionError::InvalidArgument,
+        lookup_error(
+            AddressLookupError::DeactivationInProgress,
+            InstructionError::InvalidArgument,
+        ),
     )
     .await;
 }
@@ -138,7 +149,10 @@ async fn test_close_lookup_table_recently_deactivated() {
         &mut context,
         ix,
         Some(&authority_keypair),
-        InstructionError::InvalidArgument,
+        lookup_error(
+            AddressLookupError::DeactivationInProgress,
+            InstructionError::InvalidArgument,
+        ),
     )
     .await;
 }
@@ -162,7 +176,10 @@ async fn test_close_immutable_lookup_table() {
         &mut context,
         ix,
         Some(&authority),
-        InstructionError::Immutable,
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
     )
     .await;
 }
@@ -187,7 +204,10 @@ async fn test_close_lookup_table_with_wrong_authority() {
         &mut context,
         ix,
         Some(&wrong_authority),
-        InstructionError::IncorrectAuthority,
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
     )
     .await;
 }
@@ -216,3 +236,195 @@ async fn test_close_lookup_table_without_signing() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn test_close_lookup_table_with_metadata() {
+    // Only the program closes the accounts that belong to the table
+    if runtime() == Runtime::Builtin {
+        return;
+    }
+
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let lookup_table_address = Pubkey::new_unique();
+    let authority_keypair = Keypair::new();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority_keypair.pubkey()), 0);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    let metadata_address = set_lookup_table_metadata_account(
+        &mut context,
+        LookupTableMetadata::new(lookup_table_address, "name", "", &[]).unwrap(),
+    )
+    .await;
+
+    let recipient = Pubkey::new_unique();
+    let client = &mut context.banks_client;
+    let table_lamports = client.get_balance(lookup_table_address).await.unwrap();
+    let metadata_lamports = client.get_balance(metadata_address).await.unwrap();
+
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[close_lookup_table_with_accounts(
+            lookup_table_address,
+            authority_keypair.pubkey(),
+            recipient,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    assert!(client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(client
+        .get_account(metadata_address)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        client.get_balance(recipient).await.unwrap(),
+        table_lamports.saturating_add(metadata_lamports)
+    );
+}
+
+#[tokio::test]
+async fn test_close_lookup_table_with_extension() {
+    // Only the program closes the accounts that belong to the table
+    if runtime() == Runtime::Builtin {
+        return;
+    }
+
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let lookup_table_address = Pubkey::new_unique();
+    let authority_keypair = Keypair::new();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority_keypair.pubkey()), 0);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    let extension_address =
+        add_lookup_table_extension_account(&mut context, lookup_table_address, None).await;
+
+    let recipient = Pubkey::new_unique();
+    let client = &mut context.banks_client;
+    let table_lamports = client.get_balance(lookup_table_address).await.unwrap();
+    let extension_lamports = client.get_balance(extension_address).await.unwrap();
+
+    // The table has no metadata account, which is skipped
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[close_lookup_table_with_accounts(
+            lookup_table_address,
+            authority_keypair.pubkey(),
+            recipient,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    assert!(client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(client
+        .get_account(extension_address)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        client.get_balance(recipient).await.unwrap(),
+        table_lamports.saturating_add(extension_lamports)
+    );
+}
+
+#[tokio::test]
+async fn test_close_lookup_table_without_metadata_account() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let lookup_table_address = Pubkey::new_unique();
+    let authority_keypair = Keypair::new();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority_keypair.pubkey()), 0);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    // Passing the metadata address of a table without metadata is harmless
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[close_lookup_table_with_accounts(
+            lookup_table_address,
+            authority_keypair.pubkey(),
+            payer.pubkey(),
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    assert!(client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_close_lookup_table_with_metadata_as_recipient() {
+    // Only the program closes the accounts that belong to the table
+    if runtime() == Runtime::Builtin {
+        return;
+    }
+
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let lookup_table_address = Pubkey::new_unique();
+    let authority_keypair = Keypair::new();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority_keypair.pubkey()), 0);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    let metadata_address = set_lookup_table_metadata_account(
+        &mut context,
+        LookupTableMetadata::new(lookup_table_address, "name", "", &[]).unwrap(),
+    )
+    .await;
+
+    let ix = close_lookup_table_with_accounts(
+        lookup_table_address,
+        authority_keypair.pubkey(),
+        metadata_address,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::RecipientIsTable,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
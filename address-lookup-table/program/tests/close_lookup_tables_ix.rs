@@ -0,0 +1,313 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, add_lookup_table_extension_account, assert_ix_error,
+        lookup_error, new_address_lookup_table, overwrite_slot_hashes_with_slots,
+        set_lookup_table_metadata_account, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{close_lookup_tables, close_lookup_tables_with_accounts},
+        state::LookupTableMetadata,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn add_deactivated_lookup_tables(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+    count: usize,
+) -> Vec<Pubkey> {
+    let mut lookup_table_addresses = Vec::with_capacity(count);
+    for _ in 0..count {
+        let lookup_table_address = Pubkey::new_unique();
+        let initialized_table = {
+            let mut table = new_address_lookup_table(authority, 0);
+            table.meta.deactivation_slot = 0;
+            table
+        };
+        add_lookup_table_account(context, lookup_table_address, initialized_table).await;
+        lookup_table_addresses.push(lookup_table_address);
+    }
+    lookup_table_addresses
+}
+
+async fn get_lamports(context: &mut ProgramTestContext, address: Pubkey) -> u64 {
+    context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .map(|account| account.lamports)
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority = Keypair::new();
+    let lookup_table_addresses =
+        add_deactivated_lookup_tables(&mut context, Some(authority.pubkey()), 3).await;
+    let mut table_lamports = 0u64;
+    for lookup_table_address in &lookup_table_addresses {
+        table_lamports =
+            table_lamports.saturating_add(get_lamports(&mut context, *lookup_table_address).await);
+    }
+
+    let recipient = Pubkey::new_unique();
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[close_lookup_tables(
+            authority.pubkey(),
+            recipient,
+            &lookup_table_addresses,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority],
+        context.last_blockhash,
+    );
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+    for lookup_table_address in lookup_table_addresses {
+        assert!(context
+            .banks_client
+            .get_account(lookup_table_address)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(get_lamports(&mut context, recipient).await, table_lamports);
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables_with_accounts() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority = Keypair::new();
+    let lookup_table_addresses =
+        add_deactivated_lookup_tables(&mut context, Some(authority.pubkey()), 3).await;
+
+    // The first table has both accounts, the second neither and the third
+    // only an extension account
+    let metadata_address = set_lookup_table_metadata_account(
+        &mut context,
+        LookupTableMetadata::new(lookup_table_addresses[0], "name", "", &[]).unwrap(),
+    )
+    .await;
+    let mut closed_addresses = lookup_table_addresses.clone();
+    closed_addresses.push(metadata_address);
+    for lookup_table_address in [lookup_table_addresses[0], lookup_table_addresses[2]] {
+        closed_addresses.push(
+            add_lookup_table_extension_account(&mut context, lookup_table_address, None).await,
+        );
+    }
+    let mut closed_lamports = 0u64;
+    for address in &closed_addresses {
+        closed_lamports =
+            closed_lamports.saturating_add(get_lamports(&mut context, *address).await);
+    }
+
+    let recipient = Pubkey::new_unique();
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[close_lookup_tables_with_accounts(
+            authority.pubkey(),
+            recipient,
+            &lookup_table_addresses,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority],
+        context.last_blockhash,
+    );
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+    for address in closed_addresses {
+        assert!(context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    assert_eq!(get_lamports(&mut context, recipient).await, closed_lamports);
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables_is_atomic() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority = Keypair::new();
+    let mut lookup_table_addresses =
+        add_deactivated_lookup_tables(&mut context, Some(authority.pubkey()), 2).await;
+    let active_table_address = Pubkey::new_unique();
+    add_lookup_table_account(
+        &mut context,
+        active_table_address,
+        new_address_lookup_table(Some(authority.pubkey()), 0),
+    )
+    .await;
+    lookup_table_addresses.push(active_table_address);
+
+    let ix = close_lookup_tables(
+        authority.pubkey(),
+        context.payer.pubkey(),
+        &lookup_table_addresses,
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableNotDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+
+    // The tables before the offending one are left open
+    for lookup_table_address in lookup_table_addresses {
+        assert!(context
+            .banks_client
+            .get_account(lookup_table_address)
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority = Keypair::new();
+    let mut lookup_table_addresses =
+        add_deactivated_lookup_tables(&mut context, Some(authority.pubkey()), 1).await;
+    lookup_table_addresses
+        .extend(add_deactivated_lookup_tables(&mut context, Some(Pubkey::new_unique()), 1).await);
+
+    let ix = close_lookup_tables(
+        authority.pubkey(),
+        context.payer.pubkey(),
+        &lookup_table_addresses,
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables_with_frozen_table() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority = Keypair::new();
+    let lookup_table_addresses = add_deactivated_lookup_tables(&mut context, None, 1).await;
+
+    let ix = close_lookup_tables(
+        authority.pubkey(),
+        context.payer.pubkey(),
+        &lookup_table_addresses,
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables_recipient_is_table() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority = Keypair::new();
+    let lookup_table_addresses =
+        add_deactivated_lookup_tables(&mut context, Some(authority.pubkey()), 2).await;
+
+    let ix = close_lookup_tables(
+        authority.pubkey(),
+        lookup_table_addresses[1],
+        &lookup_table_addresses,
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::RecipientIsTable,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables_with_duplicate_table() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority = Keypair::new();
+    let lookup_table_addresses =
+        add_deactivated_lookup_tables(&mut context, Some(authority.pubkey()), 1).await;
+
+    // The second occurrence finds the table already closed
+    let ix = close_lookup_tables(
+        authority.pubkey(),
+        context.payer.pubkey(),
+        &[lookup_table_addresses[0], lookup_table_addresses[0]],
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::FailedToDeserialize,
+            InstructionError::InvalidAccountData,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_close_lookup_tables_without_tables() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let ix = close_lookup_tables(authority.pubkey(), context.payer.pubkey(), &[]);
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::NotEnoughAccountKeys,
+    )
+    .await;
+}
@@ -2,6 +2,21 @@
 #![allow(dead_code)]
 
 use {
+    solana_address_lookup_table_fixture::{
+        Fixture, FixtureAccount, FixtureAccountMeta, FixtureEffects, FixtureInstruction,
+        FixtureSlotHash, FixtureSysvars,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{
+            derive_lookup_table_extension_address, derive_lookup_table_metadata_address,
+            AddressLookupInstruction,
+        },
+        processor::process,
+        state::{
+            LookupTableExtension, LookupTableMetadata, LookupTableMultisig, LEGACY_EXTENSION_LEN,
+        },
+    },
     solana_program_test::*,
     solana_sdk::{
         account::AccountSharedData,
@@ -9,7 +24,7 @@ use {
             program::id,
             state::{AddressLookupTable, LookupTableMeta},
         },
-        clock::Slot,
+        clock::{Clock, Slot},
         hash::Hash,
         instruction::{Instruction, InstructionError},
         pubkey::Pubkey,
@@ -17,16 +32,83 @@ use {
         slot_hashes::SlotHashes,
         transaction::{Transaction, TransactionError},
     },
-    std::borrow::Cow,
+    std::{borrow::Cow, path::Path},
 };
 
+/// Directory that `process_transaction` exports conformance fixtures to
+pub const FIXTURES_DIR_ENV: &str = "CONFORMANCE_FIXTURES_DIR";
+
+/// Name of the test binary that reruns the cases of the instructions the
+/// native builtin supports against the builtin
+const BUILTIN_TESTS: &str = "builtin";
+
+/// Implementation of the address lookup table program to run test cases
+/// against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Runtime {
+    /// The program built from this crate
+    Program,
+    /// The native builtin of the runtime
+    Builtin,
+}
+
+/// Returns the runtime that the cases of the current test binary run against
+pub fn runtime() -> Runtime {
+    if env!("CARGO_CRATE_NAME") == BUILTIN_TESTS {
+        Runtime::Builtin
+    } else {
+        Runtime::Program
+    }
+}
+
 pub async fn setup_test_context() -> ProgramTestContext {
-    let program_test = ProgramTest::new(
-        "solana_address_lookup_table_program",
-        id(),
-        processor!(solana_address_lookup_table_program::processor::process),
+    start_program_test(runtime()).await
+}
+
+pub async fn start_program_test(runtime: Runtime) -> ProgramTestContext {
+    match runtime {
+        Runtime::Program => {
+            let program_test = ProgramTest::new(
+                "solana_address_lookup_table_program",
+                id(),
+                processor!(process),
+            );
+            let mut context = program_test.start_with_context().await;
+            assert_program_loaded(&mut context).await;
+            context
+        }
+        Runtime::Builtin => ProgramTest::default().start_with_context().await,
+    }
+}
+
+/// Checks that transactions reach the program rather than the native builtin,
+/// which owns the same address and is not replaced by the program on every
+/// runtime version. Only the program knows `MigrateLookupTable`, so the
+/// builtin rejects it as invalid instruction data.
+async fn assert_program_loaded(context: &mut ProgramTestContext) {
+    let ix =
+        Instruction::new_with_bincode(id(), &AddressLookupInstruction::MigrateLookupTable, vec![]);
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let result = context
+        .banks_client
+        .simulate_transaction(transaction)
+        .await
+        .unwrap()
+        .result
+        .unwrap();
+    assert_ne!(
+        result,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidInstructionData
+        )),
+        "the native builtin is processing transactions in place of the program",
     );
-    program_test.start_with_context().await
 }
 
 pub async fn assert_ix_error(
@@ -52,13 +134,156 @@ pub async fn assert_ix_error(
     );
 
     assert_eq!(
-        client
+        process_transaction(client, transaction).await.unwrap_err(),
+        TransactionError::InstructionError(0, expected_err),
+    );
+}
+
+/// Processes `transaction`. If `CONFORMANCE_FIXTURES_DIR` is set, its
+/// instruction is also exported to that directory as a conformance fixture
+/// named after the test.
+pub async fn process_transaction(
+    client: &mut BanksClient,
+    transaction: Transaction,
+) -> Result<(), TransactionError> {
+    let Some(fixtures_dir) = std::env::var_os(FIXTURES_DIR_ENV) else {
+        return client
             .process_transaction(transaction)
             .await
-            .unwrap_err()
-            .unwrap(),
-        TransactionError::InstructionError(0, expected_err),
+            .map_err(|err| err.unwrap());
+    };
+
+    let name = format!(
+        "{}-{}",
+        env!("CARGO_CRATE_NAME"),
+        std::thread::current().name().unwrap_or("unnamed"),
     );
+    let (result, fixture) = capture_fixture(client, transaction, name).await;
+    if let Some(fixture) = fixture {
+        fixture.save(Path::new(&fixtures_dir)).unwrap();
+    }
+    result
+}
+
+async fn get_fixture_accounts(
+    client: &mut BanksClient,
+    addresses: &[Pubkey],
+) -> Vec<FixtureAccount> {
+    let mut accounts = Vec::with_capacity(addresses.len());
+    for address in addresses {
+        let account = client.get_account(*address).await.unwrap();
+        accounts.push(FixtureAccount::new(*address, account));
+    }
+    accounts
+}
+
+/// Processes `transaction` and captures its only instruction as a fixture
+/// named `name`.
+///
+/// Returns the result of the transaction, along with the fixture if the
+/// instruction was executed. The fee paid by the transaction is not part of
+/// the expected state, so fixtures can be replayed with any fee payer.
+pub async fn capture_fixture(
+    client: &mut BanksClient,
+    transaction: Transaction,
+    name: String,
+) -> (Result<(), TransactionError>, Option<Fixture>) {
+    let message = &transaction.message;
+    let [compiled_instruction] = message.instructions.as_slice() else {
+        panic!("Only transactions with a single instruction can be captured");
+    };
+    let mut addresses = Vec::new();
+    let mut account_metas = Vec::with_capacity(compiled_instruction.accounts.len());
+    for index in compiled_instruction
+        .accounts
+        .iter()
+        .map(|i| usize::from(*i))
+    {
+        let pubkey = message.account_keys[index];
+        if !addresses.contains(&pubkey) {
+            addresses.push(pubkey);
+        }
+        account_metas.push(FixtureAccountMeta {
+            pubkey,
+            is_signer: message.is_signer(index),
+            is_writable: message.is_writable(index),
+        });
+    }
+    let instruction = FixtureInstruction {
+        program_id: message.account_keys[usize::from(compiled_instruction.program_id_index)],
+        accounts: account_metas,
+        data: compiled_instruction.data.clone(),
+    };
+
+    let slot_hashes = client.get_sysvar::<SlotHashes>().await.unwrap();
+    let sysvars = FixtureSysvars {
+        clock: client.get_sysvar::<Clock>().await.unwrap(),
+        rent: client.get_rent().await.unwrap(),
+        slot_hashes: slot_hashes
+            .iter()
+            .map(|(slot, hash)| FixtureSlotHash {
+                slot: *slot,
+                hash: *hash,
+            })
+            .collect(),
+    };
+    let fee_payer = message.account_keys[0];
+    let fee = client
+        .get_fee_for_message(message.clone())
+        .await
+        .unwrap()
+        .unwrap_or_default();
+    let accounts = get_fixture_accounts(client, &addresses).await;
+
+    let result = client
+        .process_transaction_with_metadata(transaction)
+        .await
+        .unwrap();
+    let error = match &result.result {
+        Ok(()) => None,
+        Err(TransactionError::InstructionError(0, err)) => Some(err.clone()),
+        Err(_) => return (result.result, None),
+    };
+
+    let mut expected_accounts = get_fixture_accounts(client, &addresses).await;
+    if let Some(account) = expected_accounts
+        .iter_mut()
+        .find(|account| account.address == fee_payer)
+    {
+        account.lamports = account.lamports.saturating_add(fee);
+    }
+    let return_data = result
+        .metadata
+        .and_then(|metadata| metadata.return_data)
+        .map(|return_data| return_data.data)
+        .unwrap_or_default();
+
+    let fixture = Fixture {
+        name,
+        sysvars,
+        instruction,
+        accounts,
+        expected: FixtureEffects {
+            error,
+            accounts: expected_accounts,
+            return_data,
+        },
+    };
+    (result.result, Some(fixture))
+}
+
+/// Returns the error the program reports for `err`. Tests also state the
+/// error of the native program for the same condition, which is reported
+/// instead by the builtin, and by the program when it is built with the
+/// `native-errors` feature and `err` has a native counterpart.
+pub fn lookup_error(err: AddressLookupError, native_err: InstructionError) -> InstructionError {
+    if runtime() == Runtime::Builtin
+        || cfg!(feature = "native-errors") && err.native_error().is_some()
+    {
+        native_err
+    } else {
+        InstructionError::Custom(err as u32)
+    }
 }
 
 pub fn new_address_lookup_table(
@@ -99,3 +324,132 @@ pub fn overwrite_slot_hashes_with_slots(context: &ProgramTestContext, slots: &[S
     }
     context.set_sysvar(&slot_hashes);
 }
+
+pub async fn add_lookup_table_extension_account(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+    pending_authority: Option<Pubkey>,
+) -> Pubkey {
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.pending_authority = pending_authority.try_into().unwrap();
+    set_lookup_table_extension_account(context, extension).await
+}
+
+pub async fn set_lookup_table_extension_account(
+    context: &mut ProgramTestContext,
+    extension: LookupTableExtension,
+) -> Pubkey {
+    let (extension_address, _) = derive_lookup_table_extension_address(&extension.lookup_table);
+    let data = bytemuck::bytes_of(&extension);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let rent_exempt_balance = rent.minimum_balance(data.len());
+
+    let mut account = AccountSharedData::new(rent_exempt_balance, data.len(), &id());
+    account.set_data_from_slice(data);
+    context.set_account(&extension_address, &account);
+
+    extension_address
+}
+
+pub async fn set_legacy_lookup_table_extension_account(
+    context: &mut ProgramTestContext,
+    extension: LookupTableExtension,
+) -> Pubkey {
+    let (extension_address, _) = derive_lookup_table_extension_address(&extension.lookup_table);
+    let data = &bytemuck::bytes_of(&extension)[..LEGACY_EXTENSION_LEN];
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let rent_exempt_balance = rent.minimum_balance(data.len());
+
+    let mut account = AccountSharedData::new(rent_exempt_balance, data.len(), &id());
+    account.set_data_from_slice(data);
+    context.set_account(&extension_address, &account);
+
+    extension_address
+}
+
+pub async fn get_lookup_table_extension(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> LookupTableExtension {
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    let account = context
+        .banks_client
+        .get_account(extension_address)
+        .await
+        .unwrap()
+        .unwrap();
+    *LookupTableExtension::unpack(&account.data).unwrap()
+}
+
+pub async fn set_lookup_table_metadata_account(
+    context: &mut ProgramTestContext,
+    metadata: LookupTableMetadata,
+) -> Pubkey {
+    let (metadata_address, _) = derive_lookup_table_metadata_address(&metadata.lookup_table);
+    let data = bytemuck::bytes_of(&metadata);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let rent_exempt_balance = rent.minimum_balance(data.len());
+
+    let mut account = AccountSharedData::new(rent_exempt_balance, data.len(), &id());
+    account.set_data_from_slice(data);
+    context.set_account(&metadata_address, &account);
+
+    metadata_address
+}
+
+pub async fn get_lookup_table_metadata(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> LookupTableMetadata {
+    let (metadata_address, _) = derive_lookup_table_metadata_address(&lookup_table_address);
+    let account = context
+        .banks_client
+        .get_account(metadata_address)
+        .await
+        .unwrap()
+        .unwrap();
+    *LookupTableMetadata::unpack(&account.data).unwrap()
+}
+
+pub async fn add_multisig_account(
+    context: &mut ProgramTestContext,
+    m: u8,
+    signers: &[Pubkey],
+) -> Pubkey {
+    let multisig = LookupTableMultisig::new(m, signers).unwrap();
+    let data = bytemuck::bytes_of(&multisig);
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let rent_exempt_balance = rent.minimum_balance(data.len());
+
+    let multisig_address = Pubkey::new_unique();
+    let mut account = AccountSharedData::new(rent_exempt_balance, data.len(), &id());
+    account.set_data_from_slice(data);
+    context.set_account(&multisig_address, &account);
+
+    multisig_address
+}
+
+pub async fn process_ix_with_signers(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = recent_blockhash;
+
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &all_signers,
+        recent_blockhash,
+    );
+
+    process_transaction(client, transaction).await
+}
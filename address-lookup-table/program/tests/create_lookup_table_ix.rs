@@ -2,11 +2,14 @@
 
 use {
     assert_matches::assert_matches,
-    common::{assert_ix_error, overwrite_slot_hashes_with_slots, setup_test_context},
+    common::{assert_ix_error, lookup_error, overwrite_slot_hashes_with_slots, setup_test_context},
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{create_lookup_table, create_lookup_table_signed},
+    },
     solana_program_test::*,
     solana_sdk::{
         address_lookup_table::{
-            instruction::{create_lookup_table, create_lookup_table_signed},
             program::id,
             state::{AddressLookupTable, LOOKUP_TABLE_META_SIZE},
         },
@@ -190,7 +193,10 @@ async fn test_create_lookup_table_not_recent_slot() {
         &mut context,
         ix,
         None,
-        InstructionError::InvalidInstructionData,
+        lookup_error(
+            AddressLookupError::NotRecentSlot,
+            InstructionError::InvalidInstructionData,
+        ),
     )
     .await;
 }
@@ -206,5 +212,14 @@ async fn test_create_lookup_table_pda_mismatch() {
     let mut ix = create_lookup_table(authority_address, payer.pubkey(), test_recent_slot).0;
     ix.accounts[0].pubkey = Pubkey::new_unique();
 
-    assert_ix_error(&mut context, ix, None, InstructionError::InvalidArgument).await;
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        lookup_error(
+            AddressLookupError::AddressMismatch,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
 }
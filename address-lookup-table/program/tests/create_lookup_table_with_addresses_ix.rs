@@ -0,0 +1,197 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, lookup_error, new_address_lookup_table,
+        overwrite_slot_hashes_with_slots, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::create_lookup_table_with_addresses,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::{
+            program::id,
+            state::{AddressLookupTable, LOOKUP_TABLE_META_SIZE},
+        },
+        clock::{Clock, Slot},
+        instruction::InstructionError,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+        rent::Rent,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn test_create_lookup_table_with_addresses(freeze: bool) {
+    let mut context = setup_test_context().await;
+
+    let test_recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[test_recent_slot]);
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let authority_keypair = Keypair::new();
+    let addresses: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+    let (ix, lookup_table_address) = create_lookup_table_with_addresses(
+        authority_keypair.pubkey(),
+        payer.pubkey(),
+        test_recent_slot,
+        addresses.clone(),
+        freeze,
+    );
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let lookup_table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let expected_data_len =
+        LOOKUP_TABLE_META_SIZE.saturating_add(addresses.len().saturating_mul(PUBKEY_BYTES));
+    assert_eq!(lookup_table_account.owner, id());
+    assert_eq!(lookup_table_account.data.len(), expected_data_len);
+    assert_eq!(
+        lookup_table_account.lamports,
+        Rent::default().minimum_balance(expected_data_len)
+    );
+
+    let expected_authority = if freeze {
+        None
+    } else {
+        Some(authority_keypair.pubkey())
+    };
+    let lookup_table = AddressLookupTable::deserialize(&lookup_table_account.data).unwrap();
+    assert_eq!(lookup_table.meta.deactivation_slot, Slot::MAX);
+    assert_eq!(lookup_table.meta.authority, expected_authority);
+    assert_eq!(lookup_table.meta.last_extended_slot, clock.slot);
+    assert_eq!(lookup_table.meta.last_extended_slot_start_index, 0);
+    assert_eq!(lookup_table.addresses.to_vec(), addresses);
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_addresses_mutable() {
+    test_create_lookup_table_with_addresses(false).await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_addresses_frozen() {
+    test_create_lookup_table_with_addresses(true).await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_no_addresses() {
+    let mut context = setup_test_context().await;
+
+    let test_recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[test_recent_slot]);
+
+    let authority_keypair = Keypair::new();
+    let (ix, _) = create_lookup_table_with_addresses(
+        authority_keypair.pubkey(),
+        context.payer.pubkey(),
+        test_recent_slot,
+        vec![],
+        true,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::EmptyExtend,
+            InstructionError::InvalidInstructionData,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_addresses_already_initialized() {
+    let mut context = setup_test_context().await;
+
+    let test_recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[test_recent_slot]);
+
+    let authority_keypair = Keypair::new();
+    let (ix, lookup_table_address) = create_lookup_table_with_addresses(
+        authority_keypair.pubkey(),
+        context.payer.pubkey(),
+        test_recent_slot,
+        vec![Pubkey::new_unique()],
+        false,
+    );
+    let initialized_table = new_address_lookup_table(Some(authority_keypair.pubkey()), 0);
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        InstructionError::AccountAlreadyInitialized,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_addresses_missing_signer() {
+    let mut context = setup_test_context().await;
+
+    let test_recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[test_recent_slot]);
+
+    let (mut ix, _) = create_lookup_table_with_addresses(
+        Pubkey::new_unique(),
+        context.payer.pubkey(),
+        test_recent_slot,
+        vec![Pubkey::new_unique()],
+        false,
+    );
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_addresses_not_recent_slot() {
+    let mut context = setup_test_context().await;
+
+    let authority_keypair = Keypair::new();
+    let (ix, _) = create_lookup_table_with_addresses(
+        authority_keypair.pubkey(),
+        context.payer.pubkey(),
+        Slot::MAX,
+        vec![Pubkey::new_unique()],
+        false,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::NotRecentSlot,
+            InstructionError::InvalidInstructionData,
+        ),
+    )
+    .await;
+}
@@ -0,0 +1,246 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, get_lookup_table_extension, lookup_error,
+        new_address_lookup_table, overwrite_slot_hashes_with_slots, process_ix_with_signers,
+        set_lookup_table_extension_account, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{
+            close_lookup_table_with_accounts, create_lookup_table_with_seed,
+            deactivate_lookup_table, derive_lookup_table_address_with_seed,
+            derive_lookup_table_extension_address,
+        },
+        state::{LookupTableExtension, EXTENSION_FLAG_SEED_DERIVED},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::{
+            program::id,
+            state::{AddressLookupTable, LOOKUP_TABLE_META_SIZE},
+        },
+        clock::{Clock, Slot},
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::Signer,
+        signer::keypair::Keypair,
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed() {
+    let mut context = setup_test_context().await;
+
+    // Seed-derived tables don't depend on recent slots
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let authority_keypair = Keypair::new();
+    let seed = [7; 32];
+    let (ix, lookup_table_address) =
+        create_lookup_table_with_seed(authority_keypair.pubkey(), payer.pubkey(), seed);
+    assert_eq!(
+        lookup_table_address,
+        derive_lookup_table_address_with_seed(&authority_keypair.pubkey(), &seed).0
+    );
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let lookup_table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(lookup_table_account.owner, id());
+    assert_eq!(lookup_table_account.data.len(), LOOKUP_TABLE_META_SIZE);
+    assert_eq!(
+        lookup_table_account.lamports,
+        Rent::default().minimum_balance(LOOKUP_TABLE_META_SIZE)
+    );
+    let lookup_table = AddressLookupTable::deserialize(&lookup_table_account.data).unwrap();
+    assert_eq!(lookup_table.meta.deactivation_slot, Slot::MAX);
+    assert_eq!(
+        lookup_table.meta.authority,
+        Some(authority_keypair.pubkey())
+    );
+    assert_eq!(lookup_table.meta.last_extended_slot, 0);
+    assert_eq!(lookup_table.meta.last_extended_slot_start_index, 0);
+    assert_eq!(lookup_table.addresses.len(), 0);
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.lookup_table, lookup_table_address);
+    assert!(extension.is_seed_derived());
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed_after_close() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority_keypair = Keypair::new();
+    let (create_ix, lookup_table_address) =
+        create_lookup_table_with_seed(authority_keypair.pubkey(), context.payer.pubkey(), [7; 32]);
+    let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+    assert_matches!(
+        process_ix_with_signers(&mut context, create_ix.clone(), &[&authority_keypair]).await,
+        Ok(())
+    );
+    assert_matches!(
+        process_ix_with_signers(
+            &mut context,
+            deactivate_lookup_table(lookup_table_address, authority_keypair.pubkey()),
+            &[&authority_keypair],
+        )
+        .await,
+        Ok(())
+    );
+
+    // Advance past the deactivation slot so that the table can be closed
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    context.set_sysvar(&Clock {
+        slot: clock.slot.saturating_add(1),
+        ..clock
+    });
+    let close_ix = close_lookup_table_with_accounts(
+        lookup_table_address,
+        authority_keypair.pubkey(),
+        Pubkey::new_unique(),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, close_ix, &[&authority_keypair]).await,
+        Ok(())
+    );
+    let client = &mut context.banks_client;
+    assert!(client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(client
+        .get_account(extension_address)
+        .await
+        .unwrap()
+        .is_some());
+
+    assert_ix_error(
+        &mut context,
+        create_ix,
+        Some(&authority_keypair),
+        InstructionError::Custom(AddressLookupError::TableAddressClosed as u32),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed_existing_extension() {
+    let mut context = setup_test_context().await;
+
+    let authority_keypair = Keypair::new();
+    let (ix, lookup_table_address) =
+        create_lookup_table_with_seed(authority_keypair.pubkey(), context.payer.pubkey(), [7; 32]);
+
+    // Left behind by a previous table at the same address
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.flags = EXTENSION_FLAG_SEED_DERIVED.into();
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        InstructionError::Custom(AddressLookupError::TableAddressClosed as u32),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed_already_initialized() {
+    let mut context = setup_test_context().await;
+
+    let authority_keypair = Keypair::new();
+    let (ix, lookup_table_address) =
+        create_lookup_table_with_seed(authority_keypair.pubkey(), context.payer.pubkey(), [7; 32]);
+    let initialized_table = new_address_lookup_table(Some(authority_keypair.pubkey()), 0);
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        InstructionError::AccountAlreadyInitialized,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed_missing_signer() {
+    let mut context = setup_test_context().await;
+
+    let (mut ix, _) =
+        create_lookup_table_with_seed(Pubkey::new_unique(), context.payer.pubkey(), [7; 32]);
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed_pda_mismatch() {
+    let mut context = setup_test_context().await;
+
+    let authority_keypair = Keypair::new();
+    let (mut ix, _) =
+        create_lookup_table_with_seed(authority_keypair.pubkey(), context.payer.pubkey(), [7; 32]);
+    ix.accounts[0].pubkey = Pubkey::new_unique();
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::AddressMismatch,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed_wrong_extension_address() {
+    let mut context = setup_test_context().await;
+
+    let authority_keypair = Keypair::new();
+    let (mut ix, _) =
+        create_lookup_table_with_seed(authority_keypair.pubkey(), context.payer.pubkey(), [7; 32]);
+    ix.accounts[3].pubkey = Pubkey::new_unique();
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::AddressMismatch,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
@@ -3,11 +3,15 @@
 use {
     assert_matches::assert_matches,
     common::{
-        add_lookup_table_account, assert_ix_error, new_address_lookup_table, setup_test_context,
+        add_lookup_table_account, assert_ix_error, lookup_error, new_address_lookup_table,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::deactivate_lookup_table,
     },
     solana_program_test::*,
     solana_sdk::{
-        address_lookup_table::{instruction::deactivate_lookup_table, state::AddressLookupTable},
+        address_lookup_table::state::AddressLookupTable,
         instruction::InstructionError,
         pubkey::Pubkey,
         signature::{Keypair, Signer},
@@ -73,7 +77,10 @@ async fn test_deactivate_immutable_lookup_table() {
         &mut context,
         ix,
         Some(&authority),
-        InstructionError::Immutable,
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
     )
     .await;
 }
@@ -97,7 +104,10 @@ async fn test_deactivate_already_deactivated() {
         &mut context,
         ix,
         Some(&authority),
-        InstructionError::InvalidArgument,
+        lookup_error(
+            AddressLookupError::TableDeactivated,
+            InstructionError::InvalidArgument,
+        ),
     )
     .await;
 }
@@ -118,7 +128,10 @@ async fn test_deactivate_lookup_table_with_wrong_authority() {
         &mut context,
         ix,
         Some(&wrong_authority),
-        InstructionError::IncorrectAuthority,
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
     )
     .await;
 }
@@ -0,0 +1,260 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, lookup_error, new_address_lookup_table,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::deactivate_lookup_tables,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::AccountSharedData,
+        address_lookup_table::state::AddressLookupTable,
+        clock::{Clock, Slot},
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_program,
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn add_lookup_tables(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+    count: usize,
+) -> Vec<Pubkey> {
+    let mut lookup_table_addresses = Vec::with_capacity(count);
+    for _ in 0..count {
+        let lookup_table_address = Pubkey::new_unique();
+        let initialized_table = new_address_lookup_table(authority, 10);
+        add_lookup_table_account(context, lookup_table_address, initialized_table).await;
+        lookup_table_addresses.push(lookup_table_address);
+    }
+    lookup_table_addresses
+}
+
+async fn get_deactivation_slot(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> Slot {
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    AddressLookupTable::deserialize(&table_account.data)
+        .unwrap()
+        .meta
+        .deactivation_slot
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables() {
+    let mut context = setup_test_context().await;
+    let current_slot = 42;
+    context.set_sysvar(&Clock {
+        slot: current_slot,
+        ..Clock::default()
+    });
+
+    let authority = Keypair::new();
+    let lookup_table_addresses = add_lookup_tables(&mut context, Some(authority.pubkey()), 3).await;
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[deactivate_lookup_tables(
+            authority.pubkey(),
+            &lookup_table_addresses,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority],
+        context.last_blockhash,
+    );
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+    for lookup_table_address in lookup_table_addresses {
+        assert_eq!(
+            get_deactivation_slot(&mut context, lookup_table_address).await,
+            current_slot
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables_is_atomic() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let mut lookup_table_addresses =
+        add_lookup_tables(&mut context, Some(authority.pubkey()), 2).await;
+    let already_deactivated_address = Pubkey::new_unique();
+    let already_deactivated_table = {
+        let mut table = new_address_lookup_table(Some(authority.pubkey()), 10);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    add_lookup_table_account(
+        &mut context,
+        already_deactivated_address,
+        already_deactivated_table,
+    )
+    .await;
+    lookup_table_addresses.push(already_deactivated_address);
+
+    let ix = deactivate_lookup_tables(authority.pubkey(), &lookup_table_addresses);
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+
+    // The tables before the offending one are left active
+    for lookup_table_address in &lookup_table_addresses[..2] {
+        assert_eq!(
+            get_deactivation_slot(&mut context, *lookup_table_address).await,
+            Slot::MAX
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let mut lookup_table_addresses =
+        add_lookup_tables(&mut context, Some(authority.pubkey()), 2).await;
+    lookup_table_addresses
+        .extend(add_lookup_tables(&mut context, Some(Pubkey::new_unique()), 1).await);
+
+    let ix = deactivate_lookup_tables(authority.pubkey(), &lookup_table_addresses);
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables_with_frozen_table() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let mut lookup_table_addresses = add_lookup_tables(&mut context, None, 1).await;
+    lookup_table_addresses
+        .extend(add_lookup_tables(&mut context, Some(authority.pubkey()), 1).await);
+
+    let ix = deactivate_lookup_tables(authority.pubkey(), &lookup_table_addresses);
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables_with_duplicate_table() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_addresses = add_lookup_tables(&mut context, Some(authority.pubkey()), 1).await;
+
+    // The second occurrence finds the table already deactivated
+    let ix = deactivate_lookup_tables(
+        authority.pubkey(),
+        &[lookup_table_addresses[0], lookup_table_addresses[0]],
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables_with_invalid_owner() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let mut lookup_table_addresses =
+        add_lookup_tables(&mut context, Some(authority.pubkey()), 1).await;
+    let invalid_table_address = Pubkey::new_unique();
+    let invalid_table_data = new_address_lookup_table(Some(authority.pubkey()), 10)
+        .serialize_for_tests()
+        .unwrap();
+    let mut invalid_table_account =
+        AccountSharedData::new(1, invalid_table_data.len(), &system_program::id());
+    invalid_table_account.set_data_from_slice(&invalid_table_data);
+    context.set_account(&invalid_table_address, &invalid_table_account);
+    lookup_table_addresses.push(invalid_table_address);
+
+    let ix = deactivate_lookup_tables(authority.pubkey(), &lookup_table_addresses);
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::InvalidAccountOwner,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables_without_tables() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let ix = deactivate_lookup_tables(authority.pubkey(), &[]);
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::NotEnoughAccountKeys,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_tables_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_addresses = add_lookup_tables(&mut context, Some(authority.pubkey()), 2).await;
+
+    let mut ix = deactivate_lookup_tables(authority.pubkey(), &lookup_table_addresses);
+    ix.accounts[0].is_signer = false;
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
@@ -0,0 +1,665 @@
+#![cfg(feature = "test-sbf")]
+//! Runs the same transactions against the program and against the native
+//! address lookup table builtin of the runtime, and checks that both return
+//! the same results and leave every account they touch in the same state.
+//!
+//! Only the instructions that the builtin supports are covered. Errors of the
+//! program are converted to the errors the builtin reports for the same
+//! condition before they are compared, so the suite passes with and without
+//! the `native-errors` feature.
+//!
+//! Both runtimes run with every feature active, as on mainnet. The test
+//! cases of the instructions in `*_ix.rs` also run against the builtin, in
+//! the `builtin` test binary.
+
+use {
+    common::{overwrite_slot_hashes_with_slots, start_program_test, Runtime},
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{
+            close_lookup_table, create_lookup_table, create_lookup_table_signed,
+            deactivate_lookup_table, extend_lookup_table, freeze_lookup_table,
+        },
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::{Account, AccountSharedData, WritableAccount},
+        address_lookup_table::{
+            program::id,
+            state::{AddressLookupTable, LookupTableMeta, LOOKUP_TABLE_MAX_ADDRESSES},
+        },
+        clock::{Clock, Slot},
+        instruction::{Instruction, InstructionError},
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{Keypair, Signer},
+        system_program,
+        transaction::{Transaction, TransactionError},
+    },
+    spl_program_error::num_traits::FromPrimitive,
+    std::borrow::Cow,
+};
+
+mod common;
+
+const CURRENT_SLOT: Slot = 1000;
+const RECENT_SLOT: Slot = 999;
+
+/// Transactions to run against both runtimes, and the state they start from
+struct Case<'a> {
+    /// Fee payer of every transaction
+    payer: &'a Keypair,
+    /// Accounts stored before the first transaction
+    accounts: Vec<(Pubkey, AccountSharedData)>,
+    /// Slots in the slot hashes sysvar
+    recent_slots: Vec<Slot>,
+    /// Instructions to process, each in its own transaction, with the signers
+    /// they need besides the payer
+    transactions: Vec<(Instruction, Vec<&'a Keypair>)>,
+}
+
+impl Case<'_> {
+    /// Returns every account that the case stores or that its instructions
+    /// reference, in the order they first appear
+    fn addresses(&self) -> Vec<Pubkey> {
+        let mut addresses = vec![self.payer.pubkey()];
+        let stored = self.accounts.iter().map(|(address, _)| *address);
+        let referenced = self
+            .transactions
+            .iter()
+            .flat_map(|(ix, _)| ix.accounts.iter().map(|meta| meta.pubkey));
+        for address in stored.chain(referenced) {
+            if !addresses.contains(&address) {
+                addresses.push(address);
+            }
+        }
+        addresses
+    }
+}
+
+/// Results of the transactions of a case, and the final state of its accounts
+#[derive(Debug, PartialEq)]
+struct Outcome {
+    results: Vec<Result<(), TransactionError>>,
+    accounts: Vec<(Pubkey, Option<Account>)>,
+}
+
+/// Returns the error the builtin reports for the condition the program
+/// reported with `err`
+fn builtin_error(err: InstructionError) -> InstructionError {
+    let InstructionError::Custom(code) = err else {
+        return err;
+    };
+    match AddressLookupError::from_u32(code) {
+        Some(AddressLookupError::LookupTableImmutable) => InstructionError::Immutable,
+        Some(AddressLookupError::IncorrectAuthority) => InstructionError::IncorrectAuthority,
+        Some(err) => err
+            .native_error()
+            .map_or(InstructionError::Custom(code), |err| u64::from(err).into()),
+        None => InstructionError::Custom(code),
+    }
+}
+
+async fn run(runtime: Runtime, case: &Case<'_>) -> Outcome {
+    let mut context = start_program_test(runtime).await;
+
+    context.set_account(
+        &case.payer.pubkey(),
+        &AccountSharedData::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    for (address, account) in &case.accounts {
+        context.set_account(address, account);
+    }
+    context.set_sysvar(&Clock {
+        slot: CURRENT_SLOT,
+        ..Clock::default()
+    });
+    overwrite_slot_hashes_with_slots(&context, &case.recent_slots);
+
+    let mut results = Vec::with_capacity(case.transactions.len());
+    for (ix, signers) in &case.transactions {
+        let recent_blockhash = context
+            .banks_client
+            .get_new_latest_blockhash(&context.last_blockhash)
+            .await
+            .unwrap();
+        context.last_blockhash = recent_blockhash;
+
+        let mut all_signers = vec![case.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            &[ix.clone()],
+            Some(&case.payer.pubkey()),
+            &all_signers,
+            recent_blockhash,
+        );
+        let result = context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+            .map_err(|err| err.unwrap());
+        results.push(match (runtime, result) {
+            (Runtime::Program, Err(TransactionError::InstructionError(index, err))) => Err(
+                TransactionError::InstructionError(index, builtin_error(err)),
+            ),
+            (_, result) => result,
+        });
+    }
+
+    let mut accounts = Vec::new();
+    for address in case.addresses() {
+        let account = context.banks_client.get_account(address).await.unwrap();
+        accounts.push((address, account));
+    }
+    Outcome { results, accounts }
+}
+
+/// Runs `case` against both runtimes, checks that they agree and returns the
+/// outcome
+async fn assert_same_outcome(case: Case<'_>) -> Outcome {
+    let builtin = run(Runtime::Builtin, &case).await;
+    let program = run(Runtime::Program, &case).await;
+    assert_eq!(program, builtin);
+    builtin
+}
+
+fn ix_error(err: InstructionError) -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(0, err))
+}
+
+fn new_table_account(meta: LookupTableMeta, num_addresses: usize) -> AccountSharedData {
+    let data = AddressLookupTable {
+        meta,
+        addresses: Cow::Owned(new_addresses(num_addresses)),
+    }
+    .serialize_for_tests()
+    .unwrap();
+
+    let rent_exempt_balance = Rent::default().minimum_balance(data.len());
+    let mut account = AccountSharedData::new(rent_exempt_balance, data.len(), &id());
+    account.set_data_from_slice(&data);
+    account
+}
+
+fn new_active_table(authority: Option<Pubkey>, num_addresses: usize) -> AccountSharedData {
+    new_table_account(
+        LookupTableMeta {
+            authority,
+            ..LookupTableMeta::default()
+        },
+        num_addresses,
+    )
+}
+
+fn new_deactivated_table(
+    authority: Pubkey,
+    deactivation_slot: Slot,
+    num_addresses: usize,
+) -> AccountSharedData {
+    new_table_account(
+        LookupTableMeta {
+            authority: Some(authority),
+            deactivation_slot,
+            ..LookupTableMeta::default()
+        },
+        num_addresses,
+    )
+}
+
+fn new_addresses(num_addresses: usize) -> Vec<Pubkey> {
+    let mut addresses = Vec::with_capacity(num_addresses);
+    addresses.resize_with(num_addresses, Pubkey::new_unique);
+    addresses
+}
+
+#[tokio::test]
+async fn test_create_lookup_table() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+
+    let (create_ix, _) =
+        create_lookup_table_signed(authority.pubkey(), payer.pubkey(), RECENT_SLOT);
+    let (not_recent_slot_ix, _) =
+        create_lookup_table_signed(authority.pubkey(), payer.pubkey(), CURRENT_SLOT);
+    let mut address_mismatch_ix = create_ix.clone();
+    address_mismatch_ix.accounts[0].pubkey = Pubkey::new_unique();
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![],
+        recent_slots: vec![RECENT_SLOT, 0],
+        transactions: vec![
+            (create_ix.clone(), vec![&authority]),
+            (create_ix, vec![&authority]),
+            (not_recent_slot_ix, vec![&authority]),
+            (address_mismatch_ix, vec![&authority]),
+        ],
+    })
+    .await;
+    // Creating a table that already exists succeeds without changing it
+    assert_eq!(outcome.results[..2], [Ok(()), Ok(())]);
+    assert!(outcome.results[2..].iter().all(Result::is_err));
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_prefunded() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let (create_ix, lookup_table_address) =
+        create_lookup_table_signed(authority.pubkey(), payer.pubkey(), RECENT_SLOT);
+
+    // A table address that already holds lamports only needs to be topped up
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![(
+            lookup_table_address,
+            AccountSharedData::new(1, 0, &system_program::id()),
+        )],
+        recent_slots: vec![RECENT_SLOT],
+        transactions: vec![(create_ix, vec![&authority])],
+    })
+    .await;
+    assert_eq!(outcome.results, vec![Ok(())]);
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let lookup_table_address = Pubkey::new_unique();
+    let payer_address = Some(payer.pubkey());
+
+    let extend_ix = |authority: &Keypair, num_addresses| {
+        extend_lookup_table(
+            lookup_table_address,
+            authority.pubkey(),
+            payer_address,
+            new_addresses(num_addresses),
+        )
+    };
+    let without_payer_ix = extend_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        None,
+        new_addresses(1),
+    );
+    let mut unsigned_ix = extend_ix(&authority, 1);
+    unsigned_ix.accounts[1].is_signer = false;
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![(
+            lookup_table_address,
+            new_active_table(Some(authority.pubkey()), 10),
+        )],
+        recent_slots: vec![],
+        transactions: vec![
+            (extend_ix(&authority, 2), vec![&authority]),
+            // Extending again in the same slot keeps the start index of the
+            // first extension
+            (extend_ix(&authority, 3), vec![&authority]),
+            (extend_ix(&authority, 0), vec![&authority]),
+            (without_payer_ix, vec![&authority]),
+            (extend_ix(&wrong_authority, 1), vec![&wrong_authority]),
+            (unsigned_ix, vec![]),
+        ],
+    })
+    .await;
+    assert_eq!(outcome.results[..2], [Ok(()), Ok(())]);
+    assert!(outcome.results[2..].iter().all(Result::is_err));
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_capacity() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let almost_full_table_address = Pubkey::new_unique();
+    let full_table_address = Pubkey::new_unique();
+
+    let extend_ix = |lookup_table_address, num_addresses| {
+        extend_lookup_table(
+            lookup_table_address,
+            authority.pubkey(),
+            Some(payer.pubkey()),
+            new_addresses(num_addresses),
+        )
+    };
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![
+            (
+                almost_full_table_address,
+                new_active_table(Some(authority.pubkey()), LOOKUP_TABLE_MAX_ADDRESSES - 2),
+            ),
+            (
+                full_table_address,
+                new_active_table(Some(authority.pubkey()), LOOKUP_TABLE_MAX_ADDRESSES),
+            ),
+        ],
+        recent_slots: vec![],
+        transactions: vec![
+            (extend_ix(almost_full_table_address, 3), vec![&authority]),
+            (extend_ix(almost_full_table_address, 2), vec![&authority]),
+            (extend_ix(full_table_address, 1), vec![&authority]),
+        ],
+    })
+    .await;
+    assert!(outcome.results[0].is_err());
+    assert_eq!(outcome.results[1], Ok(()));
+    assert!(outcome.results[2].is_err());
+}
+
+#[tokio::test]
+async fn test_extend_inactive_lookup_table() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let frozen_table_address = Pubkey::new_unique();
+    let deactivated_table_address = Pubkey::new_unique();
+
+    let extend_ix = |lookup_table_address| {
+        extend_lookup_table(
+            lookup_table_address,
+            authority.pubkey(),
+            Some(payer.pubkey()),
+            new_addresses(1),
+        )
+    };
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![
+            (frozen_table_address, new_active_table(None, 10)),
+            (
+                deactivated_table_address,
+                new_deactivated_table(authority.pubkey(), RECENT_SLOT, 10),
+            ),
+        ],
+        recent_slots: vec![RECENT_SLOT],
+        transactions: vec![
+            (extend_ix(frozen_table_address), vec![&authority]),
+            (extend_ix(deactivated_table_address), vec![&authority]),
+        ],
+    })
+    .await;
+    assert!(outcome.results.iter().all(Result::is_err));
+}
+
+#[tokio::test]
+async fn test_freeze_lookup_table() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let lookup_table_address = Pubkey::new_unique();
+    let empty_table_address = Pubkey::new_unique();
+    let deactivated_table_address = Pubkey::new_unique();
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![
+            (
+                lookup_table_address,
+                new_active_table(Some(authority.pubkey()), 10),
+            ),
+            (
+                empty_table_address,
+                new_active_table(Some(authority.pubkey()), 0),
+            ),
+            (
+                deactivated_table_address,
+                new_deactivated_table(authority.pubkey(), RECENT_SLOT, 10),
+            ),
+        ],
+        recent_slots: vec![RECENT_SLOT],
+        transactions: vec![
+            (
+                freeze_lookup_table(lookup_table_address, wrong_authority.pubkey()),
+                vec![&wrong_authority],
+            ),
+            (
+                freeze_lookup_table(lookup_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+            (
+                freeze_lookup_table(lookup_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+            (
+                freeze_lookup_table(empty_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+            (
+                freeze_lookup_table(deactivated_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+        ],
+    })
+    .await;
+    assert_eq!(
+        outcome.results[..3],
+        [
+            ix_error(InstructionError::IncorrectAuthority),
+            Ok(()),
+            ix_error(InstructionError::Immutable),
+        ]
+    );
+    assert!(outcome.results[3..].iter().all(Result::is_err));
+}
+
+#[tokio::test]
+async fn test_deactivate_lookup_table() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let lookup_table_address = Pubkey::new_unique();
+    let frozen_table_address = Pubkey::new_unique();
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![
+            (
+                lookup_table_address,
+                new_active_table(Some(authority.pubkey()), 10),
+            ),
+            (frozen_table_address, new_active_table(None, 10)),
+        ],
+        recent_slots: vec![],
+        transactions: vec![
+            (
+                deactivate_lookup_table(lookup_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+            (
+                deactivate_lookup_table(lookup_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+            (
+                deactivate_lookup_table(frozen_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+        ],
+    })
+    .await;
+    assert_eq!(outcome.results[0], Ok(()));
+    assert!(outcome.results[1..].iter().all(Result::is_err));
+}
+
+#[tokio::test]
+async fn test_close_lookup_table() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let recipient_address = Pubkey::new_unique();
+    let active_table_address = Pubkey::new_unique();
+    let deactivating_table_address = Pubkey::new_unique();
+    let deactivated_table_address = Pubkey::new_unique();
+
+    let close_ix = |lookup_table_address, recipient_address| {
+        close_lookup_table(lookup_table_address, authority.pubkey(), recipient_address)
+    };
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![
+            (
+                active_table_address,
+                new_active_table(Some(authority.pubkey()), 10),
+            ),
+            (
+                deactivating_table_address,
+                new_deactivated_table(authority.pubkey(), RECENT_SLOT, 10),
+            ),
+            (
+                deactivated_table_address,
+                new_deactivated_table(authority.pubkey(), 0, 10),
+            ),
+        ],
+        recent_slots: vec![RECENT_SLOT],
+        transactions: vec![
+            (
+                close_ix(active_table_address, recipient_address),
+                vec![&authority],
+            ),
+            (
+                close_ix(deactivating_table_address, recipient_address),
+                vec![&authority],
+            ),
+            (
+                close_ix(deactivated_table_address, deactivated_table_address),
+                vec![&authority],
+            ),
+            (
+                close_ix(deactivated_table_address, recipient_address),
+                vec![&authority],
+            ),
+        ],
+    })
+    .await;
+    assert!(outcome.results[..3].iter().all(Result::is_err));
+    assert_eq!(outcome.results[3], Ok(()));
+}
+
+#[tokio::test]
+async fn test_invalid_lookup_table_accounts() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let lookup_table_address = Pubkey::new_unique();
+    let wrong_owner_address = Pubkey::new_unique();
+    let truncated_table_address = Pubkey::new_unique();
+
+    let mut wrong_owner = new_active_table(Some(authority.pubkey()), 10);
+    wrong_owner.set_owner(Pubkey::new_unique());
+    let mut truncated_table = new_active_table(Some(authority.pubkey()), 0);
+    truncated_table.set_data_from_slice(&[1, 0, 0, 0]);
+
+    let mut transactions = Vec::new();
+    for lookup_table_address in [wrong_owner_address, truncated_table_address] {
+        transactions.push((
+            freeze_lookup_table(lookup_table_address, authority.pubkey()),
+            vec![&authority],
+        ));
+        transactions.push((
+            deactivate_lookup_table(lookup_table_address, authority.pubkey()),
+            vec![&authority],
+        ));
+    }
+    let mut invalid_data_ix = freeze_lookup_table(lookup_table_address, authority.pubkey());
+    invalid_data_ix.data = vec![u8::MAX; 4];
+    transactions.push((invalid_data_ix, vec![&authority]));
+    let mut missing_account_ix = freeze_lookup_table(lookup_table_address, authority.pubkey());
+    missing_account_ix.accounts.truncate(1);
+    transactions.push((missing_account_ix, vec![]));
+
+    let outcome = assert_same_outcome(Case {
+        payer: &payer,
+        accounts: vec![
+            (
+                lookup_table_address,
+                new_active_table(Some(authority.pubkey()), 10),
+            ),
+            (wrong_owner_address, wrong_owner),
+            (truncated_table_address, truncated_table),
+        ],
+        recent_slots: vec![],
+        transactions,
+    })
+    .await;
+    assert!(outcome.results.iter().all(Result::is_err));
+}
+
+/// Conditions in which the program is known to report a different error
+/// than the builtin. This test fails once one of them is fixed, so that the
+/// case can move to the cases above.
+#[tokio::test]
+async fn test_known_divergences() {
+    let payer = Keypair::new();
+    let authority = Keypair::new();
+    let uninitialized_table_address = Pubkey::new_unique();
+
+    let mut uninitialized_table = new_active_table(Some(authority.pubkey()), 0);
+    uninitialized_table.data_as_mut_slice()[0] = 0;
+    // The builtin checks the owner of the table before it looks for the
+    // authority account
+    let mut missing_account_ix = freeze_lookup_table(Pubkey::new_unique(), authority.pubkey());
+    missing_account_ix.accounts.truncate(1);
+
+    let case = Case {
+        payer: &payer,
+        accounts: vec![(uninitialized_table_address, uninitialized_table)],
+        recent_slots: vec![],
+        transactions: vec![
+            (
+                freeze_lookup_table(uninitialized_table_address, authority.pubkey()),
+                vec![&authority],
+            ),
+            (missing_account_ix, vec![]),
+        ],
+    };
+    let builtin = run(Runtime::Builtin, &case).await;
+    let program = run(Runtime::Program, &case).await;
+
+    assert_eq!(program.accounts, builtin.accounts);
+    assert_eq!(
+        program.results,
+        vec![
+            ix_error(InstructionError::InvalidAccountData),
+            ix_error(InstructionError::NotEnoughAccountKeys),
+        ]
+    );
+    assert_eq!(
+        builtin.results,
+        vec![
+            ix_error(InstructionError::UninitializedAccount),
+            ix_error(InstructionError::InvalidAccountOwner),
+        ]
+    );
+
+    // Since `relax_authority_signer_check_for_lookup_table_creation` was
+    // activated, the builtin creates tables without the signature of their
+    // authority. The program still requires it.
+    let (unsigned_ix, lookup_table_address) =
+        create_lookup_table(authority.pubkey(), payer.pubkey(), RECENT_SLOT);
+    let case = Case {
+        payer: &payer,
+        accounts: vec![],
+        recent_slots: vec![RECENT_SLOT],
+        transactions: vec![(unsigned_ix, vec![])],
+    };
+    let builtin = run(Runtime::Builtin, &case).await;
+    let program = run(Runtime::Program, &case).await;
+
+    assert_eq!(builtin.results, vec![Ok(())]);
+    assert_eq!(
+        program.results,
+        vec![ix_error(InstructionError::MissingRequiredSignature)]
+    );
+    let lookup_table_account = |outcome: &Outcome| {
+        outcome
+            .accounts
+            .iter()
+            .find(|(address, _)| *address == lookup_table_address)
+            .and_then(|(_, account)| account.clone())
+    };
+    assert!(lookup_table_account(&builtin).is_some());
+    assert_eq!(lookup_table_account(&program), None);
+}
@@ -3,14 +3,19 @@
 use {
     assert_matches::assert_matches,
     common::{
-        add_lookup_table_account, assert_ix_error, new_address_lookup_table, setup_test_context,
+        add_lookup_table_account, assert_ix_error, lookup_error, new_address_lookup_table, runtime,
+        set_lookup_table_extension_account, setup_test_context, Runtime,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{extend_lookup_table, extend_lookup_table_as_extender},
+        state::LookupTableExtension,
     },
     solana_program_test::*,
     solana_sdk::{
         account::{ReadableAccount, WritableAccount},
-        address_lookup_table::{
-            instruction::extend_lookup_table,
-            state::{AddressLookupTable, LookupTableMeta},
+        address_lookup_table::state::{
+            AddressLookupTable, LookupTableMeta, LOOKUP_TABLE_META_SIZE,
         },
         clock::Clock,
         instruction::{Instruction, InstructionError},
@@ -88,15 +93,36 @@ async fn test_extend_lookup_table() {
 
     for extend_same_slot in [true, false] {
         for (num_existing_addresses, num_new_addresses, expected_result) in [
-            (0, 0, Err(InstructionError::InvalidInstructionData)),
+            (
+                0,
+                0,
+                Err(lookup_error(
+                    AddressLookupError::EmptyExtend,
+                    InstructionError::InvalidInstructionData,
+                )),
+            ),
             (0, 1, Ok(())),
             (0, 10, Ok(())),
             (1, 1, Ok(())),
             (1, 10, Ok(())),
             (255, 1, Ok(())),
-            (255, 2, Err(InstructionError::InvalidInstructionData)),
+            (
+                255,
+                2,
+                Err(lookup_error(
+                    AddressLookupError::MaxCapacityExceeded,
+                    InstructionError::InvalidInstructionData,
+                )),
+            ),
             (246, 10, Ok(())),
-            (256, 1, Err(InstructionError::InvalidArgument)),
+            (
+                256,
+                1,
+                Err(lookup_error(
+                    AddressLookupError::TableFull,
+                    InstructionError::InvalidArgument,
+                )),
+            ),
         ] {
             let mut lookup_table =
                 new_address_lookup_table(Some(authority.pubkey()), num_existing_addresses);
@@ -180,7 +206,10 @@ async fn test_extend_lookup_table_with_wrong_authority() {
         &mut context,
         ix,
         Some(&wrong_authority),
-        InstructionError::IncorrectAuthority,
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
     )
     .await;
 }
@@ -237,7 +266,10 @@ async fn test_extend_deactivated_lookup_table() {
         &mut context,
         ix,
         Some(&authority),
-        InstructionError::InvalidArgument,
+        lookup_error(
+            AddressLookupError::TableDeactivated,
+            InstructionError::InvalidArgument,
+        ),
     )
     .await;
 }
@@ -263,7 +295,10 @@ async fn test_extend_immutable_lookup_table() {
         &mut context,
         ix,
         Some(&authority),
-        InstructionError::Immutable,
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
     )
     .await;
 }
@@ -347,3 +382,190 @@ async fn test_extend_prepaid_lookup_table_without_payer() {
     )
     .await;
 }
+
+#[tokio::test]
+async fn test_extend_lookup_table_appends_addresses() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 3);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(
+        &mut context,
+        lookup_table_address,
+        initialized_table.clone(),
+    )
+    .await;
+
+    let mut expected_addresses = initialized_table.addresses.to_vec();
+    for num_new_addresses in [1, 20] {
+        let mut new_addresses = Vec::with_capacity(num_new_addresses);
+        new_addresses.resize_with(num_new_addresses, Pubkey::new_unique);
+        expected_addresses.extend_from_slice(&new_addresses);
+
+        let client = &mut context.banks_client;
+        let payer = &context.payer;
+        let recent_blockhash = client
+            .get_new_latest_blockhash(&context.last_blockhash)
+            .await
+            .unwrap();
+        context.last_blockhash = recent_blockhash;
+        let transaction = Transaction::new_signed_with_payer(
+            &[extend_lookup_table(
+                lookup_table_address,
+                authority.pubkey(),
+                Some(payer.pubkey()),
+                new_addresses,
+            )],
+            Some(&payer.pubkey()),
+            &[payer, &authority],
+            recent_blockhash,
+        );
+        assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+        let table_account = client
+            .get_account(lookup_table_address)
+            .await
+            .unwrap()
+            .unwrap();
+        let expected_data_len = LOOKUP_TABLE_META_SIZE + expected_addresses.len() * PUBKEY_BYTES;
+        let rent = client.get_rent().await.unwrap();
+        assert_eq!(table_account.data.len(), expected_data_len);
+        assert_eq!(
+            table_account.lamports,
+            rent.minimum_balance(expected_data_len)
+        );
+
+        let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+        assert_eq!(lookup_table.addresses.to_vec(), expected_addresses);
+        assert_eq!(lookup_table.meta.authority, Some(authority.pubkey()));
+    }
+}
+
+async fn add_lookup_table_with_extender(
+    context: &mut ProgramTestContext,
+    authority: Pubkey,
+    extender: Option<Pubkey>,
+) -> (Pubkey, AddressLookupTable<'static>) {
+    let initialized_table = new_address_lookup_table(Some(authority), 3);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table.clone()).await;
+
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.extender = extender.try_into().unwrap();
+    set_lookup_table_extension_account(context, extension).await;
+
+    (lookup_table_address, initialized_table)
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_as_extender() {
+    // Only the program lets an extender sign in place of the authority
+    if runtime() == Runtime::Builtin {
+        return;
+    }
+
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let extender = Keypair::new();
+    let (lookup_table_address, initialized_table) =
+        add_lookup_table_with_extender(&mut context, authority.pubkey(), Some(extender.pubkey()))
+            .await;
+
+    let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let instruction = extend_lookup_table_as_extender(
+        lookup_table_address,
+        extender.pubkey(),
+        Some(context.payer.pubkey()),
+        new_addresses.clone(),
+    );
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    let mut expected_addresses = initialized_table.addresses.to_vec();
+    expected_addresses.extend(new_addresses);
+    let expected_data_len = LOOKUP_TABLE_META_SIZE + expected_addresses.len() * PUBKEY_BYTES;
+    let expected_state = ExpectedTableAccount {
+        lamports: rent.minimum_balance(expected_data_len),
+        data_len: expected_data_len,
+        state: AddressLookupTable {
+            meta: LookupTableMeta {
+                last_extended_slot: clock.slot,
+                last_extended_slot_start_index: 3,
+                ..initialized_table.meta
+            },
+            addresses: Cow::Owned(expected_addresses),
+        },
+    };
+
+    run_test_case(
+        &mut context,
+        TestCase {
+            lookup_table_address,
+            instruction,
+            extra_signer: Some(&extender),
+            expected_result: Ok(expected_state),
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_as_wrong_extender() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_extender = Keypair::new();
+    for extender in [None, Some(Pubkey::new_unique())] {
+        let (lookup_table_address, _) =
+            add_lookup_table_with_extender(&mut context, authority.pubkey(), extender).await;
+
+        let ix = extend_lookup_table_as_extender(
+            lookup_table_address,
+            wrong_extender.pubkey(),
+            Some(context.payer.pubkey()),
+            vec![Pubkey::new_unique()],
+        );
+
+        assert_ix_error(
+            &mut context,
+            ix,
+            Some(&wrong_extender),
+            lookup_error(
+                AddressLookupError::IncorrectAuthority,
+                InstructionError::IncorrectAuthority,
+            ),
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_as_extender_without_extension_account() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let extender = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_extender(&mut context, authority.pubkey(), Some(extender.pubkey()))
+            .await;
+
+    let ix = extend_lookup_table(
+        lookup_table_address,
+        extender.pubkey(),
+        Some(context.payer.pubkey()),
+        vec![Pubkey::new_unique()],
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&extender),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
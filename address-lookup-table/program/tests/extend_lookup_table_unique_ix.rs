@@ -0,0 +1,263 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, lookup_error, new_address_lookup_table,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::extend_lookup_table_unique,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::state::{AddressLookupTable, LOOKUP_TABLE_META_SIZE},
+        clock::Clock,
+        instruction::InstructionError,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn add_table_with_addresses(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    num_addresses: usize,
+) -> (Pubkey, AddressLookupTable<'static>) {
+    let lookup_table = new_address_lookup_table(Some(authority.pubkey()), num_addresses);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, lookup_table.clone()).await;
+    (lookup_table_address, lookup_table)
+}
+
+async fn assert_extends_lookup_table(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+    authority: &Keypair,
+    new_addresses: Vec<Pubkey>,
+    expected_addresses: &[Pubkey],
+) {
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[extend_lookup_table_unique(
+            lookup_table_address,
+            authority.pubkey(),
+            Some(payer.pubkey()),
+            new_addresses,
+            true,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, authority],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+    let rent = client.get_rent().await.unwrap();
+    let table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let expected_data_len = LOOKUP_TABLE_META_SIZE
+        .saturating_add(expected_addresses.len().saturating_mul(PUBKEY_BYTES));
+    assert_eq!(table_account.data.len(), expected_data_len);
+    assert_eq!(
+        table_account.lamports,
+        rent.minimum_balance(expected_data_len)
+    );
+
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.addresses.as_ref(), expected_addresses);
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_unique_skips_duplicates() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let (lookup_table_address, lookup_table) =
+        add_table_with_addresses(&mut context, &authority, 3).await;
+
+    let existing = lookup_table.addresses.to_vec();
+    let new_address_1 = Pubkey::new_unique();
+    let new_address_2 = Pubkey::new_unique();
+    let new_addresses = vec![
+        existing[1],
+        new_address_1,
+        new_address_1,
+        new_address_2,
+        existing[0],
+    ];
+
+    let mut expected_addresses = existing.clone();
+    expected_addresses.extend([new_address_1, new_address_2]);
+    assert_extends_lookup_table(
+        &mut context,
+        lookup_table_address,
+        &authority,
+        new_addresses,
+        &expected_addresses,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_unique_skips_all_duplicates() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let (lookup_table_address, lookup_table) =
+        add_table_with_addresses(&mut context, &authority, 3).await;
+
+    let existing = lookup_table.addresses.to_vec();
+    assert_extends_lookup_table(
+        &mut context,
+        lookup_table_address,
+        &authority,
+        vec![existing[2], existing[0]],
+        &existing,
+    )
+    .await;
+
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table_after = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table_after.meta, lookup_table.meta);
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_unique_rejects_duplicates() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let (lookup_table_address, lookup_table) =
+        add_table_with_addresses(&mut context, &authority, 3).await;
+
+    let existing_address = lookup_table.addresses[1];
+    let new_address = Pubkey::new_unique();
+    for new_addresses in [
+        // Already in the table
+        vec![new_address, existing_address],
+        // Repeated within the batch
+        vec![new_address, Pubkey::new_unique(), new_address],
+    ] {
+        let ix = extend_lookup_table_unique(
+            lookup_table_address,
+            authority.pubkey(),
+            Some(context.payer.pubkey()),
+            new_addresses,
+            false,
+        );
+
+        assert_ix_error(
+            &mut context,
+            ix,
+            Some(&authority),
+            InstructionError::Custom(AddressLookupError::DuplicateAddress as u32),
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_unique_rejects_duplicates_in_same_slot() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+
+    // The last two addresses were appended in the current slot and are not
+    // active yet, but they still count as duplicates.
+    let lookup_table = {
+        let mut table = new_address_lookup_table(Some(authority.pubkey()), 4);
+        table.meta.last_extended_slot = clock.slot;
+        table.meta.last_extended_slot_start_index = 2;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, lookup_table.clone()).await;
+
+    let ix = extend_lookup_table_unique(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        vec![Pubkey::new_unique(), lookup_table.addresses[3]],
+        false,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::Custom(AddressLookupError::DuplicateAddress as u32),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_unique_fills_table() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let (lookup_table_address, lookup_table) =
+        add_table_with_addresses(&mut context, &authority, 255).await;
+
+    // Without deduplication the table would overflow
+    let new_address = Pubkey::new_unique();
+    let mut expected_addresses = lookup_table.addresses.to_vec();
+    expected_addresses.push(new_address);
+    assert_extends_lookup_table(
+        &mut context,
+        lookup_table_address,
+        &authority,
+        vec![
+            lookup_table.addresses[0],
+            new_address,
+            lookup_table.addresses[254],
+        ],
+        &expected_addresses,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_extend_full_lookup_table_unique() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let (lookup_table_address, lookup_table) =
+        add_table_with_addresses(&mut context, &authority, 256).await;
+
+    // Addresses already in the table are skipped before checking capacity
+    let existing = lookup_table.addresses.to_vec();
+    assert_extends_lookup_table(
+        &mut context,
+        lookup_table_address,
+        &authority,
+        vec![existing[0], existing[255]],
+        &existing,
+    )
+    .await;
+
+    let ix = extend_lookup_table_unique(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        vec![existing[0], Pubkey::new_unique()],
+        true,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableFull,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
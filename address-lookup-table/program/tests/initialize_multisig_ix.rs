@@ -0,0 +1,221 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{add_multisig_account, process_ix_with_signers, setup_test_context},
+    solana_address_lookup_table_program::{
+        instruction::initialize_multisig,
+        state::{LookupTableMultisig, MAX_MULTISIG_SIGNERS},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::program::id,
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_instruction,
+        transaction::{Transaction, TransactionError},
+    },
+};
+
+mod common;
+
+async fn create_multisig_account_ix(
+    context: &mut ProgramTestContext,
+    multisig: &Keypair,
+    data_len: usize,
+) -> Instruction {
+    let rent = context.banks_client.get_rent().await.unwrap();
+    system_instruction::create_account(
+        &context.payer.pubkey(),
+        &multisig.pubkey(),
+        rent.minimum_balance(data_len),
+        data_len as u64,
+        &id(),
+    )
+}
+
+async fn process_initialize_multisig(
+    context: &mut ProgramTestContext,
+    signers: &[Pubkey],
+    m: u8,
+) -> Result<Pubkey, TransactionError> {
+    let multisig = Keypair::new();
+    let create_ix = create_multisig_account_ix(context, &multisig, LookupTableMultisig::LEN).await;
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = recent_blockhash;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            create_ix,
+            initialize_multisig(multisig.pubkey(), signers, m),
+        ],
+        Some(&payer.pubkey()),
+        &[payer, &multisig],
+        recent_blockhash,
+    );
+    client
+        .process_transaction(transaction)
+        .await
+        .map_err(|err| err.unwrap())?;
+    Ok(multisig.pubkey())
+}
+
+#[tokio::test]
+async fn test_initialize_multisig() {
+    let mut context = setup_test_context().await;
+
+    let signers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    let multisig_address = process_initialize_multisig(&mut context, &signers, 2)
+        .await
+        .unwrap();
+
+    let multisig_account = context
+        .banks_client
+        .get_account(multisig_address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(multisig_account.owner, id());
+    let multisig = LookupTableMultisig::unpack(&multisig_account.data).unwrap();
+    assert_eq!(multisig.m, 2);
+    assert_eq!(multisig.n, 3);
+    assert_eq!(multisig.signers(), signers.as_slice());
+}
+
+#[tokio::test]
+async fn test_initialize_multisig_with_max_signers() {
+    let mut context = setup_test_context().await;
+
+    let signers: Vec<Pubkey> = (0..MAX_MULTISIG_SIGNERS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    let m = MAX_MULTISIG_SIGNERS as u8;
+    assert_matches!(
+        process_initialize_multisig(&mut context, &signers, m).await,
+        Ok(_)
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_multisig_with_invalid_threshold() {
+    let mut context = setup_test_context().await;
+
+    let signers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    for m in [0, 4] {
+        assert_eq!(
+            process_initialize_multisig(&mut context, &signers, m).await,
+            Err(TransactionError::InstructionError(
+                1,
+                InstructionError::InvalidArgument
+            )),
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_initialize_multisig_with_invalid_signers() {
+    let mut context = setup_test_context().await;
+
+    // No signers
+    assert_eq!(
+        process_initialize_multisig(&mut context, &[], 1).await,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::InvalidArgument
+        )),
+    );
+
+    // Duplicate signers
+    let signer = Pubkey::new_unique();
+    assert_eq!(
+        process_initialize_multisig(&mut context, &[signer, Pubkey::new_unique(), signer], 1).await,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::InvalidArgument
+        )),
+    );
+
+    // Too many signers
+    let signers: Vec<Pubkey> = (0..=MAX_MULTISIG_SIGNERS)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    assert_eq!(
+        process_initialize_multisig(&mut context, &signers, 1).await,
+        Err(TransactionError::InstructionError(
+            1,
+            InstructionError::InvalidArgument
+        )),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_multisig_twice() {
+    let mut context = setup_test_context().await;
+
+    let signers = [Pubkey::new_unique()];
+    let multisig_address = add_multisig_account(&mut context, 1, &signers).await;
+
+    assert_eq!(
+        process_ix_with_signers(
+            &mut context,
+            initialize_multisig(multisig_address, &signers, 1),
+            &[],
+        )
+        .await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::AccountAlreadyInitialized
+        )),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_multisig_with_wrong_data_len() {
+    let mut context = setup_test_context().await;
+
+    let multisig = Keypair::new();
+    let create_ix = create_multisig_account_ix(
+        &mut context,
+        &multisig,
+        LookupTableMultisig::LEN.saturating_sub(1),
+    )
+    .await;
+    let initialize_ix = initialize_multisig(multisig.pubkey(), &[Pubkey::new_unique()], 1);
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[create_ix, initialize_ix],
+        Some(&payer.pubkey()),
+        &[payer, &multisig],
+        context.last_blockhash,
+    );
+    assert_eq!(
+        client
+            .process_transaction(transaction)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(1, InstructionError::InvalidAccountData),
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_multisig_not_owned_by_program() {
+    let mut context = setup_test_context().await;
+
+    let ix = initialize_multisig(Pubkey::new_unique(), &[Pubkey::new_unique()], 1);
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &[]).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidAccountOwner
+        )),
+    );
+}
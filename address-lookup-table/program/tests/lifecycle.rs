@@ -0,0 +1,137 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{overwrite_slot_hashes_with_slots, setup_test_context},
+    solana_address_lookup_table_program::instruction::{
+        close_lookup_table, create_lookup_table_signed, deactivate_lookup_table,
+        extend_lookup_table, freeze_lookup_table,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::state::AddressLookupTable,
+        clock::{Clock, Slot},
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn process_ix(context: &mut ProgramTestContext, ix: Instruction, authority: &Keypair) {
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = recent_blockhash;
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, authority],
+        recent_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+}
+
+async fn get_lookup_table_data(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> Vec<u8> {
+    context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap()
+        .data
+}
+
+async fn create_and_extend_lookup_table(
+    context: &mut ProgramTestContext,
+    authority: &Keypair,
+    new_addresses: Vec<Pubkey>,
+) -> Pubkey {
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(context, &[recent_slot]);
+
+    let (create_ix, lookup_table_address) =
+        create_lookup_table_signed(authority.pubkey(), context.payer.pubkey(), recent_slot);
+    process_ix(context, create_ix, authority).await;
+
+    let extend_ix = extend_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        new_addresses.clone(),
+    );
+    process_ix(context, extend_ix, authority).await;
+
+    let data = get_lookup_table_data(context, lookup_table_address).await;
+    let lookup_table = AddressLookupTable::deserialize(&data).unwrap();
+    assert_eq!(lookup_table.addresses.to_vec(), new_addresses);
+
+    lookup_table_address
+}
+
+#[tokio::test]
+async fn test_create_extend_freeze() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let lookup_table_address =
+        create_and_extend_lookup_table(&mut context, &authority, new_addresses.clone()).await;
+
+    let freeze_ix = freeze_lookup_table(lookup_table_address, authority.pubkey());
+    process_ix(&mut context, freeze_ix, &authority).await;
+
+    let data = get_lookup_table_data(&mut context, lookup_table_address).await;
+    let lookup_table = AddressLookupTable::deserialize(&data).unwrap();
+    assert_eq!(lookup_table.meta.authority, None);
+    assert_eq!(lookup_table.addresses.to_vec(), new_addresses);
+}
+
+#[tokio::test]
+async fn test_create_extend_deactivate_close() {
+    let mut context = setup_test_context().await;
+    let authority = Keypair::new();
+    let new_addresses = vec![Pubkey::new_unique()];
+
+    let lookup_table_address =
+        create_and_extend_lookup_table(&mut context, &authority, new_addresses.clone()).await;
+
+    let deactivate_ix = deactivate_lookup_table(lookup_table_address, authority.pubkey());
+    process_ix(&mut context, deactivate_ix, &authority).await;
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let data = get_lookup_table_data(&mut context, lookup_table_address).await;
+    let lookup_table = AddressLookupTable::deserialize(&data).unwrap();
+    assert_eq!(lookup_table.meta.deactivation_slot, clock.slot);
+    assert_eq!(lookup_table.addresses.to_vec(), new_addresses);
+
+    // Move past the deactivation slot and drop it from the slot hashes so
+    // that the table is fully deactivated.
+    let next_slot: Slot = clock.slot + 1;
+    context.warp_to_slot(next_slot).unwrap();
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let close_ix = close_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        context.payer.pubkey(),
+    );
+    process_ix(&mut context, close_ix, &authority).await;
+
+    assert!(context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .is_none());
+}
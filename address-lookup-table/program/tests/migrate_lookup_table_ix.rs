@@ -0,0 +1,201 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, get_lookup_table_extension, lookup_error,
+        new_address_lookup_table, process_ix_with_signers,
+        set_legacy_lookup_table_extension_account, set_lookup_table_extension_account,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{migrate_lookup_table, set_extender},
+        state::{LookupTableExtension, EXTENSION_VERSION},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::AccountSharedData,
+        address_lookup_table::state::AddressLookupTable,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        system_program,
+    },
+};
+
+mod common;
+
+async fn add_legacy_lookup_table(context: &mut ProgramTestContext) -> (Pubkey, Keypair) {
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table).await;
+    (lookup_table_address, authority)
+}
+
+#[tokio::test]
+async fn test_migrate_lookup_table_without_extension() {
+    let mut context = setup_test_context().await;
+    let (lookup_table_address, _) = add_legacy_lookup_table(&mut context).await;
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let ix = migrate_lookup_table(lookup_table_address, Some(context.payer.pubkey()));
+    assert_matches!(process_ix_with_signers(&mut context, ix, &[]).await, Ok(()));
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension, LookupTableExtension::new(lookup_table_address));
+    assert_eq!(extension.version(), EXTENSION_VERSION);
+
+    // The table account is never touched
+    let migrated_table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(migrated_table_account, table_account);
+    assert!(AddressLookupTable::deserialize(&migrated_table_account.data).is_ok());
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_extension() {
+    let mut context = setup_test_context().await;
+    let (lookup_table_address, authority) = add_legacy_lookup_table(&mut context).await;
+
+    let mut legacy_extension = LookupTableExtension::new(lookup_table_address);
+    legacy_extension.pending_authority = Some(Pubkey::new_unique()).try_into().unwrap();
+    legacy_extension.extender = Some(Pubkey::new_unique()).try_into().unwrap();
+    let extension_address =
+        set_legacy_lookup_table_extension_account(&mut context, legacy_extension).await;
+
+    // Legacy extension accounts cannot be used until they are migrated
+    let ix = set_extender(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        None,
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::ExtensionNotMigrated,
+            InstructionError::InvalidAccountData,
+        ),
+    )
+    .await;
+
+    let ix = migrate_lookup_table(lookup_table_address, Some(context.payer.pubkey()));
+    assert_matches!(process_ix_with_signers(&mut context, ix, &[]).await, Ok(()));
+
+    let extension_account = context
+        .banks_client
+        .get_account(extension_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(extension_account.data.len(), LookupTableExtension::LEN);
+    assert!(rent.is_exempt(extension_account.lamports, LookupTableExtension::LEN));
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension, legacy_extension);
+
+    let ix = set_extender(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        None,
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_current_extension() {
+    let mut context = setup_test_context().await;
+    let (lookup_table_address, _) = add_legacy_lookup_table(&mut context).await;
+
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.extender = Some(Pubkey::new_unique()).try_into().unwrap();
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    // Migrating a current table is a no-op that needs no payer
+    let ix = migrate_lookup_table(lookup_table_address, None);
+    assert_matches!(process_ix_with_signers(&mut context, ix, &[]).await, Ok(()));
+    assert_eq!(
+        get_lookup_table_extension(&mut context, lookup_table_address).await,
+        extension
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_legacy_extension_without_payer() {
+    let mut context = setup_test_context().await;
+    let (lookup_table_address, _) = add_legacy_lookup_table(&mut context).await;
+    set_legacy_lookup_table_extension_account(
+        &mut context,
+        LookupTableExtension::new(lookup_table_address),
+    )
+    .await;
+
+    let ix = migrate_lookup_table(lookup_table_address, None);
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::NotEnoughAccountKeys,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_migrate_lookup_table_with_wrong_extension_address() {
+    let mut context = setup_test_context().await;
+    let (lookup_table_address, _) = add_legacy_lookup_table(&mut context).await;
+
+    let mut ix = migrate_lookup_table(lookup_table_address, Some(context.payer.pubkey()));
+    ix.accounts[1].pubkey = Pubkey::new_unique();
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        lookup_error(
+            AddressLookupError::AddressMismatch,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_migrate_lookup_table_with_invalid_owner() {
+    let mut context = setup_test_context().await;
+
+    let lookup_table_address = Pubkey::new_unique();
+    let data = new_address_lookup_table(Some(Pubkey::new_unique()), 10)
+        .serialize_for_tests()
+        .unwrap();
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), &system_program::id());
+    account.set_data_from_slice(&data);
+    context.set_account(&lookup_table_address, &account);
+
+    let ix = migrate_lookup_table(lookup_table_address, Some(context.payer.pubkey()));
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::InvalidAccountOwner,
+    )
+    .await;
+}
@@ -0,0 +1,703 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, add_lookup_table_extension_account, add_multisig_account,
+        get_lookup_table_extension, get_lookup_table_metadata, new_address_lookup_table,
+        overwrite_slot_hashes_with_slots, process_ix_with_signers,
+        set_lookup_table_extension_account, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        instruction::{
+            accept_authority, cancel_authority_proposal, cancel_scheduled_deactivation,
+            close_lookup_table, close_lookup_tables, deactivate_lookup_table,
+            deactivate_lookup_tables, extend_lookup_table, freeze_lookup_table, propose_authority,
+            reactivate_lookup_table, remove_extender, schedule_deactivation, set_extender,
+            set_metadata, truncate_lookup_table, with_multisig_signers,
+        },
+        state::{LookupTableExtension, MIN_TRUNCATION_COOL_DOWN_SLOTS},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::state::AddressLookupTable,
+        clock::{Clock, Slot},
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::TransactionError,
+    },
+};
+
+mod common;
+
+struct MultisigAuthority {
+    address: Pubkey,
+    signers: Vec<Keypair>,
+}
+
+impl MultisigAuthority {
+    async fn new(context: &mut ProgramTestContext, m: u8, n: usize) -> Self {
+        let signers: Vec<Keypair> = (0..n).map(|_| Keypair::new()).collect();
+        let signer_addresses: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+        let address = add_multisig_account(context, m, &signer_addresses).await;
+        Self { address, signers }
+    }
+
+    fn signers(&self, count: usize) -> Vec<&Keypair> {
+        self.signers.iter().take(count).collect()
+    }
+}
+
+fn signer_addresses(signers: &[&Keypair]) -> Vec<Pubkey> {
+    signers.iter().map(|signer| signer.pubkey()).collect()
+}
+
+async fn add_lookup_table_with_authority(
+    context: &mut ProgramTestContext,
+    authority: Pubkey,
+) -> Pubkey {
+    let lookup_table_address = Pubkey::new_unique();
+    let initialized_table = new_address_lookup_table(Some(authority), 10);
+    add_lookup_table_account(context, lookup_table_address, initialized_table).await;
+    lookup_table_address
+}
+
+async fn get_lookup_table_authority(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> Option<Pubkey> {
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    AddressLookupTable::deserialize(&table_account.data)
+        .unwrap()
+        .meta
+        .authority
+}
+
+async fn process_freeze_with_signers(
+    context: &mut ProgramTestContext,
+    multisig: &MultisigAuthority,
+    signers: &[&Keypair],
+) -> Result<(), TransactionError> {
+    let lookup_table_address = add_lookup_table_with_authority(context, multisig.address).await;
+    let ix = with_multisig_signers(
+        freeze_lookup_table(lookup_table_address, multisig.address),
+        &signer_addresses(signers),
+    );
+    process_ix_with_signers(context, ix, signers).await
+}
+
+fn missing_signature() -> Result<(), TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::MissingRequiredSignature,
+    ))
+}
+
+/// Checks that a 2-of-N multisig authority cannot approve the instruction
+/// with one signature and approves it with two.
+async fn assert_multisig_approves(
+    context: &mut ProgramTestContext,
+    multisig: &MultisigAuthority,
+    ix: Instruction,
+) {
+    let signers = multisig.signers(2);
+
+    let short_ix = with_multisig_signers(ix.clone(), &signer_addresses(&signers[..1]));
+    assert_eq!(
+        process_ix_with_signers(context, short_ix, &signers[..1]).await,
+        missing_signature(),
+    );
+
+    let ix = with_multisig_signers(ix, &signer_addresses(&signers));
+    assert_matches!(process_ix_with_signers(context, ix, &signers).await, Ok(()));
+}
+
+async fn get_lookup_table(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> AddressLookupTable<'static> {
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    AddressLookupTable {
+        meta: lookup_table.meta,
+        addresses: lookup_table.addresses.into_owned().into(),
+    }
+}
+
+#[tokio::test]
+async fn test_multisig_threshold() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+
+    // One signature short of the threshold
+    assert_eq!(
+        process_freeze_with_signers(&mut context, &multisig, &multisig.signers(1)).await,
+        missing_signature(),
+    );
+
+    // Exactly the threshold
+    assert_matches!(
+        process_freeze_with_signers(&mut context, &multisig, &multisig.signers(2)).await,
+        Ok(())
+    );
+
+    // More than the threshold
+    assert_matches!(
+        process_freeze_with_signers(&mut context, &multisig, &multisig.signers(3)).await,
+        Ok(())
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_all_signers_required() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 3, 3).await;
+
+    assert_eq!(
+        process_freeze_with_signers(&mut context, &multisig, &multisig.signers(2)).await,
+        missing_signature(),
+    );
+    assert_matches!(
+        process_freeze_with_signers(&mut context, &multisig, &multisig.signers(3)).await,
+        Ok(())
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_duplicate_signer_counts_once() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    let signer = &multisig.signers[0];
+    let ix = with_multisig_signers(
+        freeze_lookup_table(lookup_table_address, multisig.address),
+        &[signer.pubkey(), signer.pubkey()],
+    );
+
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &[signer]).await,
+        missing_signature(),
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_non_member_signer_does_not_count() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+
+    let non_member = Keypair::new();
+    assert_eq!(
+        process_freeze_with_signers(
+            &mut context,
+            &multisig,
+            &[&multisig.signers[0], &non_member]
+        )
+        .await,
+        missing_signature(),
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_unsigned_member_does_not_count() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    let mut ix = with_multisig_signers(
+        freeze_lookup_table(lookup_table_address, multisig.address),
+        &signer_addresses(&multisig.signers(2)),
+    );
+    ix.accounts[3].is_signer = false;
+
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &multisig.signers(1)).await,
+        missing_signature(),
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_without_signers() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 1, 1).await;
+
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    let ix = with_multisig_signers(
+        freeze_lookup_table(lookup_table_address, multisig.address),
+        &[],
+    );
+
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &[]).await,
+        missing_signature(),
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_extend_lookup_table() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+
+    let payer_address = context.payer.pubkey();
+    let new_addresses = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let extend_ix = |signers: &[&Keypair]| {
+        with_multisig_signers(
+            extend_lookup_table(
+                lookup_table_address,
+                multisig.address,
+                Some(payer_address),
+                new_addresses.clone(),
+            ),
+            &signer_addresses(signers),
+        )
+    };
+
+    assert_eq!(
+        process_ix_with_signers(
+            &mut context,
+            extend_ix(&multisig.signers(1)),
+            &multisig.signers(1)
+        )
+        .await,
+        missing_signature(),
+    );
+    assert_matches!(
+        process_ix_with_signers(
+            &mut context,
+            extend_ix(&multisig.signers(2)),
+            &multisig.signers(2)
+        )
+        .await,
+        Ok(())
+    );
+
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.addresses.len(), 12);
+    assert_eq!(&lookup_table.addresses[10..], new_addresses.as_slice());
+}
+
+#[tokio::test]
+async fn test_multisig_deactivate_lookup_table() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    let signers = multisig.signers(2);
+
+    let ix = with_multisig_signers(
+        deactivate_lookup_table(lookup_table_address, multisig.address),
+        &signer_addresses(&signers[..1]),
+    );
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &signers[..1]).await,
+        missing_signature(),
+    );
+
+    let ix = with_multisig_signers(
+        deactivate_lookup_table(lookup_table_address, multisig.address),
+        &signer_addresses(&signers),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &signers).await,
+        Ok(())
+    );
+
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_ne!(lookup_table.meta.deactivation_slot, u64::MAX);
+}
+
+#[tokio::test]
+async fn test_multisig_close_lookup_table() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address = Pubkey::new_unique();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(multisig.address), 0);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    let signers = multisig.signers(2);
+
+    let recipient = Pubkey::new_unique();
+    let ix = with_multisig_signers(
+        close_lookup_table(lookup_table_address, multisig.address, recipient),
+        &signer_addresses(&signers[..1]),
+    );
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &signers[..1]).await,
+        missing_signature(),
+    );
+
+    let ix = with_multisig_signers(
+        close_lookup_table(lookup_table_address, multisig.address, recipient),
+        &signer_addresses(&signers),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &signers).await,
+        Ok(())
+    );
+    assert!(context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_multisig_deactivate_lookup_tables() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_addresses = vec![
+        add_lookup_table_with_authority(&mut context, multisig.address).await,
+        add_lookup_table_with_authority(&mut context, multisig.address).await,
+    ];
+    let signers = multisig.signers(2);
+
+    let ix = with_multisig_signers(
+        deactivate_lookup_tables(multisig.address, &lookup_table_addresses),
+        &signer_addresses(&signers[..1]),
+    );
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &signers[..1]).await,
+        missing_signature(),
+    );
+
+    let ix = with_multisig_signers(
+        deactivate_lookup_tables(multisig.address, &lookup_table_addresses),
+        &signer_addresses(&signers),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &signers).await,
+        Ok(())
+    );
+
+    for lookup_table_address in lookup_table_addresses {
+        let table_account = context
+            .banks_client
+            .get_account(lookup_table_address)
+            .await
+            .unwrap()
+            .unwrap();
+        let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+        assert_ne!(lookup_table.meta.deactivation_slot, u64::MAX);
+    }
+}
+
+#[tokio::test]
+async fn test_multisig_close_lookup_tables() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let mut lookup_table_addresses = vec![];
+    for _ in 0..2 {
+        let lookup_table_address = Pubkey::new_unique();
+        let initialized_table = {
+            let mut table = new_address_lookup_table(Some(multisig.address), 0);
+            table.meta.deactivation_slot = 0;
+            table
+        };
+        add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+        lookup_table_addresses.push(lookup_table_address);
+    }
+    let signers = multisig.signers(2);
+
+    let recipient = Pubkey::new_unique();
+    let ix = with_multisig_signers(
+        close_lookup_tables(multisig.address, recipient, &lookup_table_addresses),
+        &signer_addresses(&signers[..1]),
+    );
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &signers[..1]).await,
+        missing_signature(),
+    );
+
+    let ix = with_multisig_signers(
+        close_lookup_tables(multisig.address, recipient, &lookup_table_addresses),
+        &signer_addresses(&signers),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &signers).await,
+        Ok(())
+    );
+    for lookup_table_address in lookup_table_addresses {
+        assert!(context
+            .banks_client
+            .get_account(lookup_table_address)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
+
+#[tokio::test]
+async fn test_multisig_accept_authority() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, Pubkey::new_unique()).await;
+    add_lookup_table_extension_account(&mut context, lookup_table_address, Some(multisig.address))
+        .await;
+    let signers = multisig.signers(2);
+
+    let ix = with_multisig_signers(
+        accept_authority(lookup_table_address, multisig.address),
+        &signer_addresses(&signers[..1]),
+    );
+    assert_eq!(
+        process_ix_with_signers(&mut context, ix, &signers[..1]).await,
+        missing_signature(),
+    );
+
+    let ix = with_multisig_signers(
+        accept_authority(lookup_table_address, multisig.address),
+        &signer_addresses(&signers),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &signers).await,
+        Ok(())
+    );
+    assert_eq!(
+        get_lookup_table_authority(&mut context, lookup_table_address).await,
+        Some(multisig.address)
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_propose_authority() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+
+    let new_authority = Pubkey::new_unique();
+    let ix = propose_authority(
+        lookup_table_address,
+        multisig.address,
+        new_authority,
+        Some(context.payer.pubkey()),
+    );
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(
+        Option::<Pubkey>::from(extension.pending_authority),
+        Some(new_authority)
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_cancel_authority_proposal() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    add_lookup_table_extension_account(
+        &mut context,
+        lookup_table_address,
+        Some(Pubkey::new_unique()),
+    )
+    .await;
+
+    let ix = cancel_authority_proposal(lookup_table_address, multisig.address);
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.pending_authority), None);
+}
+
+#[tokio::test]
+async fn test_multisig_reactivate_lookup_table() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let lookup_table_address = Pubkey::new_unique();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(multisig.address), 10);
+        table.meta.deactivation_slot = clock.slot;
+        table
+    };
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let ix = reactivate_lookup_table(lookup_table_address, multisig.address);
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address).await;
+    assert_eq!(lookup_table.meta.deactivation_slot, Slot::MAX);
+}
+
+#[tokio::test]
+async fn test_multisig_truncate_lookup_table() {
+    let mut context = setup_test_context().await;
+    context.set_sysvar(&Clock {
+        slot: MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        ..Clock::default()
+    });
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        multisig.address,
+        context.payer.pubkey(),
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        Some(context.payer.pubkey()),
+    );
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address).await;
+    assert_eq!(lookup_table.addresses.len(), 5);
+}
+
+#[tokio::test]
+async fn test_multisig_set_extender() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+
+    let extender = Pubkey::new_unique();
+    let ix = set_extender(
+        lookup_table_address,
+        multisig.address,
+        extender,
+        Some(context.payer.pubkey()),
+    );
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.extender), Some(extender));
+}
+
+#[tokio::test]
+async fn test_multisig_set_extender_without_payer() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    set_lookup_table_extension_account(
+        &mut context,
+        LookupTableExtension::new(lookup_table_address),
+    )
+    .await;
+
+    // The signers directly follow the extension account when the optional
+    // funding and system program accounts are left out
+    let extender = Pubkey::new_unique();
+    let ix = set_extender(lookup_table_address, multisig.address, extender, None);
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.extender), Some(extender));
+}
+
+#[tokio::test]
+async fn test_multisig_remove_extender() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.extender = Some(Pubkey::new_unique()).try_into().unwrap();
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = remove_extender(lookup_table_address, multisig.address);
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.extender), None);
+}
+
+#[tokio::test]
+async fn test_multisig_set_metadata() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+
+    let ix = set_metadata(
+        lookup_table_address,
+        multisig.address,
+        Some(context.payer.pubkey()),
+        "Swap pools".to_string(),
+        String::new(),
+        vec![],
+    );
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let metadata = get_lookup_table_metadata(&mut context, lookup_table_address).await;
+    assert_eq!(metadata.name(), "Swap pools");
+}
+
+#[tokio::test]
+async fn test_multisig_schedule_deactivation() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let scheduled_slot = clock.slot.saturating_add(100);
+
+    let ix = schedule_deactivation(
+        lookup_table_address,
+        multisig.address,
+        scheduled_slot,
+        Some(context.payer.pubkey()),
+    );
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(
+        extension.scheduled_deactivation_slot(),
+        Some(scheduled_slot)
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_cancel_scheduled_deactivation() {
+    let mut context = setup_test_context().await;
+    let multisig = MultisigAuthority::new(&mut context, 2, 3).await;
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, multisig.address).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(200));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = cancel_scheduled_deactivation(lookup_table_address, multisig.address);
+    assert_multisig_approves(&mut context, &multisig, ix).await;
+
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), None);
+}
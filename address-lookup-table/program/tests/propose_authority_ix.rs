@@ -0,0 +1,245 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, add_lookup_table_extension_account, assert_ix_error,
+        get_lookup_table_extension, lookup_error, new_address_lookup_table, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{derive_lookup_table_extension_address, propose_authority},
+        state::LookupTableExtension,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::program::id,
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+#[tokio::test]
+async fn test_propose_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    // The first proposal creates the extension account, the second one
+    // replaces the pending authority in place.
+    for new_authority in [Pubkey::new_unique(), Pubkey::new_unique()] {
+        let client = &mut context.banks_client;
+        let payer = &context.payer;
+        let recent_blockhash = client
+            .get_new_latest_blockhash(&context.last_blockhash)
+            .await
+            .unwrap();
+        context.last_blockhash = recent_blockhash;
+        let transaction = Transaction::new_signed_with_payer(
+            &[propose_authority(
+                lookup_table_address,
+                authority.pubkey(),
+                new_authority,
+                Some(payer.pubkey()),
+            )],
+            Some(&payer.pubkey()),
+            &[payer, &authority],
+            recent_blockhash,
+        );
+
+        assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+        let (extension_address, _) = derive_lookup_table_extension_address(&lookup_table_address);
+        let extension_account = client
+            .get_account(extension_address)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(extension_account.owner, id());
+        assert_eq!(extension_account.data.len(), LookupTableExtension::LEN);
+
+        let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+        assert_eq!(extension.lookup_table, lookup_table_address);
+        assert_eq!(
+            Option::<Pubkey>::from(extension.pending_authority),
+            Some(new_authority)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_propose_authority_existing_extension_without_payer() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(&mut context, lookup_table_address, None).await;
+
+    let new_authority = Pubkey::new_unique();
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[propose_authority(
+            lookup_table_address,
+            authority.pubkey(),
+            new_authority,
+            None,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(
+        Option::<Pubkey>::from(extension.pending_authority),
+        Some(new_authority)
+    );
+}
+
+#[tokio::test]
+async fn test_propose_authority_without_payer() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let ix = propose_authority(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        None,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::NotEnoughAccountKeys,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_propose_authority_for_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let initialized_table = new_address_lookup_table(None, 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let authority = Keypair::new();
+    let ix = propose_authority(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        Some(context.payer.pubkey()),
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_propose_authority_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let ix = propose_authority(
+        lookup_table_address,
+        wrong_authority.pubkey(),
+        wrong_authority.pubkey(),
+        Some(context.payer.pubkey()),
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_propose_authority_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let mut ix = propose_authority(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        Some(context.payer.pubkey()),
+    );
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_propose_authority_with_wrong_extension_address() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let mut ix = propose_authority(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        Some(context.payer.pubkey()),
+    );
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::AddressMismatch,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
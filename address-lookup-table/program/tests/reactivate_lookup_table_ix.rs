@@ -0,0 +1,255 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, lookup_error, new_address_lookup_table,
+        overwrite_slot_hashes_with_slots, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::reactivate_lookup_table,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::state::AddressLookupTable,
+        clock::{Clock, Slot},
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn add_deactivated_lookup_table(
+    context: &mut ProgramTestContext,
+    authority: Pubkey,
+    deactivation_slot: Slot,
+) -> Pubkey {
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority), 10);
+        table.meta.deactivation_slot = deactivation_slot;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table).await;
+    lookup_table_address
+}
+
+async fn assert_reactivates_lookup_table(
+    context: &mut ProgramTestContext,
+    deactivation_slot: Slot,
+) {
+    let authority_keypair = Keypair::new();
+    let lookup_table_address =
+        add_deactivated_lookup_table(context, authority_keypair.pubkey(), deactivation_slot).await;
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = context.last_blockhash;
+    let transaction = Transaction::new_signed_with_payer(
+        &[reactivate_lookup_table(
+            lookup_table_address,
+            authority_keypair.pubkey(),
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        recent_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.meta.deactivation_slot, Slot::MAX);
+    assert_eq!(
+        lookup_table.meta.authority,
+        Some(authority_keypair.pubkey())
+    );
+    assert_eq!(lookup_table.addresses.len(), 10);
+}
+
+#[tokio::test]
+async fn test_reactivate_lookup_table_deactivated_in_current_slot() {
+    let mut context = setup_test_context().await;
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert_reactivates_lookup_table(&mut context, clock.slot).await;
+}
+
+#[tokio::test]
+async fn test_reactivate_lookup_table_recently_deactivated() {
+    let mut context = setup_test_context().await;
+
+    // Context sets up the slot hashes sysvar to have an entry
+    // for slot 0 which is when the table was deactivated.
+    assert_reactivates_lookup_table(&mut context, 0).await;
+}
+
+#[tokio::test]
+async fn test_reactivate_lookup_table_across_slot_hashes_window() {
+    let mut context = setup_test_context().await;
+
+    let deactivation_slot = 5;
+    let authority_keypair = Keypair::new();
+    let lookup_table_address =
+        add_deactivated_lookup_table(&mut context, authority_keypair.pubkey(), deactivation_slot)
+            .await;
+    let ix = reactivate_lookup_table(lookup_table_address, authority_keypair.pubkey());
+
+    // Once the deactivation slot falls out of the slot hashes window the
+    // table is fully deactivated and can only be closed.
+    overwrite_slot_hashes_with_slots(&context, &[8, 7, 6]);
+    assert_ix_error(
+        &mut context,
+        ix.clone(),
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::DeactivationComplete,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+
+    // While the deactivation slot is still present, reactivation succeeds.
+    overwrite_slot_hashes_with_slots(&context, &[7, 6, deactivation_slot]);
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, &authority_keypair],
+        recent_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.meta.deactivation_slot, Slot::MAX);
+}
+
+#[tokio::test]
+async fn test_reactivate_fully_deactivated_lookup_table() {
+    let mut context = setup_test_context().await;
+    overwrite_slot_hashes_with_slots(&context, &[]);
+
+    let authority_keypair = Keypair::new();
+    let lookup_table_address =
+        add_deactivated_lookup_table(&mut context, authority_keypair.pubkey(), 0).await;
+
+    let ix = reactivate_lookup_table(lookup_table_address, authority_keypair.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::DeactivationComplete,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_reactivate_active_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let authority_keypair = Keypair::new();
+    let lookup_table_address =
+        add_deactivated_lookup_table(&mut context, authority_keypair.pubkey(), Slot::MAX).await;
+
+    let ix = reactivate_lookup_table(lookup_table_address, authority_keypair.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority_keypair),
+        lookup_error(
+            AddressLookupError::TableNotDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_reactivate_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let initialized_table = {
+        let mut table = new_address_lookup_table(None, 10);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let authority = Keypair::new();
+    let ix = reactivate_lookup_table(lookup_table_address, authority.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_reactivate_lookup_table_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let lookup_table_address =
+        add_deactivated_lookup_table(&mut context, authority.pubkey(), 0).await;
+
+    let ix = reactivate_lookup_table(lookup_table_address, wrong_authority.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_reactivate_lookup_table_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_deactivated_lookup_table(&mut context, authority.pubkey(), 0).await;
+
+    let mut ix = reactivate_lookup_table(lookup_table_address, authority.pubkey());
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
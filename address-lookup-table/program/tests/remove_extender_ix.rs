@@ -0,0 +1,165 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, add_lookup_table_extension_account, assert_ix_error,
+        get_lookup_table_extension, lookup_error, new_address_lookup_table,
+        set_lookup_table_extension_account, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError, instruction::remove_extender, state::LookupTableExtension,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+async fn add_lookup_table_with_extender(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+) -> Pubkey {
+    let initialized_table = new_address_lookup_table(authority, 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table).await;
+
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.extender = Some(Pubkey::new_unique()).try_into().unwrap();
+    set_lookup_table_extension_account(context, extension).await;
+
+    lookup_table_address
+}
+
+#[tokio::test]
+async fn test_remove_extender() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_extender(&mut context, Some(authority.pubkey())).await;
+
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[remove_extender(lookup_table_address, authority.pubkey())],
+        Some(&payer.pubkey()),
+        &[payer, &authority],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.extender), None);
+}
+
+#[tokio::test]
+async fn test_remove_extender_without_extender() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    // No extension account.
+    let ix = remove_extender(lookup_table_address, authority.pubkey());
+    assert_ix_error(
+        &mut context,
+        ix.clone(),
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::NoExtender,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+
+    // Extension account without an extender. A new blockhash makes the
+    // runtime process the same instruction again.
+    add_lookup_table_extension_account(&mut context, lookup_table_address, None).await;
+    context.last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::NoExtender,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_remove_extender_for_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let lookup_table_address = add_lookup_table_with_extender(&mut context, None).await;
+
+    let authority = Keypair::new();
+    let ix = remove_extender(lookup_table_address, authority.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_remove_extender_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_extender(&mut context, Some(authority.pubkey())).await;
+
+    let ix = remove_extender(lookup_table_address, wrong_authority.pubkey());
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_remove_extender_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_extender(&mut context, Some(authority.pubkey())).await;
+
+    let mut ix = remove_extender(lookup_table_address, authority.pubkey());
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
@@ -0,0 +1,239 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    common::{
+        add_lookup_table_account, new_address_lookup_table, overwrite_slot_hashes_with_slots,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{resolve_addresses, MAX_RESOLVED_ADDRESSES},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::AccountSharedData,
+        address_lookup_table::state::AddressLookupTable,
+        clock::{Clock, Slot},
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::Signer,
+        system_program,
+        transaction::{Transaction, TransactionError},
+    },
+};
+
+mod common;
+
+fn set_clock_slot(context: &ProgramTestContext, slot: Slot) {
+    context.set_sysvar(&Clock {
+        slot,
+        ..Clock::default()
+    });
+}
+
+async fn add_resolvable_lookup_table(
+    context: &mut ProgramTestContext,
+    deactivation_slot: Slot,
+    last_extended_slot: Slot,
+) -> (Pubkey, AddressLookupTable<'static>) {
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(Pubkey::new_unique()), 10);
+        table.meta.deactivation_slot = deactivation_slot;
+        table.meta.last_extended_slot = last_extended_slot;
+        table.meta.last_extended_slot_start_index = 6;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table.clone()).await;
+    (lookup_table_address, initialized_table)
+}
+
+/// Simulates the instruction and returns its result along with the addresses
+/// it returned
+async fn simulate_resolve_addresses(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+) -> Result<Vec<Pubkey>, TransactionError> {
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer],
+        context.last_blockhash,
+    );
+
+    let simulation = client.simulate_transaction(transaction).await.unwrap();
+    simulation.result.unwrap()?;
+    let return_data = simulation
+        .simulation_details
+        .unwrap()
+        .return_data
+        .map(|return_data| {
+            assert_eq!(
+                return_data.program_id,
+                solana_address_lookup_table_program::id()
+            );
+            return_data.data
+        })
+        .unwrap_or_default();
+    Ok(bytemuck::cast_slice(&return_data).to_vec())
+}
+
+fn lookup_error(err: AddressLookupError) -> Result<Vec<Pubkey>, TransactionError> {
+    Err(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(err as u32),
+    ))
+}
+
+#[tokio::test]
+async fn test_resolve_addresses() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let (lookup_table_address, initialized_table) =
+        add_resolvable_lookup_table(&mut context, Slot::MAX, 99).await;
+    let addresses = &initialized_table.addresses;
+
+    let ix = resolve_addresses(lookup_table_address, vec![9, 0, 4, 0]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        Ok(vec![addresses[9], addresses[0], addresses[4], addresses[0]])
+    );
+
+    let ix = resolve_addresses(lookup_table_address, vec![]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        Ok(vec![])
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_max_addresses() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let (lookup_table_address, initialized_table) =
+        add_resolvable_lookup_table(&mut context, Slot::MAX, 0).await;
+
+    let indexes: Vec<u8> = (0..10).cycle().take(MAX_RESOLVED_ADDRESSES).collect();
+    let expected = indexes
+        .iter()
+        .map(|index| initialized_table.addresses[usize::from(*index)])
+        .collect();
+    let ix = resolve_addresses(lookup_table_address, indexes);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        Ok(expected)
+    );
+
+    let ix = resolve_addresses(
+        lookup_table_address,
+        vec![0; MAX_RESOLVED_ADDRESSES.saturating_add(1)],
+    );
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        Err(TransactionError::InstructionError(
+            0,
+            InstructionError::InvalidInstructionData
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_addresses_extended_in_current_slot() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let (lookup_table_address, initialized_table) =
+        add_resolvable_lookup_table(&mut context, Slot::MAX, 100).await;
+
+    // Addresses appended before the current slot are active
+    let ix = resolve_addresses(lookup_table_address, vec![5]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        Ok(vec![initialized_table.addresses[5]])
+    );
+
+    // Addresses appended in the current slot are not active yet
+    let ix = resolve_addresses(lookup_table_address, vec![0, 6]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        lookup_error(AddressLookupError::InvalidLookupIndex)
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_addresses_out_of_bounds() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let (lookup_table_address, _) = add_resolvable_lookup_table(&mut context, Slot::MAX, 0).await;
+
+    let ix = resolve_addresses(lookup_table_address, vec![10]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        lookup_error(AddressLookupError::InvalidLookupIndex)
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_addresses_deactivating_lookup_table() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+    overwrite_slot_hashes_with_slots(&context, &[99, 98]);
+
+    let (lookup_table_address, initialized_table) =
+        add_resolvable_lookup_table(&mut context, 98, 0).await;
+
+    let ix = resolve_addresses(lookup_table_address, vec![1]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        Ok(vec![initialized_table.addresses[1]])
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_addresses_deactivated_lookup_table() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+    overwrite_slot_hashes_with_slots(&context, &[99]);
+
+    let (lookup_table_address, _) = add_resolvable_lookup_table(&mut context, 98, 0).await;
+
+    let ix = resolve_addresses(lookup_table_address, vec![1]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        lookup_error(AddressLookupError::LookupTableAccountNotFound)
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_addresses_missing_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let ix = resolve_addresses(Pubkey::new_unique(), vec![0]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        lookup_error(AddressLookupError::LookupTableAccountNotFound)
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_addresses_wrong_owner() {
+    let mut context = setup_test_context().await;
+
+    let lookup_table_address = Pubkey::new_unique();
+    let initialized_table = new_address_lookup_table(Some(Pubkey::new_unique()), 10);
+    let data = initialized_table.serialize_for_tests().unwrap();
+    let mut account = AccountSharedData::new(1_000_000_000, data.len(), &system_program::id());
+    account.set_data_from_slice(&data);
+    context.set_account(&lookup_table_address, &account);
+
+    let ix = resolve_addresses(lookup_table_address, vec![0]);
+    assert_eq!(
+        simulate_resolve_addresses(&mut context, ix).await,
+        lookup_error(AddressLookupError::InvalidAccountOwner)
+    );
+}
@@ -0,0 +1,201 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    common::{
+        add_lookup_table_account, new_address_lookup_table, overwrite_slot_hashes_with_slots,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        instruction::{
+            create_lookup_table_signed, create_lookup_table_with_addresses,
+            create_lookup_table_with_seed, derive_lookup_table_address,
+            derive_lookup_table_address_with_seed, extend_lookup_table, extend_lookup_table_unique,
+        },
+        return_data::ReturnData,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        clock::{Clock, Slot},
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+/// Simulates the instruction and decodes the return data it set
+async fn simulate_return_data(
+    context: &mut ProgramTestContext,
+    ix: Instruction,
+    authority: &Keypair,
+) -> ReturnData {
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, authority],
+        context.last_blockhash,
+    );
+
+    let simulation = client.simulate_transaction(transaction).await.unwrap();
+    assert_eq!(simulation.result, Some(Ok(())));
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    ReturnData::decode(&return_data.program_id, &return_data.data).unwrap()
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_return_data() {
+    let mut context = setup_test_context().await;
+
+    let recent_slot = 123;
+    overwrite_slot_hashes_with_slots(&context, &[recent_slot]);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, bump_seed) =
+        derive_lookup_table_address(&authority.pubkey(), recent_slot);
+    let expected = ReturnData::CreateLookupTable {
+        lookup_table_address,
+        bump_seed,
+    };
+
+    let (ix, _) =
+        create_lookup_table_signed(authority.pubkey(), context.payer.pubkey(), recent_slot);
+    assert_eq!(
+        simulate_return_data(&mut context, ix, &authority).await,
+        expected
+    );
+
+    let (ix, _) = create_lookup_table_with_addresses(
+        authority.pubkey(),
+        context.payer.pubkey(),
+        recent_slot,
+        vec![Pubkey::new_unique()],
+        false,
+    );
+    assert_eq!(
+        simulate_return_data(&mut context, ix, &authority).await,
+        expected
+    );
+}
+
+#[tokio::test]
+async fn test_create_lookup_table_with_seed_return_data() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let seed = [7; 32];
+    let (lookup_table_address, bump_seed) =
+        derive_lookup_table_address_with_seed(&authority.pubkey(), &seed);
+
+    let (ix, _) = create_lookup_table_with_seed(authority.pubkey(), context.payer.pubkey(), seed);
+    assert_eq!(
+        simulate_return_data(&mut context, ix, &authority).await,
+        ReturnData::CreateLookupTable {
+            lookup_table_address,
+            bump_seed,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_return_data() {
+    let mut context = setup_test_context().await;
+    let current_slot: Slot = 100;
+    context.set_sysvar(&Clock {
+        slot: current_slot,
+        ..Clock::default()
+    });
+
+    let authority = Keypair::new();
+    let lookup_table_address = Pubkey::new_unique();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    add_lookup_table_account(
+        &mut context,
+        lookup_table_address,
+        initialized_table.clone(),
+    )
+    .await;
+
+    let ix = extend_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        vec![Pubkey::new_unique(), Pubkey::new_unique()],
+    );
+    assert_eq!(
+        simulate_return_data(&mut context, ix, &authority).await,
+        ReturnData::ExtendLookupTable {
+            start_index: 10,
+            new_len: 12,
+            last_extended_slot: current_slot,
+            appended_len: 2,
+        }
+    );
+
+    // Duplicates are left out of the reported range
+    let ix = extend_lookup_table_unique(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        vec![
+            initialized_table.addresses[3],
+            Pubkey::new_unique(),
+            initialized_table.addresses[0],
+        ],
+        true,
+    );
+    assert_eq!(
+        simulate_return_data(&mut context, ix, &authority).await,
+        ReturnData::ExtendLookupTable {
+            start_index: 10,
+            new_len: 11,
+            last_extended_slot: current_slot,
+            appended_len: 1,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_extend_lookup_table_only_duplicates_return_data() {
+    let mut context = setup_test_context().await;
+    context.set_sysvar(&Clock {
+        slot: 100,
+        ..Clock::default()
+    });
+
+    let authority = Keypair::new();
+    let lookup_table_address = Pubkey::new_unique();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority.pubkey()), 256);
+        table.meta.last_extended_slot = 42;
+        table
+    };
+    add_lookup_table_account(
+        &mut context,
+        lookup_table_address,
+        initialized_table.clone(),
+    )
+    .await;
+
+    // Nothing is appended, even to a full table, so the table keeps its last
+    // extended slot
+    let ix = extend_lookup_table_unique(
+        lookup_table_address,
+        authority.pubkey(),
+        None,
+        vec![initialized_table.addresses[255]],
+        true,
+    );
+    assert_eq!(
+        simulate_return_data(&mut context, ix, &authority).await,
+        ReturnData::ExtendLookupTable {
+            start_index: 0,
+            new_len: 256,
+            last_extended_slot: 42,
+            appended_len: 0,
+        }
+    );
+}
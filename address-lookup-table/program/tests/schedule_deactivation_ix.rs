@@ -0,0 +1,520 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, get_lookup_table_extension, lookup_error,
+        new_address_lookup_table, process_ix_with_signers, set_lookup_table_extension_account,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{
+            cancel_scheduled_deactivation, close_lookup_table_with_accounts,
+            execute_scheduled_deactivation, freeze_lookup_table,
+            freeze_lookup_table_with_extension, reactivate_lookup_table, schedule_deactivation,
+        },
+        state::LookupTableExtension,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        address_lookup_table::state::AddressLookupTable,
+        clock::{Clock, Slot},
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+};
+
+mod common;
+
+fn set_clock_slot(context: &ProgramTestContext, slot: Slot) {
+    context.set_sysvar(&Clock {
+        slot,
+        ..Clock::default()
+    });
+}
+
+async fn add_lookup_table_with_authority(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+    deactivation_slot: Slot,
+) -> (Pubkey, AddressLookupTable<'static>) {
+    let initialized_table = {
+        let mut table = new_address_lookup_table(authority, 10);
+        table.meta.deactivation_slot = deactivation_slot;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table.clone()).await;
+    (lookup_table_address, initialized_table)
+}
+
+async fn get_lookup_table(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+) -> AddressLookupTable<'static> {
+    let table_account = context
+        .banks_client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    AddressLookupTable {
+        meta: lookup_table.meta,
+        addresses: lookup_table.addresses.into_owned().into(),
+    }
+}
+
+#[tokio::test]
+async fn test_scheduled_deactivation() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, mut initialized_table) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+
+    let ix = schedule_deactivation(
+        lookup_table_address,
+        authority.pubkey(),
+        200,
+        Some(context.payer.pubkey()),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), Some(200));
+
+    // The table stays active until the scheduled slot, flagging the schedule
+    // in the second byte of the SDK's `_padding`
+    initialized_table.meta._padding = 1 << 8;
+    assert_eq!(
+        get_lookup_table(&mut context, lookup_table_address).await,
+        initialized_table
+    );
+    set_clock_slot(&context, 199);
+    let ix = execute_scheduled_deactivation(lookup_table_address);
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        lookup_error(
+            AddressLookupError::DeactivationNotDue,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+
+    // Once the slot is reached, anyone can deactivate the table
+    set_clock_slot(&context, 205);
+    let ix = execute_scheduled_deactivation(lookup_table_address);
+    assert_matches!(process_ix_with_signers(&mut context, ix, &[]).await, Ok(()));
+
+    initialized_table.meta.deactivation_slot = 205;
+    initialized_table.meta._padding = 0;
+    assert_eq!(
+        get_lookup_table(&mut context, lookup_table_address).await,
+        initialized_table
+    );
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), None);
+}
+
+#[tokio::test]
+async fn test_reschedule_deactivation() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(200));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = schedule_deactivation(lookup_table_address, authority.pubkey(), 300, None);
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), Some(300));
+}
+
+#[tokio::test]
+async fn test_schedule_deactivation_not_in_future() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+
+    let ix = schedule_deactivation(
+        lookup_table_address,
+        authority.pubkey(),
+        100,
+        Some(context.payer.pubkey()),
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::ScheduledSlotNotInFuture,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_schedule_deactivation_for_deactivated_lookup_table() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), 90).await;
+
+    let ix = schedule_deactivation(
+        lookup_table_address,
+        authority.pubkey(),
+        200,
+        Some(context.payer.pubkey()),
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_schedule_deactivation_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+
+    let ix = schedule_deactivation(
+        lookup_table_address,
+        wrong_authority.pubkey(),
+        200,
+        Some(context.payer.pubkey()),
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_schedule_deactivation_without_signing() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+
+    let mut ix = schedule_deactivation(
+        lookup_table_address,
+        authority.pubkey(),
+        200,
+        Some(context.payer.pubkey()),
+    );
+    ix.accounts[1].is_signer = false;
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_cancel_scheduled_deactivation() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(200));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = cancel_scheduled_deactivation(lookup_table_address, authority.pubkey());
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), None);
+
+    // A cancelled schedule can no longer be executed or cancelled
+    set_clock_slot(&context, 200);
+    let ix = execute_scheduled_deactivation(lookup_table_address);
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        lookup_error(
+            AddressLookupError::NoScheduledDeactivation,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+
+    context.last_blockhash = context
+        .banks_client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let ix = cancel_scheduled_deactivation(lookup_table_address, authority.pubkey());
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::NoScheduledDeactivation,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_cancel_scheduled_deactivation_without_extension() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+
+    let ix = cancel_scheduled_deactivation(lookup_table_address, authority.pubkey());
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::NoScheduledDeactivation,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_cancel_scheduled_deactivation_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(200));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = cancel_scheduled_deactivation(lookup_table_address, wrong_authority.pubkey());
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_execute_scheduled_deactivation_for_deactivated_lookup_table() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    // The authority deactivated the table before the scheduled slot
+    let authority = Pubkey::new_unique();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority), 90).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(95));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = execute_scheduled_deactivation(lookup_table_address);
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        lookup_error(
+            AddressLookupError::TableDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_execute_scheduled_deactivation_for_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    // The table was frozen after the deactivation was scheduled, before
+    // freezing had to clear the schedule
+    let (lookup_table_address, initialized_table) =
+        add_lookup_table_with_authority(&mut context, None, Slot::MAX).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(95));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = execute_scheduled_deactivation(lookup_table_address);
+    assert_matches!(process_ix_with_signers(&mut context, ix, &[]).await, Ok(()));
+
+    // The stale schedule is discarded and the table stays active
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address).await;
+    assert_eq!(lookup_table, initialized_table);
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), None);
+}
+
+#[tokio::test]
+async fn test_freeze_lookup_table_clears_scheduled_deactivation() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(200));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = freeze_lookup_table_with_extension(lookup_table_address, authority.pubkey());
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address).await;
+    assert_eq!(lookup_table.meta.authority, None);
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), None);
+}
+
+#[tokio::test]
+async fn test_freeze_lookup_table_with_scheduled_deactivation_requires_extension() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+
+    let ix = schedule_deactivation(
+        lookup_table_address,
+        authority.pubkey(),
+        200,
+        Some(context.payer.pubkey()),
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+
+    let ix = freeze_lookup_table(lookup_table_address, authority.pubkey());
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::NotEnoughAccountKeys,
+    )
+    .await;
+
+    let ix = freeze_lookup_table_with_extension(lookup_table_address, authority.pubkey());
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+
+    let lookup_table = get_lookup_table(&mut context, lookup_table_address).await;
+    assert_eq!(lookup_table.meta.authority, None);
+    assert_eq!(lookup_table.meta._padding, 0);
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(extension.scheduled_deactivation_slot(), None);
+}
+
+#[tokio::test]
+async fn test_close_lookup_table_with_scheduled_deactivation() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(200));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = close_lookup_table_with_accounts(
+        lookup_table_address,
+        authority.pubkey(),
+        context.payer.pubkey(),
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableNotDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_reactivate_lookup_table_with_scheduled_deactivation() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, 100);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey()), Slot::MAX).await;
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.set_scheduled_deactivation_slot(Some(200));
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let ix = reactivate_lookup_table(lookup_table_address, authority.pubkey());
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableNotDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
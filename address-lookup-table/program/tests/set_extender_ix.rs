@@ -0,0 +1,230 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, add_lookup_table_extension_account, assert_ix_error,
+        get_lookup_table_extension, lookup_error, new_address_lookup_table,
+        set_lookup_table_extension_account, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{
+            close_lookup_table, deactivate_lookup_table, freeze_lookup_table, set_extender,
+        },
+        state::LookupTableExtension,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::{Instruction, InstructionError},
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+#[tokio::test]
+async fn test_set_extender() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    // The first call creates the extension account, the second one replaces
+    // the extender in place.
+    for extender in [Pubkey::new_unique(), Pubkey::new_unique()] {
+        let client = &mut context.banks_client;
+        let payer = &context.payer;
+        let recent_blockhash = client
+            .get_new_latest_blockhash(&context.last_blockhash)
+            .await
+            .unwrap();
+        context.last_blockhash = recent_blockhash;
+        let transaction = Transaction::new_signed_with_payer(
+            &[set_extender(
+                lookup_table_address,
+                authority.pubkey(),
+                extender,
+                Some(payer.pubkey()),
+            )],
+            Some(&payer.pubkey()),
+            &[payer, &authority],
+            recent_blockhash,
+        );
+
+        assert_matches!(client.process_transaction(transaction).await, Ok(()));
+        let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+        assert_eq!(extension.lookup_table, lookup_table_address);
+        assert_eq!(Option::<Pubkey>::from(extension.extender), Some(extender));
+    }
+}
+
+#[tokio::test]
+async fn test_set_extender_keeps_pending_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let pending_authority = Pubkey::new_unique();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+    add_lookup_table_extension_account(&mut context, lookup_table_address, Some(pending_authority))
+        .await;
+
+    let extender = Pubkey::new_unique();
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let transaction = Transaction::new_signed_with_payer(
+        &[set_extender(
+            lookup_table_address,
+            authority.pubkey(),
+            extender,
+            None,
+        )],
+        Some(&payer.pubkey()),
+        &[payer, &authority],
+        context.last_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+    let extension = get_lookup_table_extension(&mut context, lookup_table_address).await;
+    assert_eq!(Option::<Pubkey>::from(extension.extender), Some(extender));
+    assert_eq!(
+        Option::<Pubkey>::from(extension.pending_authority),
+        Some(pending_authority)
+    );
+}
+
+#[tokio::test]
+async fn test_set_extender_for_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let initialized_table = new_address_lookup_table(None, 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let authority = Keypair::new();
+    let ix = set_extender(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        Some(context.payer.pubkey()),
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_set_extender_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let ix = set_extender(
+        lookup_table_address,
+        wrong_authority.pubkey(),
+        wrong_authority.pubkey(),
+        Some(context.payer.pubkey()),
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_set_extender_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = new_address_lookup_table(Some(authority.pubkey()), 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let mut ix = set_extender(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        Some(context.payer.pubkey()),
+    );
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_extender_cannot_manage_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let extender = Keypair::new();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority.pubkey()), 10);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let mut extension = LookupTableExtension::new(lookup_table_address);
+    extension.extender = Some(extender.pubkey()).try_into().unwrap();
+    set_lookup_table_extension_account(&mut context, extension).await;
+
+    let instructions: [Instruction; 4] = [
+        freeze_lookup_table(lookup_table_address, extender.pubkey()),
+        deactivate_lookup_table(lookup_table_address, extender.pubkey()),
+        close_lookup_table(
+            lookup_table_address,
+            extender.pubkey(),
+            context.payer.pubkey(),
+        ),
+        set_extender(
+            lookup_table_address,
+            extender.pubkey(),
+            extender.pubkey(),
+            None,
+        ),
+    ];
+    for ix in instructions {
+        assert_ix_error(
+            &mut context,
+            ix,
+            Some(&extender),
+            lookup_error(
+                AddressLookupError::IncorrectAuthority,
+                InstructionError::IncorrectAuthority,
+            ),
+        )
+        .await;
+    }
+}
@@ -0,0 +1,266 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, get_lookup_table_metadata, lookup_error,
+        new_address_lookup_table, process_ix_with_signers, set_lookup_table_metadata_account,
+        setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::set_metadata,
+        state::{LookupTableMetadata, MAX_METADATA_NAME_LEN, MAX_METADATA_TAGS},
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        instruction::InstructionError,
+        pubkey::Pubkey,
+        signature::{Keypair, Signer},
+    },
+};
+
+mod common;
+
+async fn add_lookup_table_with_authority(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+) -> Pubkey {
+    let initialized_table = new_address_lookup_table(authority, 10);
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table).await;
+    lookup_table_address
+}
+
+#[tokio::test]
+async fn test_set_metadata() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey())).await;
+
+    // The first call creates the metadata account
+    let ix = set_metadata(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        "Swap pools".to_string(),
+        "https://example.com/pools.json".to_string(),
+        vec!["defi".to_string(), "amm".to_string()],
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+    let metadata = get_lookup_table_metadata(&mut context, lookup_table_address).await;
+    assert_eq!(metadata.lookup_table, lookup_table_address);
+    assert_eq!(metadata.name(), "Swap pools");
+    assert_eq!(metadata.uri(), "https://example.com/pools.json");
+    assert_eq!(metadata.tags(), vec!["defi", "amm"]);
+
+    // Later calls replace every field in place
+    let ix = set_metadata(
+        lookup_table_address,
+        authority.pubkey(),
+        None,
+        "Pools".to_string(),
+        String::new(),
+        vec!["amm".to_string()],
+    );
+    assert_matches!(
+        process_ix_with_signers(&mut context, ix, &[&authority]).await,
+        Ok(())
+    );
+    let metadata = get_lookup_table_metadata(&mut context, lookup_table_address).await;
+    assert_eq!(metadata.name(), "Pools");
+    assert_eq!(metadata.uri(), "");
+    assert_eq!(metadata.tags(), vec!["amm"]);
+}
+
+#[tokio::test]
+async fn test_set_metadata_without_payer() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey())).await;
+
+    let ix = set_metadata(
+        lookup_table_address,
+        authority.pubkey(),
+        None,
+        "name".to_string(),
+        String::new(),
+        vec![],
+    );
+
+    // A payer is required to create the metadata account
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::NotEnoughAccountKeys,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_set_metadata_exceeding_limits() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey())).await;
+    set_lookup_table_metadata_account(
+        &mut context,
+        LookupTableMetadata::new(lookup_table_address, "name", "", &[]).unwrap(),
+    )
+    .await;
+
+    let too_long_name = "n".repeat(MAX_METADATA_NAME_LEN.saturating_add(1));
+    let too_many_tags = vec!["tag".to_string(); MAX_METADATA_TAGS.saturating_add(1)];
+    for (name, tags) in [
+        (too_long_name, vec![]),
+        ("name".to_string(), too_many_tags),
+        ("name".to_string(), vec![String::new()]),
+        ("na\0me".to_string(), vec![]),
+    ] {
+        let ix = set_metadata(
+            lookup_table_address,
+            authority.pubkey(),
+            None,
+            name,
+            String::new(),
+            tags,
+        );
+        assert_ix_error(
+            &mut context,
+            ix,
+            Some(&authority),
+            lookup_error(
+                AddressLookupError::InvalidMetadata,
+                InstructionError::InvalidInstructionData,
+            ),
+        )
+        .await;
+    }
+
+    let metadata = get_lookup_table_metadata(&mut context, lookup_table_address).await;
+    assert_eq!(metadata.name(), "name");
+}
+
+#[tokio::test]
+async fn test_set_metadata_for_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let lookup_table_address = add_lookup_table_with_authority(&mut context, None).await;
+
+    let authority = Keypair::new();
+    let ix = set_metadata(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        "name".to_string(),
+        String::new(),
+        vec![],
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_set_metadata_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey())).await;
+
+    let ix = set_metadata(
+        lookup_table_address,
+        wrong_authority.pubkey(),
+        Some(context.payer.pubkey()),
+        "name".to_string(),
+        String::new(),
+        vec![],
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_set_metadata_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey())).await;
+
+    let mut ix = set_metadata(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        "name".to_string(),
+        String::new(),
+        vec![],
+    );
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_set_metadata_with_wrong_metadata_address() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let lookup_table_address =
+        add_lookup_table_with_authority(&mut context, Some(authority.pubkey())).await;
+
+    let mut ix = set_metadata(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(context.payer.pubkey()),
+        "name".to_string(),
+        String::new(),
+        vec![],
+    );
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::AddressMismatch,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
@@ -0,0 +1,550 @@
+#![cfg(feature = "test-sbf")]
+
+use {
+    assert_matches::assert_matches,
+    common::{
+        add_lookup_table_account, assert_ix_error, get_lookup_table_extension, lookup_error,
+        new_address_lookup_table, setup_test_context,
+    },
+    solana_address_lookup_table_program::{
+        error::AddressLookupError,
+        instruction::{
+            derive_lookup_table_extension_address, extend_lookup_table, truncate_lookup_table,
+        },
+        state::MIN_TRUNCATION_COOL_DOWN_SLOTS,
+    },
+    solana_program_test::*,
+    solana_sdk::{
+        account::AccountSharedData,
+        address_lookup_table::state::{AddressLookupTable, LOOKUP_TABLE_META_SIZE},
+        clock::{Clock, Slot},
+        instruction::{AccountMeta, Instruction, InstructionError},
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+        signature::{Keypair, Signer},
+        system_program,
+        transaction::Transaction,
+    },
+};
+
+mod common;
+
+const CURRENT_SLOT: Slot = 1000;
+
+async fn add_extended_lookup_table(
+    context: &mut ProgramTestContext,
+    authority: Option<Pubkey>,
+    last_extended_slot: Slot,
+    last_extended_slot_start_index: u8,
+) -> (Pubkey, AddressLookupTable<'static>) {
+    let initialized_table = {
+        let mut table = new_address_lookup_table(authority, 10);
+        table.meta.last_extended_slot = last_extended_slot;
+        table.meta.last_extended_slot_start_index = last_extended_slot_start_index;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(context, lookup_table_address, initialized_table.clone()).await;
+    (lookup_table_address, initialized_table)
+}
+
+fn set_clock_slot(context: &ProgramTestContext, slot: Slot) {
+    context.set_sysvar(&Clock {
+        slot,
+        ..Clock::default()
+    });
+}
+
+fn add_recipient_account(context: &mut ProgramTestContext) -> Pubkey {
+    let recipient = Pubkey::new_unique();
+    let account = AccountSharedData::new(LAMPORTS_PER_SOL, 0, &system_program::id());
+    context.set_account(&recipient, &account);
+    recipient
+}
+
+async fn assert_truncates_lookup_table(
+    context: &mut ProgramTestContext,
+    lookup_table_address: Pubkey,
+    authority: &Keypair,
+    initialized_table: &AddressLookupTable<'static>,
+    ix: Instruction,
+    new_len: usize,
+    cool_down_slots: Slot,
+) {
+    let expected_meta = {
+        let mut meta = initialized_table.meta.clone();
+        meta.last_extended_slot_start_index = meta
+            .last_extended_slot_start_index
+            .min(u8::try_from(new_len).unwrap());
+        // The SDK reads the truncation cool-down flag as padding
+        meta._padding = 1;
+        meta
+    };
+
+    let recipient = ix.accounts[2].pubkey;
+    let client = &mut context.banks_client;
+    let payer = &context.payer;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    context.last_blockhash = recent_blockhash;
+
+    let rent = client.get_rent().await.unwrap();
+    let old_table_lamports = client.get_balance(lookup_table_address).await.unwrap();
+    let old_recipient_lamports = client.get_balance(recipient).await.unwrap();
+
+    let transaction = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[payer, authority],
+        recent_blockhash,
+    );
+
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+    let table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let new_table_data_len =
+        LOOKUP_TABLE_META_SIZE.saturating_add(new_len.saturating_mul(PUBKEY_BYTES));
+    assert_eq!(table_account.data.len(), new_table_data_len);
+    assert_eq!(
+        table_account.lamports,
+        rent.minimum_balance(new_table_data_len)
+    );
+
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.meta, expected_meta);
+    assert_eq!(
+        lookup_table.addresses.as_ref(),
+        &initialized_table.addresses[..new_len]
+    );
+
+    let new_recipient_lamports = client.get_balance(recipient).await.unwrap();
+    assert_eq!(
+        new_recipient_lamports.saturating_sub(old_recipient_lamports),
+        old_table_lamports.saturating_sub(table_account.lamports),
+    );
+
+    let extension = get_lookup_table_extension(context, lookup_table_address).await;
+    assert_eq!(
+        extension.truncation_cool_down_end_slot(),
+        Some(CURRENT_SLOT.saturating_add(cool_down_slots))
+    );
+}
+
+/// Adds the extension account to an extend instruction, as required while a
+/// truncation cool-down is pending
+fn with_extension_account(mut ix: Instruction, lookup_table_address: &Pubkey) -> Instruction {
+    let (extension_address, _) = derive_lookup_table_extension_address(lookup_table_address);
+    ix.accounts
+        .insert(2, AccountMeta::new_readonly(extension_address, false));
+    ix
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table() {
+    for new_len in [6, 0] {
+        let mut context = setup_test_context().await;
+        set_clock_slot(&context, CURRENT_SLOT);
+
+        let authority = Keypair::new();
+        let (lookup_table_address, initialized_table) = add_extended_lookup_table(
+            &mut context,
+            Some(authority.pubkey()),
+            CURRENT_SLOT.saturating_sub(MIN_TRUNCATION_COOL_DOWN_SLOTS),
+            5,
+        )
+        .await;
+
+        let ix = truncate_lookup_table(
+            lookup_table_address,
+            authority.pubkey(),
+            add_recipient_account(&mut context),
+            new_len,
+            MIN_TRUNCATION_COOL_DOWN_SLOTS,
+            Some(context.payer.pubkey()),
+        );
+        assert_truncates_lookup_table(
+            &mut context,
+            lookup_table_address,
+            &authority,
+            &initialized_table,
+            ix,
+            usize::from(new_len),
+            MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        )
+        .await;
+    }
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table_during_cool_down() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, CURRENT_SLOT);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) = add_extended_lookup_table(
+        &mut context,
+        Some(authority.pubkey()),
+        CURRENT_SLOT
+            .saturating_sub(MIN_TRUNCATION_COOL_DOWN_SLOTS)
+            .saturating_add(1),
+        5,
+    )
+    .await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        6,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TruncationTooSoon,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table_in_extended_slot() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, CURRENT_SLOT);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_extended_lookup_table(&mut context, Some(authority.pubkey()), CURRENT_SLOT, 5).await;
+
+    // Not even the addresses appended in this slot may be removed
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TruncationTooSoon,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_extend_truncated_lookup_table() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, CURRENT_SLOT);
+
+    let cool_down_slots = MIN_TRUNCATION_COOL_DOWN_SLOTS.saturating_add(1);
+    let authority = Keypair::new();
+    let (lookup_table_address, initialized_table) = add_extended_lookup_table(
+        &mut context,
+        Some(authority.pubkey()),
+        CURRENT_SLOT.saturating_sub(cool_down_slots),
+        5,
+    )
+    .await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        add_recipient_account(&mut context),
+        5,
+        cool_down_slots,
+        Some(context.payer.pubkey()),
+    );
+    assert_truncates_lookup_table(
+        &mut context,
+        lookup_table_address,
+        &authority,
+        &initialized_table,
+        ix,
+        5,
+        cool_down_slots,
+    )
+    .await;
+
+    // The end of the cool-down is only recorded in the extension account
+    let payer = context.payer.pubkey();
+    let extend_ix = extend_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Some(payer),
+        vec![Pubkey::new_unique()],
+    );
+    set_clock_slot(&context, CURRENT_SLOT.saturating_add(cool_down_slots));
+    assert_ix_error(
+        &mut context,
+        extend_ix.clone(),
+        Some(&authority),
+        InstructionError::NotEnoughAccountKeys,
+    )
+    .await;
+
+    // Re-extending would reuse the removed indexes while transactions
+    // compiled against them may still land
+    let extend_ix = with_extension_account(extend_ix, &lookup_table_address);
+    for slot in [
+        CURRENT_SLOT,
+        CURRENT_SLOT
+            .saturating_add(cool_down_slots)
+            .saturating_sub(1),
+    ] {
+        set_clock_slot(&context, slot);
+        assert_ix_error(
+            &mut context,
+            extend_ix.clone(),
+            Some(&authority),
+            lookup_error(
+                AddressLookupError::TableRecentlyTruncated,
+                InstructionError::InvalidArgument,
+            ),
+        )
+        .await;
+    }
+
+    set_clock_slot(&context, CURRENT_SLOT.saturating_add(cool_down_slots));
+    let client = &mut context.banks_client;
+    let recent_blockhash = client
+        .get_new_latest_blockhash(&context.last_blockhash)
+        .await
+        .unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[extend_ix],
+        Some(&payer),
+        &[&context.payer, &authority],
+        recent_blockhash,
+    );
+    assert_matches!(client.process_transaction(transaction).await, Ok(()));
+
+    // Once the cool-down is over, the extension account is no longer needed
+    let table_account = client
+        .get_account(lookup_table_address)
+        .await
+        .unwrap()
+        .unwrap();
+    let lookup_table = AddressLookupTable::deserialize(&table_account.data).unwrap();
+    assert_eq!(lookup_table.meta._padding, 0);
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table_with_short_cool_down() {
+    let mut context = setup_test_context().await;
+    set_clock_slot(&context, CURRENT_SLOT);
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_extended_lookup_table(&mut context, Some(authority.pubkey()), 0, 0).await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS.saturating_sub(1),
+        Some(context.payer.pubkey()),
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        InstructionError::InvalidArgument,
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table_without_removing_addresses() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_extended_lookup_table(&mut context, Some(authority.pubkey()), 0, 0).await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        10,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::InvalidAddressRange,
+            InstructionError::InvalidInstructionData,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_truncate_deactivated_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let initialized_table = {
+        let mut table = new_address_lookup_table(Some(authority.pubkey()), 10);
+        table.meta.deactivation_slot = 0;
+        table
+    };
+    let lookup_table_address = Pubkey::new_unique();
+    add_lookup_table_account(&mut context, lookup_table_address, initialized_table).await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::TableDeactivated,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table_to_itself() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_extended_lookup_table(&mut context, Some(authority.pubkey()), 0, 0).await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        lookup_table_address,
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::RecipientIsTable,
+            InstructionError::InvalidArgument,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_truncate_immutable_lookup_table() {
+    let mut context = setup_test_context().await;
+
+    let (lookup_table_address, _) = add_extended_lookup_table(&mut context, None, 0, 0).await;
+
+    let authority = Keypair::new();
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&authority),
+        lookup_error(
+            AddressLookupError::LookupTableImmutable,
+            InstructionError::Immutable,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table_with_wrong_authority() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let wrong_authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_extended_lookup_table(&mut context, Some(authority.pubkey()), 0, 0).await;
+
+    let ix = truncate_lookup_table(
+        lookup_table_address,
+        wrong_authority.pubkey(),
+        Pubkey::new_unique(),
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        Some(&wrong_authority),
+        lookup_error(
+            AddressLookupError::IncorrectAuthority,
+            InstructionError::IncorrectAuthority,
+        ),
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_truncate_lookup_table_without_signing() {
+    let mut context = setup_test_context().await;
+
+    let authority = Keypair::new();
+    let (lookup_table_address, _) =
+        add_extended_lookup_table(&mut context, Some(authority.pubkey()), 0, 0).await;
+
+    let mut ix = truncate_lookup_table(
+        lookup_table_address,
+        authority.pubkey(),
+        Pubkey::new_unique(),
+        5,
+        MIN_TRUNCATION_COOL_DOWN_SLOTS,
+        None,
+    );
+    ix.accounts[1].is_signer = false;
+
+    assert_ix_error(
+        &mut context,
+        ix,
+        None,
+        InstructionError::MissingRequiredSignature,
+    )
+    .await;
+}
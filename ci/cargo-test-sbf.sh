@@ -2,6 +2,7 @@
 
 set -e
 cd "$(dirname "$0")/.."
+sbf_out_dir=$(pwd)/target/deploy
 
 source ./ci/rust-version.sh stable
 source ./ci/solana-version.sh
@@ -48,6 +49,17 @@ for program in $run_dir/program{,-*}; do
   fi
 done
 
+# Test the crates that load the programs built above from SBF_OUT_DIR
+for crate in cpi-test-program events conformance; do
+  if [[ -r $run_dir/$crate/Cargo.toml ]]; then
+    (
+      set -x
+      cd $run_dir/$crate
+      cargo +"$rust_stable" test-sbf --sbf-out-dir "$sbf_out_dir" -- --nocapture
+    )
+  fi
+done
+
 if [[ -n $run_all ]]; then
   # Build/test all directories
   set -x